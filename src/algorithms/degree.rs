@@ -0,0 +1,116 @@
+use bigraph::interface::static_bigraph::StaticEdgeCentricBigraph;
+
+/// Degrees of the nodes of an edge-centric genome graph that respect bidirectedness.
+///
+/// The right side of a node is the node itself, and the left side is its mirror node.
+/// An edge and its mirror spell the same sequence, so if both leave the same side, they are counted only once.
+/// This happens for the two parallel edges of a palindromic unitig.
+pub trait BidirectedDegree: StaticEdgeCentricBigraph {
+    /// Returns the number of distinct extensions on the right side of the node, i.e. its out-degree.
+    fn right_degree(&self, node: Self::NodeIndex) -> usize {
+        let out_edges: Vec<_> = self
+            .out_neighbors(node)
+            .map(|neighbor| neighbor.edge_id)
+            .collect();
+        out_edges
+            .iter()
+            .enumerate()
+            .filter(|&(index, &edge)| {
+                !self
+                    .mirror_edge_edge_centric(edge)
+                    .is_some_and(|mirror_edge| out_edges[..index].contains(&mirror_edge))
+            })
+            .count()
+    }
+
+    /// Returns the number of distinct extensions on the left side of the node, i.e. the out-degree of its mirror node.
+    ///
+    /// For a self-mirror node, both sides are the same, so this equals the right degree.
+    fn left_degree(&self, node: Self::NodeIndex) -> usize {
+        let mirror_node = self.mirror_node(node).unwrap_or(node);
+        self.right_degree(mirror_node)
+    }
+
+    /// Returns the number of distinct extensions on both sides of the node.
+    ///
+    /// A self-mirror node has only a single side, which is not counted twice.
+    fn degree(&self, node: Self::NodeIndex) -> usize {
+        if self.is_self_mirror_node(node) {
+            self.right_degree(node)
+        } else {
+            self.left_degree(node) + self.right_degree(node)
+        }
+    }
+}
+
+impl<Graph: StaticEdgeCentricBigraph> BidirectedDegree for Graph {}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::degree::BidirectedDegree;
+    use crate::io::bcalm2::read_bigraph_from_bcalm2_as_edge_centric;
+    use crate::types::PetBCalm2EdgeGraph;
+    use bigraph::interface::static_bigraph::StaticBigraph;
+    use bigraph::traitgraph::interface::ImmutableGraphContainer;
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use std::io::BufReader;
+
+    #[test]
+    fn test_degree_palindromic_unitig() {
+        let test_file: &'static [u8] = b">0 LN:i:4\nACGT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        // The unitig and its reverse complement are parallel edges.
+        assert_eq!(graph.edge_count(), 2);
+        let node = graph
+            .node_indices()
+            .find(|&node| graph.out_neighbors(node).count() > 0)
+            .unwrap();
+        assert_eq!(graph.out_neighbors(node).count(), 2);
+        assert_eq!(graph.right_degree(node), 1);
+        assert_eq!(graph.left_degree(node), 0);
+        assert_eq!(graph.degree(node), 1);
+    }
+
+    #[test]
+    fn test_degree_self_mirror_node() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:0:- L:+:1:- L:+:2:+\n\
+            CAT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:1:- L:+:2:+\n\
+            GGTCTCGGGTAAAT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:0:- L:-:1:- L:-:2:+\n\
+            ATGATT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let node = graph
+            .node_indices()
+            .find(|&node| graph.is_self_mirror_node(node))
+            .unwrap();
+        assert_eq!(graph.right_degree(node), 3);
+        assert_eq!(graph.left_degree(node), 3);
+        assert_eq!(graph.degree(node), 3);
+
+        for node in graph.node_indices() {
+            if !graph.is_self_mirror_node(node) {
+                assert_eq!(
+                    graph.degree(node),
+                    graph.out_neighbors(node).count() + graph.in_neighbors(node).count()
+                );
+            }
+        }
+    }
+}
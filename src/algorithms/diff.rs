@@ -0,0 +1,407 @@
+use crate::algorithms::unitigs::maximal_unitigs;
+use crate::io::bcalm2::{oriented_sequence_vec, PlainBCalm2NodeData};
+use crate::io::SequenceData;
+use bigraph::interface::static_bigraph::StaticNodeCentricBigraph;
+use bigraph::traitgraph::index::GraphIndex;
+use bigraph::traitgraph::interface::Edge;
+use compact_genome::implementation::bit_vec_sequence::BitVectorGenome;
+use compact_genome::interface::alphabet::Alphabet;
+use compact_genome::interface::sequence::{GenomeSequence, OwnedGenomeSequence};
+use compact_genome::interface::sequence_store::SequenceStore;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A unitig whose mean abundance differs between the two graphs compared by [`diff_graphs`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbundanceDifference {
+    /// The canonical sequence of the unitig.
+    pub sequence: Vec<u8>,
+    /// The mean abundance of the unitig in the first graph.
+    pub first_abundance: Option<f64>,
+    /// The mean abundance of the unitig in the second graph.
+    pub second_abundance: Option<f64>,
+}
+
+/// The differences between two node-centric bcalm2 graphs, as computed by [`diff_graphs`].
+///
+/// Unitigs are identified by their canonical sequence, i.e. the lexicographically smaller of their sequence and its reverse complement.
+/// Edges are identified by the sequences of their endpoints, canonicalized over the edge and its mirror.
+/// All lists are sorted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphDiff {
+    /// The canonical sequences of the unitigs that exist only in the first graph.
+    pub unitigs_only_in_first: Vec<Vec<u8>>,
+    /// The canonical sequences of the unitigs that exist only in the second graph.
+    pub unitigs_only_in_second: Vec<Vec<u8>>,
+    /// The edges that exist only in the first graph, as pairs of the oriented sequences of their endpoints.
+    pub edges_only_in_first: Vec<(Vec<u8>, Vec<u8>)>,
+    /// The edges that exist only in the second graph, as pairs of the oriented sequences of their endpoints.
+    pub edges_only_in_second: Vec<(Vec<u8>, Vec<u8>)>,
+    /// The unitigs that exist in both graphs, but with different mean abundances.
+    pub abundance_differences: Vec<AbundanceDifference>,
+}
+
+impl GraphDiff {
+    /// Returns true if the compared graphs have no differences.
+    pub fn is_empty(&self) -> bool {
+        self.unitigs_only_in_first.is_empty()
+            && self.unitigs_only_in_second.is_empty()
+            && self.edges_only_in_first.is_empty()
+            && self.edges_only_in_second.is_empty()
+            && self.abundance_differences.is_empty()
+    }
+}
+
+/// The canonical unitigs with their mean abundances and the canonical edges of a graph.
+type CanonicalGraph = (BTreeMap<Vec<u8>, Option<f64>>, BTreeSet<(Vec<u8>, Vec<u8>)>);
+
+fn canonical_graph<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    Graph: StaticNodeCentricBigraph<NodeData = NodeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+) -> CanonicalGraph
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    let sequences: Vec<_> = graph
+        .node_indices()
+        .map(|node| {
+            oriented_sequence_vec(
+                &PlainBCalm2NodeData::from(graph.node_data(node)),
+                source_sequence_store,
+            )
+        })
+        .collect();
+    let sequence_of = |node: Graph::NodeIndex| &sequences[node.as_usize()];
+    // Nodes without mirror are their own reverse complement.
+    let mirror_sequence_of =
+        |node: Graph::NodeIndex| sequence_of(graph.mirror_node(node).unwrap_or(node));
+
+    let mut unitigs = BTreeMap::new();
+    for node in graph.node_indices() {
+        let canonical_sequence = sequence_of(node).min(mirror_sequence_of(node)).clone();
+        let mean_abundance = PlainBCalm2NodeData::from(graph.node_data(node)).mean_abundance;
+        unitigs.insert(canonical_sequence, mean_abundance);
+    }
+
+    let mut edges = BTreeSet::new();
+    for edge in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+        let forward = (sequence_of(from_node).clone(), sequence_of(to_node).clone());
+        let mirror = (
+            mirror_sequence_of(to_node).clone(),
+            mirror_sequence_of(from_node).clone(),
+        );
+        edges.insert(forward.min(mirror));
+    }
+
+    (unitigs, edges)
+}
+
+/// Compute the differences between two node-centric bcalm2 graphs.
+///
+/// The graphs are compared by sequence, so the ids of the nodes and their strands do not matter.
+/// Mean abundances are compared exactly.
+pub fn diff_graphs<
+    AlphabetType: Alphabet,
+    FirstSequenceStore: SequenceStore<AlphabetType>,
+    SecondSequenceStore: SequenceStore<AlphabetType>,
+    FirstNodeData,
+    SecondNodeData,
+    FirstGraph: StaticNodeCentricBigraph<NodeData = FirstNodeData>,
+    SecondGraph: StaticNodeCentricBigraph<NodeData = SecondNodeData>,
+>(
+    first_graph: &FirstGraph,
+    first_sequence_store: &FirstSequenceStore,
+    second_graph: &SecondGraph,
+    second_sequence_store: &SecondSequenceStore,
+) -> GraphDiff
+where
+    PlainBCalm2NodeData<FirstSequenceStore::Handle>: for<'a> From<&'a FirstNodeData>,
+    PlainBCalm2NodeData<SecondSequenceStore::Handle>: for<'a> From<&'a SecondNodeData>,
+{
+    let (first_unitigs, first_edges) = canonical_graph(first_graph, first_sequence_store);
+    let (second_unitigs, second_edges) = canonical_graph(second_graph, second_sequence_store);
+
+    let mut diff = GraphDiff::default();
+    for (sequence, &first_abundance) in &first_unitigs {
+        match second_unitigs.get(sequence) {
+            None => diff.unitigs_only_in_first.push(sequence.clone()),
+            Some(&second_abundance) if second_abundance != first_abundance => {
+                diff.abundance_differences.push(AbundanceDifference {
+                    sequence: sequence.clone(),
+                    first_abundance,
+                    second_abundance,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    diff.unitigs_only_in_second = second_unitigs
+        .keys()
+        .filter(|sequence| !first_unitigs.contains_key(*sequence))
+        .cloned()
+        .collect();
+    diff.edges_only_in_first = first_edges.difference(&second_edges).cloned().collect();
+    diff.edges_only_in_second = second_edges.difference(&first_edges).cloned().collect();
+
+    diff
+}
+
+/// The canonical sequences of the unitigs with a flag if they are cyclic,
+/// and the canonical edges between unitigs, each with their multiplicity.
+pub(crate) type CanonicalUnitigGraph = (
+    BTreeMap<(Vec<u8>, bool), usize>,
+    BTreeMap<(Vec<u8>, Vec<u8>), usize>,
+);
+
+pub(crate) fn canonical_unitig_graph<
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: SequenceData<AlphabetType, GenomeSequenceStore>,
+    Graph: StaticNodeCentricBigraph<NodeData = NodeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    kmer_size: usize,
+) -> crate::error::Result<CanonicalUnitigGraph> {
+    let overlap = kmer_size
+        .checked_sub(1)
+        .ok_or(crate::error::Error::ZeroKmerSize)?;
+    let sequences: Vec<BitVectorGenome<AlphabetType>> = graph
+        .node_indices()
+        .map(|node| graph.node_data(node).sequence_owned(source_sequence_store))
+        .collect();
+    let spell = |walk: &[Graph::NodeIndex]| -> BitVectorGenome<AlphabetType> {
+        let mut sequence = Vec::new();
+        for (i, node) in walk.iter().enumerate() {
+            let overlap = if i == 0 { 0 } else { overlap };
+            sequence.extend(sequences[node.as_usize()].iter().skip(overlap).cloned());
+        }
+        sequence.into_iter().collect()
+    };
+    let canonical = |sequence: &BitVectorGenome<AlphabetType>| {
+        sequence
+            .clone_as_vec()
+            .min(sequence.clone_as_reverse_complement().clone_as_vec())
+    };
+
+    let mut unitigs = BTreeMap::new();
+    // The spelled sequence of each unitig and its reverse complement.
+    let mut unitig_sequences = Vec::new();
+    // The unitig of each node and if the node is in the same orientation as the unitig.
+    let mut node_unitigs = vec![(0, true); graph.node_count()];
+    for unitig in maximal_unitigs(graph) {
+        let first = unitig[0];
+        let last = *unitig.last().unwrap();
+        let mut out_neighbors = graph.out_neighbors(last);
+        let is_cycle = matches!(
+            (out_neighbors.next(), out_neighbors.next()),
+            (Some(neighbor), None) if neighbor.node_id == first
+        ) && graph.in_neighbors(first).count() == 1;
+
+        let sequence = spell(&unitig);
+        // Cycles can be spelled starting from any of their nodes.
+        let canonical_sequence = if is_cycle {
+            (0..unitig.len())
+                .map(|rotation| {
+                    let mut rotated = unitig.clone();
+                    rotated.rotate_left(rotation);
+                    canonical(&spell(&rotated))
+                })
+                .min()
+                .unwrap()
+        } else {
+            canonical(&sequence)
+        };
+        *unitigs.entry((canonical_sequence, is_cycle)).or_insert(0) += 1;
+
+        for &node in &unitig {
+            node_unitigs[node.as_usize()] = (unitig_sequences.len(), true);
+            if let Some(mirror_node) = graph.mirror_node(node) {
+                // If the mirror node is in the same unitig, then its sequence is a palindrome.
+                if mirror_node != node {
+                    node_unitigs[mirror_node.as_usize()] = (unitig_sequences.len(), false);
+                }
+            }
+        }
+        unitig_sequences.push((
+            sequence.clone_as_vec(),
+            sequence.clone_as_reverse_complement().clone_as_vec(),
+        ));
+    }
+
+    // Returns the sequence of the oriented unitig containing the node, or its reverse complement.
+    let unitig_sequence = |node: Graph::NodeIndex, forwards: bool| {
+        let (unitig, node_forwards) = node_unitigs[node.as_usize()];
+        let (sequence, reverse_complement) = &unitig_sequences[unitig];
+        if node_forwards == forwards {
+            sequence.clone()
+        } else {
+            reverse_complement.clone()
+        }
+    };
+
+    let mut edges = BTreeMap::new();
+    for edge in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+        // Edges within unitigs are represented by the unitig sequences.
+        if graph.out_neighbors(from_node).count() == 1 && graph.in_neighbors(to_node).count() == 1 {
+            continue;
+        }
+
+        let forward = (
+            unitig_sequence(from_node, true),
+            unitig_sequence(to_node, true),
+        );
+        let mirror = (
+            unitig_sequence(to_node, false),
+            unitig_sequence(from_node, false),
+        );
+        *edges.entry(forward.min(mirror)).or_insert(0) += 1;
+    }
+
+    Ok((unitigs, edges))
+}
+
+/// Check if two node-centric genome graphs are isomorphic as bidirected graphs with sequences.
+///
+/// Both graphs are canonicalized by spelling their maximal unitigs, where consecutive nodes overlap by `kmer_size - 1` characters.
+/// The graphs are isomorphic if they have the same canonical unitig sequences and the same edges between unitigs,
+/// where edges are labelled by the oriented sequences of the unitigs they connect, both counted with multiplicity.
+/// Hence, the ids and strands of the nodes do not matter, and neither does how unitigs are split into nodes.
+/// If different unitigs spell the same sequence, this is only a necessary condition for isomorphism.
+/// Returns an error if `kmer_size` is zero.
+pub fn bidirected_isomorphic<
+    AlphabetType: Alphabet + 'static,
+    FirstSequenceStore: SequenceStore<AlphabetType>,
+    SecondSequenceStore: SequenceStore<AlphabetType>,
+    FirstNodeData: SequenceData<AlphabetType, FirstSequenceStore>,
+    SecondNodeData: SequenceData<AlphabetType, SecondSequenceStore>,
+    FirstGraph: StaticNodeCentricBigraph<NodeData = FirstNodeData>,
+    SecondGraph: StaticNodeCentricBigraph<NodeData = SecondNodeData>,
+>(
+    first_graph: &FirstGraph,
+    first_sequence_store: &FirstSequenceStore,
+    second_graph: &SecondGraph,
+    second_sequence_store: &SecondSequenceStore,
+    kmer_size: usize,
+) -> crate::error::Result<bool> {
+    Ok(
+        canonical_unitig_graph(first_graph, first_sequence_store, kmer_size)?
+            == canonical_unitig_graph(second_graph, second_sequence_store, kmer_size)?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::diff::{bidirected_isomorphic, diff_graphs, AbundanceDifference};
+    use crate::io::bcalm2::read_bigraph_from_bcalm2_as_node_centric;
+    use crate::types::PetBCalm2NodeGraph;
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use std::io::BufReader;
+
+    #[test]
+    fn test_diff_graphs() {
+        let first_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let second_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0\n\
+            AGA\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:5.0 L:-:1:-\n\
+            ATGATG\n";
+        let mut first_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let first: PetBCalm2NodeGraph<_> =
+            read_bigraph_from_bcalm2_as_node_centric(BufReader::new(first_file), &mut first_store)
+                .unwrap();
+        let mut second_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let second: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(second_file),
+            &mut second_store,
+        )
+        .unwrap();
+
+        assert!(diff_graphs(&first, &first_store, &first, &first_store).is_empty());
+
+        let diff = diff_graphs(&first, &first_store, &second, &second_store);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.unitigs_only_in_first, vec![b"ACT".to_vec()]);
+        assert_eq!(diff.unitigs_only_in_second, vec![b"AGA".to_vec()]);
+        assert_eq!(
+            diff.edges_only_in_first,
+            vec![(b"AGT".to_vec(), b"ACTTACCCGAGACC".to_vec())]
+        );
+        assert!(diff.edges_only_in_second.is_empty());
+        assert_eq!(
+            diff.abundance_differences,
+            vec![AbundanceDifference {
+                sequence: b"ATGATG".to_vec(),
+                first_abundance: Some(2.2),
+                second_abundance: Some(5.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_bidirected_isomorphic() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:3 L:-:0:- L:+:2:+ L:+:3:+\n\
+            ACG\n\
+            >2 LN:i:3 L:-:1:-\n\
+            CGT\n\
+            >3 LN:i:3 L:-:1:-\n\
+            CGA\n";
+        // The same graph with reversed record order and the last record stored as reverse complement.
+        let reordered_file: &'static [u8] = b">0 LN:i:3 L:+:2:-\n\
+            TCG\n\
+            >1 LN:i:3 L:-:2:-\n\
+            CGT\n\
+            >2 LN:i:3 L:-:3:- L:+:1:+ L:+:0:-\n\
+            ACG\n\
+            >3 LN:i:3 L:+:2:+\n\
+            AAC\n";
+        let modified_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:3 L:-:0:- L:+:2:+ L:+:3:+\n\
+            ACG\n\
+            >2 LN:i:3 L:-:1:-\n\
+            CGT\n\
+            >3 LN:i:3 L:-:1:-\n\
+            CGC\n";
+        let read = |input: &'static [u8]| {
+            let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+            let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+                BufReader::new(input),
+                &mut sequence_store,
+            )
+            .unwrap();
+            (graph, sequence_store)
+        };
+        let (graph, store) = read(test_file);
+        let (reordered_graph, reordered_store) = read(reordered_file);
+        let (modified_graph, modified_store) = read(modified_file);
+
+        assert!(bidirected_isomorphic(&graph, &store, &graph, &store, 3).unwrap());
+        assert!(
+            bidirected_isomorphic(&graph, &store, &reordered_graph, &reordered_store, 3).unwrap()
+        );
+        assert!(
+            !bidirected_isomorphic(&graph, &store, &modified_graph, &modified_store, 3).unwrap()
+        );
+        assert!(matches!(
+            bidirected_isomorphic(&graph, &store, &graph, &store, 0),
+            Err(crate::error::Error::ZeroKmerSize)
+        ));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::io::SequenceData;
+use bigraph::traitgraph::interface::ImmutableGraphContainer;
+use compact_genome::implementation::bit_vec_sequence::BitVectorGenome;
+use compact_genome::interface::alphabet::Alphabet;
+use compact_genome::interface::sequence::GenomeSequence;
+use compact_genome::interface::sequence_store::SequenceStore;
+use std::collections::HashMap;
+
+/// An index mapping the (w, k)-minimizers of the node sequences of a genome graph to their occurrences.
+///
+/// A (w, k)-minimizer is the lexicographically smallest k-mer in a window of `w` consecutive k-mers,
+/// where ties are broken by choosing the leftmost k-mer.
+/// The minimizers are computed over the sequence of each node in its orientation,
+/// so the minimizers of a node and its mirror are indexed separately.
+#[derive(Debug, Clone)]
+pub struct MinimizerIndex<NodeIndex> {
+    kmer_size: usize,
+    window_size: usize,
+    occurrences: HashMap<Vec<u8>, Vec<(NodeIndex, usize)>>,
+}
+
+impl<NodeIndex: Copy> MinimizerIndex<NodeIndex> {
+    /// Build the index of the (w, k)-minimizers of the node sequences of the given graph.
+    ///
+    /// Sequences with fewer than `window_size` k-mers have no minimizers.
+    pub fn build<
+        AlphabetType: Alphabet + 'static,
+        GenomeSequenceStore: SequenceStore<AlphabetType>,
+        NodeData: SequenceData<AlphabetType, GenomeSequenceStore>,
+        Graph: ImmutableGraphContainer<NodeData = NodeData, NodeIndex = NodeIndex>,
+    >(
+        graph: &Graph,
+        source_sequence_store: &GenomeSequenceStore,
+        kmer_size: usize,
+        window_size: usize,
+    ) -> Self {
+        assert!(kmer_size > 0, "the k-mer size must be positive");
+        assert!(window_size > 0, "the window size must be positive");
+        let mut occurrences: HashMap<_, Vec<_>> = HashMap::new();
+
+        for node in graph.node_indices() {
+            let sequence: BitVectorGenome<AlphabetType> =
+                graph.node_data(node).sequence_owned(source_sequence_store);
+            let sequence = sequence.clone_as_vec();
+            if sequence.len() < kmer_size {
+                continue;
+            }
+
+            let kmers: Vec<_> = sequence.windows(kmer_size).collect();
+            let mut last_offset = None;
+            for (window_offset, window) in kmers.windows(window_size).enumerate() {
+                let (offset, minimizer) = window
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, kmer)| **kmer)
+                    .map(|(offset, kmer)| (window_offset + offset, *kmer))
+                    .unwrap();
+
+                // Consecutive windows often share their minimizer.
+                if last_offset != Some(offset) {
+                    occurrences
+                        .entry(minimizer.to_vec())
+                        .or_default()
+                        .push((node, offset));
+                    last_offset = Some(offset);
+                }
+            }
+        }
+
+        Self {
+            kmer_size,
+            window_size,
+            occurrences,
+        }
+    }
+
+    /// Returns the nodes and offsets within their sequences at which the given minimizer occurs.
+    ///
+    /// The minimizer is given as ASCII string.
+    /// If it does not occur as minimizer, the result is empty.
+    pub fn query(&self, minimizer: &[u8]) -> &[(NodeIndex, usize)] {
+        self.occurrences
+            .get(minimizer)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Returns the k-mer size of the minimizers.
+    pub fn kmer_size(&self) -> usize {
+        self.kmer_size
+    }
+
+    /// Returns the number of k-mers in each window.
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::minimizers::MinimizerIndex;
+    use crate::io::bcalm2::read_bigraph_from_bcalm2_as_node_centric;
+    use crate::types::PetBCalm2NodeGraph;
+    use bigraph::traitgraph::interface::ImmutableGraphContainer;
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use std::io::BufReader;
+
+    #[test]
+    fn test_minimizer_index() {
+        let test_file: &'static [u8] = b">0 LN:i:5\n\
+            GGTCA\n\
+            >1 LN:i:5\n\
+            CCCAA\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        let index = MinimizerIndex::build(&graph, &sequence_store, 3, 2);
+        let nodes: Vec<_> = graph.node_indices().collect();
+        // Record i is node i, and its mirror is node 2 + i.
+        assert_eq!(index.query(b"GGT"), &[(nodes[0], 0)]);
+        assert_eq!(index.query(b"GTC"), &[(nodes[0], 1)]);
+        assert_eq!(index.query(b"CCA"), &[(nodes[1], 1)]);
+        // The mirror of node 0 spells TGACC.
+        assert_eq!(index.query(b"GAC"), &[(nodes[2], 1)]);
+        assert!(index.query(b"TCA").is_empty());
+        assert!(index.query(b"AAA").is_empty());
+    }
+}
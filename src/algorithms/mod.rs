@@ -0,0 +1,16 @@
+/// Degrees of the nodes of bidirected genome graphs.
+pub mod degree;
+/// Comparison of genome graphs by sequence.
+pub mod diff;
+/// An index of the minimizers of the sequences of a genome graph.
+pub mod minimizers;
+/// Statistics of genome graphs, such as degree histograms.
+pub mod statistics;
+/// Transformations of genome graphs, such as reversing all edges.
+pub mod transform;
+/// Traversals of genome graphs, such as topological orderings.
+pub mod traversal;
+/// Functions to compute unitigs of genome graphs.
+pub mod unitigs;
+/// Functions to check if a genome graph fulfils the properties of a de Bruijn graph.
+pub mod validation;
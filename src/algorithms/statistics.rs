@@ -0,0 +1,497 @@
+use crate::io::bcalm2::PlainBCalm2NodeData;
+use bigraph::interface::static_bigraph::{
+    StaticBigraph, StaticEdgeCentricBigraph, StaticNodeCentricBigraph,
+};
+use bigraph::traitgraph::index::GraphIndex;
+use bigraph::traitgraph::interface::StaticGraph;
+use bigraph::traitgraph::traitsequence::interface::Sequence;
+use compact_genome::interface::alphabet::Alphabet;
+use compact_genome::interface::sequence_store::SequenceStore;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Compute the degree histogram of a node-centric genome graph.
+///
+/// The histogram maps the degree of a node (in-degree plus out-degree) to the number of nodes with that degree.
+/// Only one node of each mirror pair is counted, since both nodes of a pair have the same degree.
+pub fn degree_histogram<Graph: StaticNodeCentricBigraph>(graph: &Graph) -> BTreeMap<usize, usize> {
+    representative_degree_histogram(graph)
+}
+
+/// Compute the junction degree histogram of an edge-centric genome graph.
+///
+/// The histogram maps the degree of a junction node (in-degree plus out-degree) to the number of junctions with that degree.
+/// Only one node of each mirror pair is counted, since both nodes of a pair have the same degree.
+pub fn junction_degree_histogram<Graph: StaticEdgeCentricBigraph>(
+    graph: &Graph,
+) -> BTreeMap<usize, usize> {
+    representative_degree_histogram(graph)
+}
+
+/// Returns one node of each mirror pair, namely the one with the lower index.
+/// Self-mirrors and nodes without mirror are their own representatives.
+fn representative_nodes<Graph: StaticBigraph>(
+    graph: &Graph,
+) -> impl Iterator<Item = Graph::NodeIndex> + '_ {
+    graph.node_indices().filter(move |&node| {
+        !graph
+            .mirror_node(node)
+            .is_some_and(|mirror_node| mirror_node.as_usize() < node.as_usize())
+    })
+}
+
+fn representative_degree_histogram<Graph: StaticBigraph>(graph: &Graph) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+
+    for node in representative_nodes(graph) {
+        let degree = graph.out_neighbors(node).count() + graph.in_neighbors(node).count();
+        *histogram.entry(degree).or_insert(0) += 1;
+    }
+
+    histogram
+}
+
+/// Estimate the sequencing coverage of a node-centric bcalm2 graph from the k-mer abundances of its nodes.
+///
+/// The estimate is the sum of the total abundances divided by the sum of the k-mer counts of the nodes.
+/// Only one node of each mirror pair is counted, and nodes without total abundance are skipped.
+/// Returns `None` if no node has a total abundance.
+pub fn estimated_coverage<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    Graph: StaticNodeCentricBigraph<NodeData = NodeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    kmer_size: usize,
+) -> Option<f64>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    let mut total_abundance = 0;
+    let mut kmer_count = 0;
+
+    for node in representative_nodes(graph) {
+        let node_data = PlainBCalm2NodeData::from(graph.node_data(node));
+        if let Some(node_abundance) = node_data.total_abundance {
+            let length = source_sequence_store.get(&node_data.sequence_handle).len();
+            total_abundance += node_abundance;
+            kmer_count += (length + 1).saturating_sub(kmer_size);
+        }
+    }
+
+    if kmer_count == 0 {
+        None
+    } else {
+        Some(total_abundance as f64 / kmer_count as f64)
+    }
+}
+
+/// Compute the GC content of a node-centric bcalm2 graph.
+///
+/// The GC content of each node is weighted by its length.
+/// Only one node of each mirror pair is counted.
+/// Returns `None` if the alphabet is not a nucleotide alphabet, or if the graph has no sequence characters.
+pub fn overall_gc_content<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    Graph: StaticNodeCentricBigraph<NodeData = NodeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+) -> Option<f64>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    let mut gc_count = 0;
+    let mut length = 0;
+
+    for node in representative_nodes(graph) {
+        let (node_gc_count, node_length) =
+            PlainBCalm2NodeData::from(graph.node_data(node)).gc_count(source_sequence_store)?;
+        gc_count += node_gc_count;
+        length += node_length;
+    }
+
+    if length == 0 {
+        None
+    } else {
+        Some(gc_count as f64 / length as f64)
+    }
+}
+
+/// Count the edges from `from_node` to `to_node`.
+pub fn edge_multiplicity<Graph: StaticGraph>(
+    graph: &Graph,
+    from_node: Graph::NodeIndex,
+    to_node: Graph::NodeIndex,
+) -> usize {
+    graph
+        .out_neighbors(from_node)
+        .filter(|neighbor| neighbor.node_id == to_node)
+        .count()
+}
+
+/// Count the edges from `from_node` to `to_node` together with their mirror edges,
+/// i.e. the edges from the mirror of `to_node` to the mirror of `from_node`.
+///
+/// If `to_node` is the mirror of `from_node`, then the edges are their own mirrors and are counted only once.
+pub fn edge_multiplicity_bidirected<Graph: StaticBigraph>(
+    graph: &Graph,
+    from_node: Graph::NodeIndex,
+    to_node: Graph::NodeIndex,
+) -> usize {
+    let multiplicity = edge_multiplicity(graph, from_node, to_node);
+    match (graph.mirror_node(to_node), graph.mirror_node(from_node)) {
+        (Some(mirror_to_node), _) if mirror_to_node == from_node => multiplicity,
+        (Some(mirror_to_node), Some(mirror_from_node)) => {
+            multiplicity + edge_multiplicity(graph, mirror_to_node, mirror_from_node)
+        }
+        _ => multiplicity,
+    }
+}
+
+/// Compute the histogram of the mean k-mer abundances of the nodes of a node-centric bcalm2 graph.
+///
+/// The histogram maps each mean abundance, rounded to the nearest integer, to the number of nodes with that abundance.
+/// Only one node of each mirror pair is counted, and nodes without mean abundance are skipped.
+pub fn abundance_histogram<
+    GenomeSequenceStoreHandle,
+    Graph: StaticNodeCentricBigraph<NodeData = PlainBCalm2NodeData<GenomeSequenceStoreHandle>>,
+>(
+    graph: &Graph,
+) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+
+    for node in representative_nodes(graph) {
+        if let Some(mean_abundance) = graph.node_data(node).mean_abundance {
+            *histogram
+                .entry(mean_abundance.round() as usize)
+                .or_insert(0) += 1;
+        }
+    }
+
+    histogram
+}
+
+/// Compute the k-mer spectrum of a node-centric bcalm2 graph, e.g. for estimating the genome size.
+///
+/// The spectrum maps each abundance to the number of distinct k-mers with that abundance.
+/// Each node contributes the `length - k + 1` k-mers of its sequence to the bucket of its mean abundance, rounded to the nearest integer.
+/// Only one node of each mirror pair is counted, and nodes without mean abundance are skipped.
+pub fn kmer_spectrum<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    Graph: StaticNodeCentricBigraph<NodeData = NodeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    kmer_size: usize,
+) -> BTreeMap<usize, usize>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    let mut spectrum = BTreeMap::new();
+
+    for node in representative_nodes(graph) {
+        let node_data = PlainBCalm2NodeData::from(graph.node_data(node));
+        if let Some(mean_abundance) = node_data.mean_abundance {
+            let length = source_sequence_store.get(&node_data.sequence_handle).len();
+            if length >= kmer_size {
+                *spectrum.entry(mean_abundance.round() as usize).or_insert(0) +=
+                    length - kmer_size + 1;
+            }
+        }
+    }
+
+    spectrum
+}
+
+/// Count the distinct colors of the nodes of a node-centric bcalm2 graph, i.e. the number of samples represented in the graph.
+///
+/// Nodes without colors are skipped.
+pub fn color_count<
+    GenomeSequenceStoreHandle,
+    Graph: StaticNodeCentricBigraph<NodeData = PlainBCalm2NodeData<GenomeSequenceStoreHandle>>,
+>(
+    graph: &Graph,
+) -> usize {
+    graph
+        .node_indices()
+        .filter_map(|node| graph.node_data(node).colors.as_ref())
+        .flatten()
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+/// Summarize a node-centric bcalm2 graph in a few human-readable lines.
+///
+/// The summary contains the node and edge counts, the number of self-mirror nodes,
+/// the total sequence length and the minimum and maximum degree.
+/// The sequence length and the degrees are computed over one node of each mirror pair.
+pub fn summarize<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    Graph: StaticNodeCentricBigraph<NodeData = NodeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+) -> String
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    let self_mirror_count = graph
+        .node_indices()
+        .filter(|&node| graph.is_self_mirror_node(node))
+        .count();
+    let total_length: usize = representative_nodes(graph)
+        .map(|node| {
+            let node_data = PlainBCalm2NodeData::from(graph.node_data(node));
+            source_sequence_store.get(&node_data.sequence_handle).len()
+        })
+        .sum();
+    let degrees = degree_histogram(graph);
+
+    let mut summary = String::new();
+    // Writing to a string never fails.
+    writeln!(summary, "nodes: {}", graph.node_count()).unwrap();
+    writeln!(summary, "edges: {}", graph.edge_count()).unwrap();
+    writeln!(summary, "self-mirror nodes: {self_mirror_count}").unwrap();
+    writeln!(summary, "total sequence length: {total_length}").unwrap();
+    if let (Some(min_degree), Some(max_degree)) =
+        (degrees.keys().next(), degrees.keys().next_back())
+    {
+        writeln!(summary, "min degree: {min_degree}").unwrap();
+        writeln!(summary, "max degree: {max_degree}").unwrap();
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::statistics::{
+        abundance_histogram, color_count, degree_histogram, edge_multiplicity,
+        edge_multiplicity_bidirected, estimated_coverage, junction_degree_histogram, kmer_spectrum,
+        overall_gc_content, summarize,
+    };
+    use crate::io::bcalm2::{
+        read_bigraph_from_bcalm2_as_edge_centric, read_bigraph_from_bcalm2_as_node_centric,
+    };
+    use crate::types::{PetBCalm2EdgeGraph, PetBCalm2NodeGraph};
+    use bigraph::interface::static_bigraph::StaticBigraph;
+    use bigraph::traitgraph::interface::{Edge, ImmutableGraphContainer};
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use compact_genome::interface::sequence_store::SequenceStore;
+    use std::collections::BTreeMap;
+    use std::io::BufReader;
+
+    /// Three unitigs, where the middle one links to both others.
+    const TEST_FILE: &[u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+        AGT\n\
+        >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+        GGTCTCGGGTAAGT\n\
+        >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+        ATGATG\n";
+
+    type TestGraph = PetBCalm2NodeGraph<
+        <DefaultSequenceStore<DnaAlphabet> as SequenceStore<DnaAlphabet>>::Handle,
+    >;
+
+    fn read_test_graph(test_file: &[u8]) -> (TestGraph, DefaultSequenceStore<DnaAlphabet>) {
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        (graph, sequence_store)
+    }
+
+    #[test]
+    fn test_degree_histogram() {
+        let (graph, _) = read_test_graph(TEST_FILE);
+
+        assert_eq!(degree_histogram(&graph), BTreeMap::from([(1, 2), (2, 1)]));
+    }
+
+    #[test]
+    fn test_junction_degree_histogram() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        // The junction GT/AC joins all three unitigs, all other junctions are dead ends.
+        assert_eq!(
+            junction_degree_histogram(&graph),
+            BTreeMap::from([(1, 3), (3, 1)])
+        );
+    }
+
+    #[test]
+    fn test_estimated_coverage() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let (graph, sequence_store) = read_test_graph(test_file);
+
+        // Node 2 has no total abundance, so only nodes 0 and 1 with 1 + 12 k-mers are counted.
+        assert_eq!(
+            estimated_coverage(&graph, &sequence_store, 3),
+            Some(6.0 / 13.0)
+        );
+    }
+
+    #[test]
+    fn test_estimated_coverage_without_abundances() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:3 L:+:0:-\n\
+            ACT\n";
+        let (graph, sequence_store) = read_test_graph(test_file);
+
+        assert_eq!(estimated_coverage(&graph, &sequence_store, 3), None);
+    }
+
+    #[test]
+    fn test_edge_multiplicity() {
+        let test_file: &'static [u8] = b"\
+            >0 LN:i:7 KC:i:4 km:f:3.0 L:+:2:+ L:-:2:-\n\
+            AGTTCTC\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:2:+ L:-:2:-\n\
+            AGTCTCGGGTAATC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:+:0:+ L:+:1:+ L:-:0:- L:-:1:-\n\
+            TCGAAG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        // Unitigs 0 and 1 both lead from AG to TC, and unitig 2 leads back.
+        let unitig_endpoints = |id| {
+            let edge = graph
+                .edge_indices()
+                .find(|&edge| graph.edge_data(edge).id == id && graph.edge_data(edge).forwards)
+                .unwrap();
+            graph.edge_endpoints(edge)
+        };
+        let Edge {
+            from_node: ag,
+            to_node: tc,
+        } = unitig_endpoints(0);
+        assert_eq!(unitig_endpoints(1).from_node, ag);
+        assert_eq!(unitig_endpoints(1).to_node, tc);
+
+        assert_eq!(edge_multiplicity(&graph, ag, tc), 2);
+        assert_eq!(edge_multiplicity(&graph, tc, ag), 1);
+        assert_eq!(edge_multiplicity(&graph, ag, ag), 0);
+        assert_eq!(edge_multiplicity_bidirected(&graph, ag, tc), 4);
+        assert_eq!(edge_multiplicity_bidirected(&graph, tc, ag), 2);
+        let ct = graph.mirror_node(ag).unwrap();
+        let ga = graph.mirror_node(tc).unwrap();
+        assert_eq!(edge_multiplicity(&graph, ga, ct), 2);
+        assert_eq!(edge_multiplicity_bidirected(&graph, ag, ct), 0);
+    }
+
+    #[test]
+    fn test_overall_gc_content() {
+        let test_file: &'static [u8] = b">0 LN:i:4 L:+:1:-\n\
+            AGTC\n\
+            >1 LN:i:6 L:+:0:-\n\
+            GGCCAT\n";
+        let (graph, sequence_store) = read_test_graph(test_file);
+
+        // Node 0 has 2 of 4 and node 1 has 4 of 6 GC characters.
+        assert_eq!(overall_gc_content(&graph, &sequence_store), Some(0.6));
+    }
+
+    #[test]
+    fn test_abundance_histogram() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n\
+            >3 LN:i:4\n\
+            ACCA\n\
+            >4 LN:i:4 km:f:7.6\n\
+            TTGA\n";
+        let (graph, _) = read_test_graph(test_file);
+
+        assert_eq!(
+            abundance_histogram(&graph),
+            BTreeMap::from([(2, 1), (3, 2), (8, 1)])
+        );
+    }
+
+    #[test]
+    fn test_summarize() {
+        let (graph, sequence_store) = read_test_graph(TEST_FILE);
+
+        let summary = summarize(&graph, &sequence_store);
+        assert!(summary.contains("nodes: 6\n"));
+        assert!(summary.contains(&format!("edges: {}\n", graph.edge_count())));
+        assert!(summary.contains("self-mirror nodes: 0\n"));
+        assert!(summary.contains("total sequence length: 23\n"));
+        assert!(summary.contains("min degree: 1\n"));
+        assert!(summary.contains("max degree: 2\n"));
+    }
+
+    #[test]
+    fn test_kmer_spectrum() {
+        let test_file: &'static [u8] = b">0 LN:i:5 km:f:3.0\n\
+            AACGT\n\
+            >1 LN:i:4 km:f:7.6\n\
+            TTGA\n\
+            >2 km:f:3.2\n\
+            GATTACA\n\
+            >3 LN:i:4\n\
+            ACCA\n";
+        let (graph, sequence_store) = read_test_graph(test_file);
+
+        // The length of node 2 is taken from its sequence, since it has no `LN` tag.
+        assert_eq!(
+            kmer_spectrum(&graph, &sequence_store, 3),
+            BTreeMap::from([(3, 8), (8, 2)])
+        );
+        assert_eq!(
+            kmer_spectrum(&graph, &sequence_store, 5),
+            BTreeMap::from([(3, 4)])
+        );
+    }
+
+    #[test]
+    fn test_color_count() {
+        let test_file: &'static [u8] = b">0 LN:i:3 CL:Z:0,2,5 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 CL:Z:2,7 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 L:-:1:-\n\
+            ATGATG\n";
+        let (graph, _) = read_test_graph(test_file);
+
+        assert_eq!(color_count(&graph), 4);
+    }
+}
@@ -0,0 +1,985 @@
+use crate::algorithms::unitigs::maximal_unitigs;
+use crate::io::bcalm2::{oriented_sequence_vec, PlainBCalm2NodeData};
+use bigraph::interface::dynamic_bigraph::{DynamicBigraph, DynamicNodeCentricBigraph};
+use bigraph::interface::static_bigraph::StaticBigraph;
+use bigraph::interface::BidirectedData;
+use bigraph::traitgraph::index::GraphIndex;
+use bigraph::traitgraph::interface::Edge;
+use compact_genome::interface::alphabet::Alphabet;
+use compact_genome::interface::sequence_store::SequenceStore;
+use thiserror::Error;
+
+/// The reason why [`merge_unitig_pair`] could not merge a node with its successor, or [`compact_unitigs`] could not merge a chain.
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum UnitigMergeError {
+    #[error("node {node} does not have exactly one outgoing edge")]
+    OutDegreeNotOne { node: usize },
+
+    #[error("node {node} does not have exactly one incoming edge")]
+    InDegreeNotOne { node: usize },
+
+    #[error("node {node} cannot be merged with itself or its mirror")]
+    SelfMerge { node: usize },
+
+    #[error("the sequences of nodes {from_node} and {to_node} do not overlap by k - 1 characters")]
+    OverlapMismatch { from_node: usize, to_node: usize },
+
+    #[error("node {node} has no mirror")]
+    NodeWithoutMirror { node: usize },
+
+    #[error("the k-mer size must be at least one")]
+    ZeroKmerSize,
+}
+
+/// Compute the transpose of a bigraph, i.e. a copy of the graph in which the direction of each edge is reversed.
+///
+/// The nodes are copied in order together with their mirror pairing, and each edge keeps its data.
+/// Since an edge `(u, v)` and its mirror `(mirror(v), mirror(u))` become `(v, u)` and `(mirror(u), mirror(v))`,
+/// which are again mirrors of each other, the transpose of a bigraph is a bigraph.
+/// Reversing a graph twice yields the original graph.
+pub fn reverse_graph<Graph: DynamicBigraph + Default>(graph: &Graph) -> Graph
+where
+    Graph::NodeData: Clone,
+    Graph::EdgeData: Clone,
+{
+    let mut result = Graph::default();
+    let node_map: Vec<_> = graph
+        .node_indices()
+        .map(|node| result.add_node(graph.node_data(node).clone()))
+        .collect();
+
+    for node in graph.node_indices() {
+        if let Some(mirror_node) = graph.mirror_node(node) {
+            if node.as_usize() <= mirror_node.as_usize() {
+                result
+                    .set_mirror_nodes(node_map[node.as_usize()], node_map[mirror_node.as_usize()]);
+            }
+        }
+    }
+
+    for edge in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+        result.add_edge(
+            node_map[to_node.as_usize()],
+            node_map[from_node.as_usize()],
+            graph.edge_data(edge).clone(),
+        );
+    }
+
+    result
+}
+
+/// Split each self-mirror node of a bigraph into a pair of distinct mirror nodes.
+///
+/// The original node keeps its incoming edges, and its outgoing edges are moved to the new mirror node.
+/// Since the mirror of an incoming edge is an outgoing edge, the mirror property of the edges is preserved.
+/// To preserve the connectivity, a marker edge from the original node to the new mirror node is added,
+/// with data created by `marker_edge_data` from the data of the original node.
+/// In an edge-centric graph, the marker edge is its own mirror, so its data should be its own mirror as well.
+///
+/// The graph is rebuilt, and the new mirror nodes are appended after the existing nodes, which keep their indices.
+/// Returns the number of self-mirror nodes that were expanded.
+pub fn expand_self_mirror_nodes<Graph: DynamicBigraph + Default>(
+    graph: &mut Graph,
+    mut marker_edge_data: impl FnMut(&Graph::NodeData) -> Graph::EdgeData,
+) -> usize
+where
+    Graph::NodeData: Clone,
+    Graph::EdgeData: Clone,
+{
+    let self_mirror_nodes: Vec<_> = graph
+        .node_indices()
+        .filter(|&node| graph.mirror_node(node) == Some(node))
+        .collect();
+    if self_mirror_nodes.is_empty() {
+        return 0;
+    }
+
+    let mut result = Graph::default();
+    let node_map: Vec<_> = graph
+        .node_indices()
+        .map(|node| result.add_node(graph.node_data(node).clone()))
+        .collect();
+    let mut expanded_mirror_nodes = vec![None; graph.node_count()];
+    for &node in &self_mirror_nodes {
+        expanded_mirror_nodes[node.as_usize()] =
+            Some(result.add_node(graph.node_data(node).clone()));
+    }
+
+    for node in graph.node_indices() {
+        if let Some(mirror_node) = expanded_mirror_nodes[node.as_usize()] {
+            result.set_mirror_nodes(node_map[node.as_usize()], mirror_node);
+        } else if let Some(mirror_node) = graph.mirror_node(node) {
+            if node.as_usize() < mirror_node.as_usize() {
+                result
+                    .set_mirror_nodes(node_map[node.as_usize()], node_map[mirror_node.as_usize()]);
+            }
+        }
+    }
+
+    for edge in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+        let from_node =
+            expanded_mirror_nodes[from_node.as_usize()].unwrap_or(node_map[from_node.as_usize()]);
+        result.add_edge(
+            from_node,
+            node_map[to_node.as_usize()],
+            graph.edge_data(edge).clone(),
+        );
+    }
+
+    for &node in &self_mirror_nodes {
+        result.add_edge(
+            node_map[node.as_usize()],
+            expanded_mirror_nodes[node.as_usize()].unwrap(),
+            marker_edge_data(graph.node_data(node)),
+        );
+    }
+
+    *graph = result;
+    self_mirror_nodes.len()
+}
+
+/// Group the edges of a graph into classes of exact duplicates, i.e. edges with the same endpoints and equal data.
+///
+/// The classes are ordered by their first edge, and each class is ordered by edge index.
+fn duplicate_edge_classes<Graph: StaticBigraph>(graph: &Graph) -> Vec<Vec<Graph::EdgeIndex>>
+where
+    Graph::EdgeData: Eq,
+{
+    let mut classes = Vec::new();
+    for node in graph.node_indices() {
+        let mut node_classes: Vec<Vec<Graph::EdgeIndex>> = Vec::new();
+        let mut out_edges: Vec<_> = graph
+            .out_neighbors(node)
+            .map(|neighbor| (neighbor.node_id, neighbor.edge_id))
+            .collect();
+        out_edges.sort_unstable_by_key(|(_, edge)| edge.as_usize());
+
+        for (to_node, edge) in out_edges {
+            if let Some(class) = node_classes.iter_mut().find(|class| {
+                graph.edge_endpoints(class[0]).to_node == to_node
+                    && graph.edge_data(class[0]) == graph.edge_data(edge)
+            }) {
+                class.push(edge);
+            } else {
+                node_classes.push(vec![edge]);
+            }
+        }
+        classes.extend(node_classes);
+    }
+
+    classes.sort_unstable_by_key(|class| class[0].as_usize());
+    classes
+}
+
+/// Find exact duplicate edges, i.e. edges with the same endpoints and equal data, as they may result from transformations.
+///
+/// Returns the endpoints of each set of duplicate edges together with its multiplicity, which is greater than one.
+/// The duplicates of the mirrors of a set of duplicate edges form another set,
+/// so only the set whose endpoints have the lower indices is reported.
+///
+/// In edge-centric graphs, parallel edges with different data are distinct unitigs and are not duplicates.
+pub fn find_duplicate_edges<Graph: StaticBigraph>(
+    graph: &Graph,
+) -> Vec<(Graph::NodeIndex, Graph::NodeIndex, usize)>
+where
+    Graph::EdgeData: Eq,
+{
+    duplicate_edge_classes(graph)
+        .into_iter()
+        .filter(|class| class.len() > 1)
+        .filter_map(|class| {
+            let Edge { from_node, to_node } = graph.edge_endpoints(class[0]);
+            let mirror_endpoints = graph.mirror_node(to_node).zip(graph.mirror_node(from_node));
+            if mirror_endpoints.is_some_and(|(mirror_from_node, mirror_to_node)| {
+                (mirror_from_node.as_usize(), mirror_to_node.as_usize())
+                    < (from_node.as_usize(), to_node.as_usize())
+            }) {
+                None
+            } else {
+                Some((from_node, to_node, class.len()))
+            }
+        })
+        .collect()
+}
+
+/// Remove exact duplicate edges, i.e. edges with the same endpoints and equal data, keeping the first edge of each set.
+///
+/// Since the duplicates of an edge are mirrored by the duplicates of its mirror,
+/// the kept edges still have their mirrors.
+/// The graph is rebuilt, so nodes keep their indices, and the kept edges keep their relative order.
+/// Returns the number of removed edges.
+pub fn dedup_edges<Graph: DynamicBigraph + Default>(graph: &mut Graph) -> usize
+where
+    Graph::NodeData: Clone,
+    Graph::EdgeData: Clone + Eq,
+{
+    let classes = duplicate_edge_classes(graph);
+    let removed_edge_count = graph.edge_count() - classes.len();
+    if removed_edge_count == 0 {
+        return 0;
+    }
+
+    let mut result = Graph::default();
+    for node in graph.node_indices() {
+        result.add_node(graph.node_data(node).clone());
+    }
+    for node in graph.node_indices() {
+        if let Some(mirror_node) = graph.mirror_node(node) {
+            if node.as_usize() <= mirror_node.as_usize() {
+                result.set_mirror_nodes(node, mirror_node);
+            }
+        }
+    }
+    for class in classes {
+        let Edge { from_node, to_node } = graph.edge_endpoints(class[0]);
+        result.add_edge(from_node, to_node, graph.edge_data(class[0]).clone());
+    }
+
+    *graph = result;
+    removed_edge_count
+}
+
+/// Create a random subgraph that retains each pair of mirror nodes with probability `fraction`.
+///
+/// The same `seed` always results in the same subgraph.
+/// Edges are retained if and only if both their endpoints are retained, so the connectivity of the graph is not preserved,
+/// and the subgraph may contain dead ends and components that are connected in the original graph.
+/// The retained nodes keep their relative order, and the retained edges keep their data and relative order.
+pub fn subsample<Graph: DynamicBigraph + Default>(graph: &Graph, fraction: f64, seed: u64) -> Graph
+where
+    Graph::NodeData: Clone,
+    Graph::EdgeData: Clone,
+{
+    let mut random_state = seed;
+    let mut retained = vec![false; graph.node_count()];
+    for node in graph.node_indices() {
+        retained[node.as_usize()] = match graph.mirror_node(node) {
+            Some(mirror_node) if mirror_node.as_usize() < node.as_usize() => {
+                retained[mirror_node.as_usize()]
+            }
+            _ => next_random_fraction(&mut random_state) < fraction,
+        };
+    }
+
+    let mut result = Graph::default();
+    let node_map: Vec<_> = graph
+        .node_indices()
+        .map(|node| {
+            retained[node.as_usize()].then(|| result.add_node(graph.node_data(node).clone()))
+        })
+        .collect();
+
+    for node in graph.node_indices() {
+        if let (Some(result_node), Some(mirror_node)) =
+            (node_map[node.as_usize()], graph.mirror_node(node))
+        {
+            if node.as_usize() <= mirror_node.as_usize() {
+                result.set_mirror_nodes(result_node, node_map[mirror_node.as_usize()].unwrap());
+            }
+        }
+    }
+
+    for edge in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+        if let (Some(from_node), Some(to_node)) =
+            (node_map[from_node.as_usize()], node_map[to_node.as_usize()])
+        {
+            result.add_edge(from_node, to_node, graph.edge_data(edge).clone());
+        }
+    }
+
+    result
+}
+
+/// Advance the state of a splitmix64 generator and return a uniformly distributed number in `[0, 1)`.
+fn next_random_fraction(state: &mut u64) -> f64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut value = *state;
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+    value ^= value >> 31;
+    (value >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Merge a node of a node-centric bcalm2 graph with its unique successor, if the successor has no other predecessor.
+///
+/// The sequence of the merged node is the sequence of `node` followed by the sequence of its successor without the
+/// `kmer_size - 1` overlapping characters, and is added to `target_sequence_store`.
+/// The merged node keeps the id of `node`, and its abundances are combined if both nodes have them.
+/// The mirror nodes are merged accordingly.
+///
+/// The graph is rebuilt without the successor and its mirror, so the indices of other nodes may change.
+/// Nodes that are not involved in the merge keep their relative order.
+pub fn merge_unitig_pair<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    EdgeData: Clone,
+    Graph: DynamicNodeCentricBigraph<
+            NodeData = PlainBCalm2NodeData<GenomeSequenceStore::Handle>,
+            EdgeData = EdgeData,
+        > + Default,
+>(
+    graph: &mut Graph,
+    target_sequence_store: &mut GenomeSequenceStore,
+    node: Graph::NodeIndex,
+    kmer_size: usize,
+) -> Result<(), UnitigMergeError>
+where
+    GenomeSequenceStore::Handle: Clone,
+{
+    if kmer_size == 0 {
+        return Err(UnitigMergeError::ZeroKmerSize);
+    }
+    let mut out_neighbors = graph.out_neighbors(node);
+    let successor = match (out_neighbors.next(), out_neighbors.next()) {
+        (Some(neighbor), None) => neighbor.node_id,
+        _ => {
+            return Err(UnitigMergeError::OutDegreeNotOne {
+                node: node.as_usize(),
+            })
+        }
+    };
+    if graph.in_neighbors(successor).count() != 1 {
+        return Err(UnitigMergeError::InDegreeNotOne {
+            node: successor.as_usize(),
+        });
+    }
+    let mirror_node = graph
+        .mirror_node(node)
+        .ok_or(UnitigMergeError::NodeWithoutMirror {
+            node: node.as_usize(),
+        })?;
+    let mirror_successor =
+        graph
+            .mirror_node(successor)
+            .ok_or(UnitigMergeError::NodeWithoutMirror {
+                node: successor.as_usize(),
+            })?;
+    if successor == node || successor == mirror_node {
+        return Err(UnitigMergeError::SelfMerge {
+            node: node.as_usize(),
+        });
+    }
+
+    let node_data = graph.node_data(node);
+    let successor_data = graph.node_data(successor);
+    let mut sequence = oriented_sequence_vec(node_data, target_sequence_store);
+    let successor_sequence = oriented_sequence_vec(successor_data, target_sequence_store);
+    let overlap = kmer_size - 1;
+    if sequence.len() < overlap
+        || successor_sequence.len() < overlap
+        || sequence[sequence.len() - overlap..] != successor_sequence[..overlap]
+    {
+        return Err(UnitigMergeError::OverlapMismatch {
+            from_node: node.as_usize(),
+            to_node: successor.as_usize(),
+        });
+    }
+    sequence.extend_from_slice(&successor_sequence[overlap..]);
+
+    let kmer_count = |length: usize| (length + 1).saturating_sub(kmer_size);
+    let node_kmer_count = kmer_count(sequence.len() - successor_sequence.len() + overlap);
+    let successor_kmer_count = kmer_count(successor_sequence.len());
+    let total_abundance = node_data
+        .total_abundance
+        .zip(successor_data.total_abundance)
+        .map(|(a, b)| a + b);
+    let mean_abundance = node_data
+        .mean_abundance
+        .zip(successor_data.mean_abundance)
+        .filter(|_| node_kmer_count + successor_kmer_count > 0)
+        .map(|(a, b)| {
+            (a * node_kmer_count as f64 + b * successor_kmer_count as f64)
+                / (node_kmer_count + successor_kmer_count) as f64
+        });
+    let merged_data = PlainBCalm2NodeData {
+        id: node_data.id,
+        sequence_handle: target_sequence_store
+            .add_from_slice_u8(&sequence)
+            .expect("merged sequence consists of characters from the sequence store"),
+        forwards: true,
+        length: Some(sequence.len()),
+        total_abundance,
+        mean_abundance,
+        colors: None,
+        raw_description: None,
+        edges: Vec::new(),
+    };
+
+    let successor_out_edges: Vec<_> = graph
+        .out_neighbors(successor)
+        .map(|neighbor| (neighbor.node_id, graph.edge_data(neighbor.edge_id).clone()))
+        .collect();
+
+    let mut result = Graph::default();
+    let mut node_map = vec![None; graph.node_count()];
+    for old_node in graph.node_indices() {
+        if old_node == successor || old_node == mirror_successor {
+            continue;
+        }
+        let data = if old_node == node {
+            merged_data.clone()
+        } else if old_node == mirror_node {
+            merged_data.mirror()
+        } else {
+            graph.node_data(old_node).clone()
+        };
+        node_map[old_node.as_usize()] = Some(result.add_node(data));
+    }
+    for old_node in graph.node_indices() {
+        let Some(new_node) = node_map[old_node.as_usize()] else {
+            continue;
+        };
+        let old_mirror_node =
+            graph
+                .mirror_node(old_node)
+                .ok_or(UnitigMergeError::NodeWithoutMirror {
+                    node: old_node.as_usize(),
+                })?;
+        if old_node.as_usize() <= old_mirror_node.as_usize() {
+            result.set_mirror_nodes(new_node, node_map[old_mirror_node.as_usize()].unwrap());
+        }
+    }
+    for edge in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+        if let (Some(from_node), Some(to_node)) =
+            (node_map[from_node.as_usize()], node_map[to_node.as_usize()])
+        {
+            result.add_edge(from_node, to_node, graph.edge_data(edge).clone());
+        }
+    }
+
+    // The outgoing edges of the successor become outgoing edges of the merged node.
+    let merged_node = node_map[node.as_usize()].unwrap();
+    let merged_mirror_node = node_map[mirror_node.as_usize()].unwrap();
+    for (neighbor, edge_data) in successor_out_edges {
+        let to_node = if neighbor == mirror_successor {
+            merged_mirror_node
+        } else {
+            node_map[neighbor.as_usize()].unwrap()
+        };
+        result.add_edge(merged_node, to_node, edge_data.clone());
+        // An edge to the merged mirror node is its own mirror.
+        if to_node != merged_mirror_node {
+            let mirror_to_node = result.mirror_node(to_node).unwrap();
+            result.add_edge(mirror_to_node, merged_mirror_node, edge_data);
+        }
+    }
+
+    *graph = result;
+    Ok(())
+}
+
+/// Compact all non-branching chains of a node-centric bcalm2 graph, such that each maximal unitig becomes a single node.
+///
+/// The chains are computed once with [`maximal_unitigs`], and the graph is rebuilt in a single pass.
+/// A chain is split where it would contain a node together with its mirror.
+/// The sequence of a merged node is the sequence spelled by its chain, whose consecutive sequences overlap by `kmer_size - 1`
+/// characters, and is added to `target_sequence_store`.
+/// A merged node keeps the id of the first node of its chain, and its abundances are combined like by [`merge_unitig_pair`].
+///
+/// Nodes that are not merged keep their data.
+/// All nodes keep their relative order, where a merged node takes the place of the first node of its chain.
+/// Returns the number of removed nodes.
+pub fn compact_unitigs<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    EdgeData: Clone,
+    Graph: DynamicNodeCentricBigraph<
+            NodeData = PlainBCalm2NodeData<GenomeSequenceStore::Handle>,
+            EdgeData = EdgeData,
+        > + Default,
+>(
+    graph: &mut Graph,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+) -> Result<usize, UnitigMergeError>
+where
+    GenomeSequenceStore::Handle: Clone,
+{
+    if kmer_size == 0 {
+        return Err(UnitigMergeError::ZeroKmerSize);
+    }
+
+    // The chain, the offset within the chain and the orientation of each node.
+    let mut chain_positions = vec![None; graph.node_count()];
+    let mut chains = Vec::new();
+    for unitig in maximal_unitigs(graph) {
+        let mut chain = Vec::new();
+        for node in unitig {
+            let mirror_node =
+                graph
+                    .mirror_node(node)
+                    .ok_or(UnitigMergeError::NodeWithoutMirror {
+                        node: node.as_usize(),
+                    })?;
+            // The unitig reached the mirror of one of its nodes, which is already covered.
+            if chain_positions[node.as_usize()].is_some() {
+                if !chain.is_empty() {
+                    chains.push(chain);
+                    chain = Vec::new();
+                }
+                continue;
+            }
+
+            chain_positions[node.as_usize()] = Some((chains.len(), chain.len(), true));
+            if mirror_node != node {
+                chain_positions[mirror_node.as_usize()] = Some((chains.len(), chain.len(), false));
+            }
+            chain.push(node);
+        }
+        if !chain.is_empty() {
+            chains.push(chain);
+        }
+    }
+
+    let mut result = Graph::default();
+    let mut chain_nodes = vec![None; chains.len()];
+    for node in graph.node_indices() {
+        let (chain_index, offset, forwards) = chain_positions[node.as_usize()].unwrap();
+        if offset != 0 || chain_nodes[chain_index].is_some() {
+            continue;
+        }
+
+        let chain: &Vec<_> = &chains[chain_index];
+        let mirror_node = graph.mirror_node(chain[0]).unwrap();
+        let (data, mirror_data) = if chain.len() == 1 {
+            (
+                graph.node_data(chain[0]).clone(),
+                graph.node_data(mirror_node).clone(),
+            )
+        } else {
+            let data = merge_chain_data(graph, target_sequence_store, chain, kmer_size)?;
+            let mirror_data = data.mirror();
+            (data, mirror_data)
+        };
+
+        let (first_data, second_data) = if forwards {
+            (data, mirror_data)
+        } else {
+            (mirror_data, data)
+        };
+        let first_node = result.add_node(first_data);
+        let second_node = if mirror_node == chain[0] {
+            first_node
+        } else {
+            result.add_node(second_data)
+        };
+        result.set_mirror_nodes(first_node, second_node);
+        chain_nodes[chain_index] = Some(if forwards {
+            (first_node, second_node)
+        } else {
+            (second_node, first_node)
+        });
+    }
+
+    let node_map = |node: Graph::NodeIndex| {
+        let (chain_index, _, forwards) = chain_positions[node.as_usize()].unwrap();
+        let (chain_node, chain_mirror_node) = chain_nodes[chain_index].unwrap();
+        if forwards {
+            chain_node
+        } else {
+            chain_mirror_node
+        }
+    };
+    for edge in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+        let (from_chain, from_offset, from_forwards) =
+            chain_positions[from_node.as_usize()].unwrap();
+        let (to_chain, to_offset, to_forwards) = chain_positions[to_node.as_usize()].unwrap();
+        // Edges within a chain are contained in the merged node.
+        let is_chain_edge = from_chain == to_chain
+            && from_forwards == to_forwards
+            && if from_forwards {
+                to_offset == from_offset + 1
+            } else {
+                from_offset == to_offset + 1
+            };
+        if !is_chain_edge {
+            result.add_edge(
+                node_map(from_node),
+                node_map(to_node),
+                graph.edge_data(edge).clone(),
+            );
+        }
+    }
+
+    let removed_node_count = graph.node_count() - result.node_count();
+    *graph = result;
+    Ok(removed_node_count)
+}
+
+/// Compute the node data of a chain of nodes merged by [`compact_unitigs`].
+fn merge_chain_data<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    Graph: StaticBigraph<NodeData = PlainBCalm2NodeData<GenomeSequenceStore::Handle>>,
+>(
+    graph: &Graph,
+    target_sequence_store: &mut GenomeSequenceStore,
+    chain: &[Graph::NodeIndex],
+    kmer_size: usize,
+) -> Result<PlainBCalm2NodeData<GenomeSequenceStore::Handle>, UnitigMergeError> {
+    let overlap = kmer_size - 1;
+    let mut sequence: Vec<u8> = Vec::new();
+    let mut total_abundance = Some(0);
+    let mut weighted_mean_abundance = Some(0.0);
+    let mut kmer_count = 0;
+
+    for (offset, &node) in chain.iter().enumerate() {
+        let data = graph.node_data(node);
+        let node_sequence = oriented_sequence_vec(data, target_sequence_store);
+        if offset > 0
+            && (sequence.len() < overlap
+                || node_sequence.len() < overlap
+                || sequence[sequence.len() - overlap..] != node_sequence[..overlap])
+        {
+            return Err(UnitigMergeError::OverlapMismatch {
+                from_node: chain[offset - 1].as_usize(),
+                to_node: node.as_usize(),
+            });
+        }
+
+        let node_kmer_count = (node_sequence.len() + 1).saturating_sub(kmer_size);
+        total_abundance = total_abundance
+            .zip(data.total_abundance)
+            .map(|(a, b)| a + b);
+        weighted_mean_abundance = weighted_mean_abundance
+            .zip(data.mean_abundance)
+            .map(|(a, b)| a + b * node_kmer_count as f64);
+        kmer_count += node_kmer_count;
+        let skipped = if offset > 0 { overlap } else { 0 };
+        sequence.extend_from_slice(&node_sequence[skipped..]);
+    }
+
+    Ok(PlainBCalm2NodeData {
+        id: graph.node_data(chain[0]).id,
+        sequence_handle: target_sequence_store
+            .add_from_slice_u8(&sequence)
+            .expect("merged sequence consists of characters from the sequence store"),
+        forwards: true,
+        length: Some(sequence.len()),
+        total_abundance,
+        mean_abundance: weighted_mean_abundance
+            .filter(|_| kmer_count > 0)
+            .map(|weighted_mean_abundance| weighted_mean_abundance / kmer_count as f64),
+        colors: None,
+        raw_description: None,
+        edges: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::transform::{
+        compact_unitigs, dedup_edges, expand_self_mirror_nodes, find_duplicate_edges,
+        merge_unitig_pair, reverse_graph, subsample, UnitigMergeError,
+    };
+    use crate::io::bcalm2::{
+        read_bigraph_from_bcalm2_as_edge_centric, read_bigraph_from_bcalm2_as_node_centric,
+        write_edge_centric_bigraph_to_bcalm2, write_node_centric_bigraph_to_bcalm2,
+    };
+    use crate::types::{PetBCalm2EdgeGraph, PetBCalm2NodeGraph};
+    use bigraph::interface::static_bigraph::{StaticBigraph, StaticEdgeCentricBigraph};
+    use bigraph::traitgraph::interface::{Edge, ImmutableGraphContainer, MutableGraphContainer};
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use std::io::BufReader;
+
+    #[test]
+    fn test_reverse_graph() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let reversed = reverse_graph(&graph);
+        assert_eq!(reversed.node_count(), graph.node_count());
+        assert_eq!(reversed.edge_count(), graph.edge_count());
+        assert!(reversed.verify_node_pairing());
+        assert!(reversed.verify_edge_mirror_property());
+        for node in graph.node_indices() {
+            assert_eq!(reversed.mirror_node(node), graph.mirror_node(node));
+        }
+        for edge in graph.edge_indices() {
+            let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+            let reversed_endpoints = reversed.edge_endpoints(edge);
+            assert_eq!(reversed_endpoints.from_node, to_node);
+            assert_eq!(reversed_endpoints.to_node, from_node);
+            assert_eq!(reversed.edge_data(edge), graph.edge_data(edge));
+        }
+
+        let twice_reversed = reverse_graph(&reversed);
+        for edge in graph.edge_indices() {
+            let endpoints = graph.edge_endpoints(edge);
+            let twice_reversed_endpoints = twice_reversed.edge_endpoints(edge);
+            assert_eq!(twice_reversed_endpoints.from_node, endpoints.from_node);
+            assert_eq!(twice_reversed_endpoints.to_node, endpoints.to_node);
+            assert_eq!(twice_reversed.edge_data(edge), graph.edge_data(edge));
+        }
+    }
+
+    #[test]
+    fn test_merge_unitig_pair() {
+        let test_file: &'static [u8] = b">0 LN:i:4 KC:i:4 km:f:2.0 L:+:1:+\n\
+            AACG\n\
+            >1 LN:i:4 KC:i:8 km:f:4.0 L:+:2:+ L:-:0:-\n\
+            CGTT\n\
+            >2 LN:i:4 L:-:1:-\n\
+            TTGC\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let mut graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert_eq!(
+            merge_unitig_pair(&mut graph, &mut sequence_store, 2.into(), 3),
+            Err(UnitigMergeError::OutDegreeNotOne { node: 2 })
+        );
+        assert_eq!(
+            merge_unitig_pair(&mut graph, &mut sequence_store, 0.into(), 0),
+            Err(UnitigMergeError::ZeroKmerSize)
+        );
+
+        merge_unitig_pair(&mut graph, &mut sequence_store, 0.into(), 3).unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.verify_node_pairing());
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">0 LN:i:6 KC:i:12 km:f:3.0 L:+:2:+\nAACGTT\n>2 LN:i:4 L:-:0:-\nTTGC\n"
+        );
+
+        merge_unitig_pair(&mut graph, &mut sequence_store, 0.into(), 3).unwrap();
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 0);
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), ">0 LN:i:8\nAACGTTGC\n");
+    }
+
+    #[test]
+    fn test_compact_unitigs() {
+        // The chain of records 0 and 1 ends in a branch.
+        let branching_file: &'static [u8] = b">0 LN:i:4 L:+:1:+\n\
+            AACG\n\
+            >1 LN:i:4 L:+:2:+ L:+:3:+ L:-:0:-\n\
+            CGTT\n\
+            >2 LN:i:4 L:-:1:-\n\
+            TTGC\n\
+            >3 LN:i:4 L:-:1:-\n\
+            TTAC\n";
+        // Records 0 and 1 form a cycle.
+        let cycle_file: &'static [u8] = b">0 LN:i:4 L:+:1:+ L:-:1:-\n\
+            AACG\n\
+            >1 LN:i:4 L:+:0:+ L:-:0:-\n\
+            CGAA\n";
+
+        for (test_file, removed_node_count, expected_output) in [
+            (
+                branching_file,
+                2,
+                ">0 LN:i:6 L:+:2:+ L:+:3:+\nAACGTT\n>2 LN:i:4 L:-:0:-\nTTGC\n>3 LN:i:4 L:-:0:-\nTTAC\n",
+            ),
+            (cycle_file, 2, ">0 LN:i:6 L:+:0:+ L:-:0:-\nAACGAA\n"),
+        ] {
+            let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+            let mut graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+                BufReader::new(test_file),
+                &mut sequence_store,
+            )
+            .unwrap();
+
+            assert_eq!(
+                compact_unitigs(&mut graph, &mut sequence_store, 0),
+                Err(UnitigMergeError::ZeroKmerSize)
+            );
+            assert_eq!(
+                compact_unitigs(&mut graph, &mut sequence_store, 3),
+                Ok(removed_node_count)
+            );
+            assert!(graph.verify_node_pairing());
+            // Compacting again does not change the graph.
+            assert_eq!(compact_unitigs(&mut graph, &mut sequence_store, 3), Ok(0));
+
+            let mut output = Vec::new();
+            write_node_centric_bigraph_to_bcalm2(
+                &graph,
+                &sequence_store,
+                bio::io::fasta::Writer::new(&mut output),
+            )
+            .unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), expected_output);
+        }
+    }
+
+    #[test]
+    fn test_expand_self_mirror_nodes() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:0:- L:+:1:- L:+:2:+\n\
+            CAT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:1:- L:+:2:+\n\
+            GGTCTCGGGTAAAT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:0:- L:-:1:- L:-:2:+\n\
+            ATGATT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let mut graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let self_mirror_count = graph
+            .node_indices()
+            .filter(|&node| graph.is_self_mirror_node(node))
+            .count();
+        assert!(self_mirror_count > 0);
+        let node_count = graph.node_count();
+        let edge_count = graph.edge_count();
+        let mut marker = graph
+            .edge_data(graph.edge_indices().next().unwrap())
+            .clone();
+        marker.id = usize::MAX;
+
+        assert_eq!(
+            expand_self_mirror_nodes(&mut graph, |_| marker.clone()),
+            self_mirror_count
+        );
+        assert_eq!(graph.node_count(), node_count + self_mirror_count);
+        assert_eq!(graph.edge_count(), edge_count + self_mirror_count);
+        assert!(graph.verify_node_pairing());
+        assert!(!graph
+            .node_indices()
+            .any(|node| graph.is_self_mirror_node(node)));
+
+        // Each non-marker edge still has its mirror.
+        for edge in graph.edge_indices() {
+            if graph.edge_data(edge).id != usize::MAX {
+                assert!(graph.mirror_edge_edge_centric(edge).is_some());
+            }
+        }
+
+        assert_eq!(expand_self_mirror_nodes(&mut graph, |_| marker.clone()), 0);
+    }
+
+    #[test]
+    fn test_duplicate_edges() {
+        let test_file: &'static [u8] = b"\
+            >0 LN:i:7 KC:i:4 km:f:3.0 L:+:2:+ L:-:2:-\n\
+            AGTTCTC\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:2:+ L:-:2:-\n\
+            AGTCTCGGGTAATC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:+:0:+ L:+:1:+ L:-:0:- L:-:1:-\n\
+            TCGAAG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let mut graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let edge_count = graph.edge_count();
+
+        // The parallel edges of the multigraph are distinct unitigs.
+        assert!(find_duplicate_edges(&graph).is_empty());
+        assert_eq!(dedup_edges(&mut graph), 0);
+
+        let edge = graph.edge_indices().next().unwrap();
+        let mirror_edge = graph.mirror_edge_edge_centric(edge).unwrap();
+        for edge in [edge, mirror_edge] {
+            let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+            let edge_data = graph.edge_data(edge).clone();
+            graph.add_edge(from_node, to_node, edge_data.clone());
+            graph.add_edge(from_node, to_node, edge_data);
+        }
+        assert!(graph.verify_edge_mirror_property());
+
+        let duplicates = find_duplicate_edges(&graph);
+        assert_eq!(duplicates.len(), 1);
+        let (from_node, to_node, multiplicity) = duplicates[0];
+        assert_eq!(multiplicity, 3);
+        let endpoints = graph.edge_endpoints(edge);
+        let mirror_endpoints = graph.edge_endpoints(mirror_edge);
+        assert!(
+            (from_node, to_node) == (endpoints.from_node, endpoints.to_node)
+                || (from_node, to_node) == (mirror_endpoints.from_node, mirror_endpoints.to_node)
+        );
+
+        assert_eq!(dedup_edges(&mut graph), 4);
+        assert_eq!(graph.edge_count(), edge_count);
+        assert!(graph.verify_node_pairing());
+        assert!(graph.verify_edge_mirror_property());
+        assert!(find_duplicate_edges(&graph).is_empty());
+
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        assert_eq!(output, test_file);
+    }
+
+    #[test]
+    fn test_subsample() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        let full = subsample(&graph, 1.0, 0);
+        assert_eq!(full.node_count(), graph.node_count());
+        assert_eq!(full.edge_count(), graph.edge_count());
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &full,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(output, test_file);
+
+        let empty = subsample(&graph, 0.0, 0);
+        assert_eq!(empty.node_count(), 0);
+        assert_eq!(empty.edge_count(), 0);
+
+        for seed in 0..10 {
+            let sample = subsample(&graph, 0.5, seed);
+            assert_eq!(sample.node_count() % 2, 0);
+            assert!(sample.verify_node_pairing());
+            assert!(sample.verify_edge_mirror_property());
+            let same_sample = subsample(&graph, 0.5, seed);
+            assert_eq!(same_sample.node_count(), sample.node_count());
+            assert_eq!(same_sample.edge_count(), sample.edge_count());
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use bigraph::interface::static_bigraph::StaticBigraph;
+use bigraph::traitgraph::index::GraphIndex;
+use bigraph::traitgraph::walks::VecNodeWalk;
+use std::collections::VecDeque;
+use thiserror::Error;
+
+/// The error returned by [`topological_order`] if the graph contains a cycle.
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+#[error("the graph contains a cycle that reaches node {node}")]
+pub struct CycleError {
+    /// A node that lies on a cycle or is reachable from a cycle.
+    pub node: usize,
+}
+
+/// Compute a topological ordering of the nodes of a genome graph.
+///
+/// The nodes are ordered per weakly-connected component, where a node and its mirror are considered connected.
+/// Hence, the nodes of a component and of its mirror component form a contiguous part of the ordering.
+/// Returns an error if the graph contains a cycle.
+pub fn topological_order<Graph: StaticBigraph>(
+    graph: &Graph,
+) -> Result<Vec<Graph::NodeIndex>, CycleError> {
+    let mut in_degrees: Vec<_> = graph
+        .node_indices()
+        .map(|node| graph.in_neighbors(node).count())
+        .collect();
+    let mut visited = vec![false; graph.node_count()];
+    let mut order = Vec::with_capacity(graph.node_count());
+
+    for start in graph.node_indices() {
+        if visited[start.as_usize()] {
+            continue;
+        }
+
+        // Collect the component of the start node.
+        let mut component = vec![start];
+        visited[start.as_usize()] = true;
+        let mut index = 0;
+        while let Some(&node) = component.get(index) {
+            index += 1;
+            let neighbors = graph
+                .out_neighbors(node)
+                .chain(graph.in_neighbors(node))
+                .map(|neighbor| neighbor.node_id)
+                .chain(graph.mirror_node(node));
+            for neighbor in neighbors {
+                if !visited[neighbor.as_usize()] {
+                    visited[neighbor.as_usize()] = true;
+                    component.push(neighbor);
+                }
+            }
+        }
+
+        // Order the component by repeatedly removing nodes without incoming edges.
+        let component_offset = order.len();
+        let mut queue: VecDeque<_> = component
+            .iter()
+            .copied()
+            .filter(|node| in_degrees[node.as_usize()] == 0)
+            .collect();
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for neighbor in graph.out_neighbors(node) {
+                let in_degree = &mut in_degrees[neighbor.node_id.as_usize()];
+                *in_degree -= 1;
+                if *in_degree == 0 {
+                    queue.push_back(neighbor.node_id);
+                }
+            }
+        }
+
+        if order.len() - component_offset < component.len() {
+            let node = component
+                .iter()
+                .find(|node| in_degrees[node.as_usize()] > 0)
+                .unwrap();
+            return Err(CycleError {
+                node: node.as_usize(),
+            });
+        }
+    }
+
+    Ok(order)
+}
+
+/// Returns true if `target` can be reached from `source` by following edges and mirror transitions.
+pub fn are_connected_bidirected<Graph: StaticBigraph>(
+    graph: &Graph,
+    source: Graph::NodeIndex,
+    target: Graph::NodeIndex,
+) -> bool {
+    shortest_path_bidirected(graph, source, target).is_some()
+}
+
+/// Compute a shortest walk from `source` to `target` that follows edges and mirror transitions.
+///
+/// Each step of the walk either follows an edge of the graph, or moves from a node to its mirror.
+/// A step to the mirror corresponds to continuing on the reverse complement,
+/// so two consecutive nodes in the walk are either connected by an edge or are mirrors of each other.
+/// Returns `None` if `target` cannot be reached from `source`.
+pub fn shortest_path_bidirected<Graph: StaticBigraph>(
+    graph: &Graph,
+    source: Graph::NodeIndex,
+    target: Graph::NodeIndex,
+) -> Option<VecNodeWalk<Graph>> {
+    let mut predecessors = vec![None; graph.node_count()];
+    let mut visited = vec![false; graph.node_count()];
+    let mut queue = VecDeque::from([source]);
+    visited[source.as_usize()] = true;
+
+    while let Some(node) = queue.pop_front() {
+        if node == target {
+            let mut walk = vec![target];
+            let mut current = target;
+            while let Some(predecessor) = predecessors[current.as_usize()] {
+                walk.push(predecessor);
+                current = predecessor;
+            }
+            walk.reverse();
+            return Some(walk);
+        }
+
+        let successors = graph
+            .out_neighbors(node)
+            .map(|neighbor| neighbor.node_id)
+            .chain(graph.mirror_node(node));
+        for successor in successors {
+            if !visited[successor.as_usize()] {
+                visited[successor.as_usize()] = true;
+                predecessors[successor.as_usize()] = Some(node);
+                queue.push_back(successor);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::traversal::{
+        are_connected_bidirected, shortest_path_bidirected, topological_order, CycleError,
+    };
+    use crate::io::bcalm2::read_bigraph_from_bcalm2_as_node_centric;
+    use crate::types::PetBCalm2NodeGraph;
+    use bigraph::traitgraph::index::GraphIndex;
+    use bigraph::traitgraph::interface::{Edge, ImmutableGraphContainer};
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use std::io::BufReader;
+
+    #[test]
+    fn test_topological_order() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:3 L:-:0:- L:+:2:+\n\
+            ACG\n\
+            >2 LN:i:3 L:-:1:-\n\
+            CGG\n\
+            >3 LN:i:3\n\
+            TTT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        let order = topological_order(&graph).unwrap();
+        assert_eq!(order.len(), graph.node_count());
+        let mut positions = vec![usize::MAX; graph.node_count()];
+        for (position, node) in order.iter().enumerate() {
+            positions[node.as_usize()] = position;
+        }
+        for edge in graph.edge_indices() {
+            let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+            assert!(positions[from_node.as_usize()] < positions[to_node.as_usize()]);
+        }
+
+        // The chain and its mirror come first, followed by the isolated record.
+        let mut last_nodes: Vec<_> = order[6..].iter().map(|node| node.as_usize()).collect();
+        last_nodes.sort_unstable();
+        assert_eq!(last_nodes, vec![3, 7]);
+    }
+
+    #[test]
+    fn test_topological_order_cycle() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+ L:-:1:-\n\
+            AAC\n\
+            >1 LN:i:3 L:+:0:+ L:-:0:-\n\
+            ACA\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert!(matches!(topological_order(&graph), Err(CycleError { .. })));
+    }
+
+    #[test]
+    fn test_shortest_path_bidirected() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:3 L:-:0:- L:+:2:+\n\
+            ACG\n\
+            >2 LN:i:3 L:-:1:-\n\
+            CGG\n\
+            >3 LN:i:3\n\
+            TTT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        // Record i is node i, and its mirror is node 4 + i.
+        assert_eq!(
+            shortest_path_bidirected(&graph, 0.into(), 2.into()),
+            Some(vec![0.into(), 1.into(), 2.into()])
+        );
+        assert_eq!(
+            shortest_path_bidirected(&graph, 2.into(), 0.into()),
+            Some(vec![2.into(), 6.into(), 5.into(), 4.into(), 0.into()])
+        );
+        assert!(are_connected_bidirected(&graph, 0.into(), 2.into()));
+        assert!(are_connected_bidirected(&graph, 5.into(), 0.into()));
+
+        assert_eq!(shortest_path_bidirected(&graph, 0.into(), 3.into()), None);
+        assert!(!are_connected_bidirected(&graph, 0.into(), 3.into()));
+        assert!(!are_connected_bidirected(&graph, 7.into(), 5.into()));
+    }
+}
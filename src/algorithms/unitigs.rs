@@ -0,0 +1,392 @@
+use crate::io::SequenceData;
+use bigraph::interface::static_bigraph::StaticNodeCentricBigraph;
+use bigraph::traitgraph::index::GraphIndex;
+use bigraph::traitgraph::interface::GraphBase;
+use bigraph::traitgraph::walks::VecNodeWalk;
+use compact_genome::interface::alphabet::Alphabet;
+use compact_genome::interface::sequence::GenomeSequence;
+use compact_genome::interface::sequence_store::SequenceStore;
+use thiserror::Error;
+
+/// The error returned by [`eulerian_contigs`] if the edges of a graph cannot be covered by a single walk.
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+#[error("the graph is not Eulerian, its edges are covered by {} walks", .path_cover.len())]
+pub struct NonEulerianError<NodeIndex> {
+    /// A minimal set of walks covering all edges of the graph.
+    pub path_cover: Vec<Vec<NodeIndex>>,
+}
+
+/// Compute the maximal unitigs of a node-centric genome graph.
+///
+/// A maximal unitig is a maximal walk in which each edge is the only outgoing edge of its tail and the only incoming edge of its head.
+/// Of each unitig and its reverse complement, only one is reported.
+/// Unitigs that form a cycle start at an arbitrary node and do not repeat it at the end.
+pub fn maximal_unitigs<Graph: StaticNodeCentricBigraph>(graph: &Graph) -> Vec<VecNodeWalk<Graph>> {
+    let out_degree = |node: Graph::NodeIndex| graph.out_neighbors(node).count();
+    let unique_predecessor = |node: Graph::NodeIndex| {
+        let mut in_neighbors = graph.in_neighbors(node);
+        match (in_neighbors.next(), in_neighbors.next()) {
+            (Some(neighbor), None) => Some(neighbor.node_id),
+            _ => None,
+        }
+    };
+    // True if the node continues the unitig of its unique predecessor.
+    let is_continuation =
+        |node| unique_predecessor(node).is_some_and(|predecessor| out_degree(predecessor) == 1);
+
+    let mut visited = vec![false; graph.node_count()];
+    let mut unitigs = Vec::new();
+
+    // Unitigs that do not form a cycle are found from their first node,
+    // and afterwards all unvisited nodes are on cycles.
+    for cycles in [false, true] {
+        for start in graph.node_indices() {
+            if visited[start.as_usize()] || (!cycles && is_continuation(start)) {
+                continue;
+            }
+
+            let mut unitig = vec![start];
+            let mut current = start;
+            while out_degree(current) == 1 {
+                let next = graph.out_neighbors(current).next().unwrap().node_id;
+                if next == start || unique_predecessor(next).is_none() {
+                    break;
+                }
+                unitig.push(next);
+                current = next;
+            }
+
+            for &node in &unitig {
+                visited[node.as_usize()] = true;
+                if let Some(mirror_node) = graph.mirror_node(node) {
+                    visited[mirror_node.as_usize()] = true;
+                }
+            }
+            unitigs.push(unitig);
+        }
+    }
+
+    unitigs
+}
+
+/// Compute the length of the longest maximal unitig of a node-centric genome graph, e.g. for quality control.
+///
+/// The length of a unitig is the length of the sequence it spells, where consecutive nodes overlap by `kmer_size - 1` characters.
+/// This is cheaper than spelling all unitigs, since only the lengths of the node sequences are needed.
+/// Returns zero for a graph without nodes, and an error if `kmer_size` is zero.
+/// If a node is shorter than the overlap, e.g. because `kmer_size` does not match the graph, it adds nothing to the length.
+pub fn longest_contig_length<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: SequenceData<AlphabetType, GenomeSequenceStore>,
+    Graph: StaticNodeCentricBigraph<NodeData = NodeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    kmer_size: usize,
+) -> crate::error::Result<usize> {
+    let overlap = kmer_size
+        .checked_sub(1)
+        .ok_or(crate::error::Error::ZeroKmerSize)?;
+    let node_length = |node| {
+        source_sequence_store
+            .get(graph.node_data(node).sequence_handle())
+            .len()
+    };
+
+    Ok(maximal_unitigs(graph)
+        .iter()
+        .map(|unitig| {
+            node_length(unitig[0])
+                + unitig[1..]
+                    .iter()
+                    .map(|&node| node_length(node).saturating_sub(overlap))
+                    .sum::<usize>()
+        })
+        .max()
+        .unwrap_or(0))
+}
+
+/// Compute an Eulerian walk of a node-centric genome graph, i.e. a walk that covers each edge exactly once.
+///
+/// Of each edge and its mirror, only one is covered, since walking an edge also spells its reverse complement.
+/// Hence, a walk may pass through a node in either orientation, entering it through its incoming edges and leaving it through its outgoing edges,
+/// or the other way round through its mirror.
+/// Such a walk exists if and only if the edges are connected and the in- and out-degrees of all nodes are equal,
+/// except for at most two nodes of different mirror pairs whose degrees differ by one, or one node whose degrees differ by two.
+///
+/// If such a walk exists, it is returned as the only contig, and a graph without edges results in no contigs.
+/// Otherwise, the error contains a minimal cover of all edges by walks.
+/// The walks are computed with Hierholzer's algorithm on the graph where each surplus of out-edges is connected to a surplus of in-edges by a dummy edge.
+/// This makes all degrees equal, and splitting the resulting closed walks at the dummy edges results in one walk per dummy edge,
+/// which is the lower bound given by the degree differences.
+pub fn eulerian_contigs<Graph: StaticNodeCentricBigraph>(
+    graph: &Graph,
+) -> Result<Vec<VecNodeWalk<Graph>>, NonEulerianError<<Graph as GraphBase>::NodeIndex>> {
+    let mirror = |node: Graph::NodeIndex| graph.mirror_node(node).unwrap_or(node);
+    let surplus = |node: Graph::NodeIndex| {
+        graph.out_neighbors(node).count() as isize - graph.in_neighbors(node).count() as isize
+    };
+
+    // Each walk starts at a node with more outgoing than incoming edges, or ends at its mirror.
+    // Of each mirror pair, the node with the surplus of outgoing edges is listed once per surplus edge.
+    let mut walk_starts = Vec::new();
+    for node in graph.node_indices() {
+        if node.as_usize() <= mirror(node).as_usize() {
+            let start = if surplus(node) > 0 {
+                node
+            } else {
+                mirror(node)
+            };
+            walk_starts.extend(std::iter::repeat(start).take(surplus(node).unsigned_abs()));
+        }
+    }
+    // Every edge has two ends, so the total surplus is even.
+    debug_assert_eq!(walk_starts.len() % 2, 0);
+    let satisfies_degree_condition = walk_starts.len() <= 2;
+
+    // A dummy edge from the end of one walk to the start of the next, and its mirror, share an id.
+    let mut dummy_out_edges = vec![Vec::new(); graph.node_count()];
+    for (dummy_edge, pair) in walk_starts.chunks_exact(2).enumerate() {
+        let (first, second) = (pair[0], pair[1]);
+        dummy_out_edges[mirror(first).as_usize()].push((second, dummy_edge));
+        dummy_out_edges[mirror(second).as_usize()].push((first, dummy_edge));
+    }
+    let mut used = vec![false; graph.edge_count()];
+    let mut used_dummy = vec![false; walk_starts.len() / 2];
+
+    // Returns the head of an unused out-edge of the node and if it is a dummy edge, and marks the edge and its mirror as used.
+    let mut next_edge = |node: Graph::NodeIndex| {
+        if let Some(neighbor) = graph
+            .out_neighbors(node)
+            .find(|neighbor| !used[neighbor.edge_id.as_usize()])
+        {
+            used[neighbor.edge_id.as_usize()] = true;
+            if let Some(mirror_edge) = graph.mirror_edge_node_centric(neighbor.edge_id) {
+                used[mirror_edge.as_usize()] = true;
+            }
+            Some((neighbor.node_id, false))
+        } else if let Some(&(head, dummy_edge)) = dummy_out_edges[node.as_usize()]
+            .iter()
+            .find(|(_, dummy_edge)| !used_dummy[*dummy_edge])
+        {
+            used_dummy[dummy_edge] = true;
+            Some((head, true))
+        } else {
+            None
+        }
+    };
+
+    let mut walks = Vec::new();
+    for start in graph.node_indices() {
+        // Each stack entry stores if the edge that was used to reach the node is a dummy edge.
+        let mut stack = Vec::new();
+        if let Some(first) = next_edge(start) {
+            stack.extend([(start, false), first]);
+        }
+        // Since all degrees are equal, the walk can only get stuck at its start, so it is closed.
+        let mut closed_walk = Vec::new();
+        while let Some(&(node, _)) = stack.last() {
+            if let Some(next) = next_edge(node) {
+                stack.push(next);
+            } else {
+                closed_walk.push(stack.pop().unwrap());
+            }
+        }
+        if closed_walk.is_empty() {
+            continue;
+        }
+        closed_walk.reverse();
+
+        // Split the closed walk at its dummy edges, starting after the first.
+        // The first entry repeats the last node, so the walk is rotated over the remaining entries.
+        let length = closed_walk.len() - 1;
+        let Some(first_dummy) = (1..=length).find(|&index| closed_walk[index].1) else {
+            walks.push(closed_walk.into_iter().map(|(node, _)| node).collect());
+            continue;
+        };
+        let mut walk = vec![closed_walk[first_dummy].0];
+        for offset in 1..=length {
+            let (node, is_dummy) = closed_walk[(first_dummy + offset - 1) % length + 1];
+            if is_dummy {
+                walks.push(std::mem::replace(&mut walk, vec![node]));
+            } else {
+                walk.push(node);
+            }
+        }
+    }
+
+    if satisfies_degree_condition && walks.len() <= 1 {
+        Ok(walks)
+    } else {
+        Err(NonEulerianError { path_cover: walks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::unitigs::{eulerian_contigs, longest_contig_length, maximal_unitigs};
+    use crate::io::bcalm2::read_bigraph_from_bcalm2_as_node_centric;
+    use crate::types::PetBCalm2NodeGraph;
+    use bigraph::traitgraph::interface::ImmutableGraphContainer;
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use std::io::BufReader;
+
+    #[test]
+    fn test_maximal_unitigs_chain() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:3 L:-:0:- L:+:2:+\n\
+            ACG\n\
+            >2 LN:i:3 L:-:1:-\n\
+            CGT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert_eq!(
+            maximal_unitigs(&graph),
+            vec![vec![0.into(), 1.into(), 2.into()]]
+        );
+    }
+
+    #[test]
+    fn test_maximal_unitigs_branching() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:3 L:-:0:- L:+:2:+ L:+:3:+\n\
+            ACG\n\
+            >2 LN:i:3 L:-:1:-\n\
+            CGT\n\
+            >3 LN:i:3 L:-:1:-\n\
+            CGA\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert_eq!(
+            maximal_unitigs(&graph),
+            vec![vec![0.into(), 1.into()], vec![2.into()], vec![3.into()]]
+        );
+    }
+
+    #[test]
+    fn test_eulerian_contigs_cycle() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+ L:-:2:-\n\
+            AAC\n\
+            >1 LN:i:3 L:-:0:- L:+:2:+\n\
+            ACG\n\
+            >2 LN:i:3 L:-:1:- L:+:0:+\n\
+            CGA\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert_eq!(
+            eulerian_contigs(&graph),
+            Ok(vec![vec![0.into(), 1.into(), 2.into(), 0.into()]])
+        );
+    }
+
+    #[test]
+    fn test_eulerian_contigs_mirror_traversal() {
+        // The third unitig can only be walked in reverse complement after the second.
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:3 L:-:0:- L:+:2:-\n\
+            ACG\n\
+            >2 LN:i:3 L:+:1:-\n\
+            TCG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert_eq!(
+            eulerian_contigs(&graph),
+            Ok(vec![vec![0.into(), 1.into(), 5.into()]])
+        );
+    }
+
+    #[test]
+    fn test_eulerian_contigs_unbalanced() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:3 L:-:0:- L:+:2:+ L:+:3:+\n\
+            ACG\n\
+            >2 LN:i:3 L:-:1:-\n\
+            CGT\n\
+            >3 LN:i:3 L:-:1:-\n\
+            CGA\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        let path_cover = eulerian_contigs(&graph).unwrap_err().path_cover;
+        assert_eq!(path_cover.len(), 2);
+        assert_eq!(
+            path_cover.iter().map(|walk| walk.len() - 1).sum::<usize>(),
+            graph.edge_count() / 2
+        );
+        for walk in &path_cover {
+            for pair in walk.windows(2) {
+                assert!(graph.contains_edge_between(pair[0], pair[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_longest_contig_length() {
+        let test_file: &'static [u8] = b">0 LN:i:6 L:+:1:+\n\
+            AAAAAC\n\
+            >1 LN:i:3 L:-:0:- L:+:2:+ L:+:3:+\n\
+            ACG\n\
+            >2 LN:i:4 L:-:1:-\n\
+            CGTT\n\
+            >3 LN:i:3 L:-:1:-\n\
+            CGA\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        // The longest unitig consists of the first two nodes and spells AAAAACG.
+        assert_eq!(
+            longest_contig_length(&graph, &sequence_store, 3).unwrap(),
+            7
+        );
+        // Nodes shorter than the overlap add nothing.
+        assert_eq!(
+            longest_contig_length(&graph, &sequence_store, 10).unwrap(),
+            6
+        );
+        assert!(matches!(
+            longest_contig_length(&graph, &sequence_store, 0),
+            Err(crate::error::Error::ZeroKmerSize)
+        ));
+
+        let graph = PetBCalm2NodeGraph::default();
+        assert_eq!(
+            longest_contig_length(&graph, &sequence_store, 3).unwrap(),
+            0
+        );
+    }
+}
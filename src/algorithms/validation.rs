@@ -0,0 +1,206 @@
+use crate::io::SequenceData;
+use bigraph::interface::static_bigraph::StaticNodeCentricBigraph;
+use bigraph::traitgraph::index::GraphIndex;
+use bigraph::traitgraph::interface::Edge;
+use bigraph::traitgraph::traitsequence::interface::Sequence;
+use compact_genome::implementation::bit_vec_sequence::BitVectorGenome;
+use compact_genome::interface::alphabet::Alphabet;
+use compact_genome::interface::sequence::GenomeSequence;
+use compact_genome::interface::sequence_store::SequenceStore;
+use std::collections::HashMap;
+use std::hash::Hash;
+use thiserror::Error;
+
+/// The first violation of the de Bruijn graph properties found by [`is_valid_de_bruijn_graph`].
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum DeBruijnViolation {
+    #[error("the k-mer size must be at least one")]
+    ZeroKmerSize,
+
+    #[error(
+        "node {node} has a sequence of length {length}, which is shorter than k = {kmer_size}"
+    )]
+    SequenceTooShort {
+        node: usize,
+        length: usize,
+        kmer_size: usize,
+    },
+
+    #[error("the sequences of nodes {from_node} and {to_node} do not overlap by k - 1 characters")]
+    OverlapMismatch { from_node: usize, to_node: usize },
+
+    #[error("edges {first_edge} and {second_edge} spell the same (k + 1)-mer")]
+    DuplicateKmer {
+        first_edge: usize,
+        second_edge: usize,
+    },
+}
+
+/// Check if a node-centric genome graph is a valid bidirected de Bruijn graph of order `kmer_size`.
+///
+/// The sequences of the endpoints of each edge must overlap by exactly `kmer_size - 1` characters,
+/// and no two edges may spell the same (k + 1)-mer, except for an edge and its mirror.
+/// The (k + 1)-mer of an edge consists of the last k-mer of its tail followed by the next character of its head,
+/// so nodes may have multiple predecessors or successors, but no two edges may join the same pair of k-mers.
+/// Returns the first violation found, or [`DeBruijnViolation::ZeroKmerSize`] if `kmer_size` is zero.
+pub fn is_valid_de_bruijn_graph<
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: SequenceData<AlphabetType, GenomeSequenceStore>,
+    Graph: StaticNodeCentricBigraph<NodeData = NodeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    kmer_size: usize,
+) -> Result<(), DeBruijnViolation> {
+    let overlap = kmer_size
+        .checked_sub(1)
+        .ok_or(DeBruijnViolation::ZeroKmerSize)?;
+    let mut sequences = Vec::with_capacity(graph.node_count());
+
+    for node in graph.node_indices() {
+        let sequence: BitVectorGenome<AlphabetType> =
+            graph.node_data(node).sequence_owned(source_sequence_store);
+        if sequence.len() < kmer_size {
+            return Err(DeBruijnViolation::SequenceTooShort {
+                node: node.as_usize(),
+                length: sequence.len(),
+                kmer_size,
+            });
+        }
+        sequences.push(sequence);
+    }
+
+    let mut kmers = HashMap::new();
+    for edge in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+        let from_sequence = &sequences[from_node.as_usize()];
+        let to_sequence = &sequences[to_node.as_usize()];
+
+        if !from_sequence
+            .suffix(overlap)
+            .iter()
+            .eq(to_sequence.prefix(overlap).iter())
+        {
+            return Err(DeBruijnViolation::OverlapMismatch {
+                from_node: from_node.as_usize(),
+                to_node: to_node.as_usize(),
+            });
+        }
+
+        let kmer: BitVectorGenome<AlphabetType> = from_sequence
+            .suffix(kmer_size)
+            .iter()
+            .chain(to_sequence.iter().skip(overlap).take(1))
+            .cloned()
+            .collect();
+        if let Some(&other_edge) = kmers.get(&kmer) {
+            // An edge spelling a palindromic (k + 1)-mer shares it with its mirror.
+            if graph.mirror_edge_node_centric(edge) != Some(other_edge) {
+                return Err(DeBruijnViolation::DuplicateKmer {
+                    first_edge: other_edge.as_usize(),
+                    second_edge: edge.as_usize(),
+                });
+            }
+        } else {
+            kmers.insert(kmer, edge);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::validation::{is_valid_de_bruijn_graph, DeBruijnViolation};
+    use crate::io::bcalm2::read_bigraph_from_bcalm2_as_node_centric;
+    use crate::types::PetBCalm2NodeGraph;
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use std::io::BufReader;
+
+    #[test]
+    fn test_valid_de_bruijn_graph() {
+        let test_file: &'static [u8] = b">0 LN:i:4 L:+:1:+\n\
+            AACG\n\
+            >1 LN:i:4 L:-:0:-\n\
+            CGAT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert_eq!(is_valid_de_bruijn_graph(&graph, &sequence_store, 3), Ok(()));
+        assert_eq!(
+            is_valid_de_bruijn_graph(&graph, &sequence_store, 0),
+            Err(DeBruijnViolation::ZeroKmerSize)
+        );
+    }
+
+    #[test]
+    fn test_branching_de_bruijn_graph() {
+        // The first two records both lead into the third.
+        let test_file: &'static [u8] = b">0 LN:i:4 L:+:2:+\n\
+            AACG\n\
+            >1 LN:i:4 L:+:2:+\n\
+            GGCG\n\
+            >2 LN:i:4 L:-:0:- L:-:1:-\n\
+            CGTT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert_eq!(is_valid_de_bruijn_graph(&graph, &sequence_store, 3), Ok(()));
+    }
+
+    #[test]
+    fn test_duplicate_kmer_de_bruijn_graph() {
+        // The first two records are equal, so both edges into the third record spell AACGT.
+        let test_file: &'static [u8] = b">0 LN:i:4 L:+:2:+\n\
+            AACG\n\
+            >1 LN:i:4 L:+:2:+\n\
+            AACG\n\
+            >2 LN:i:4 L:-:0:- L:-:1:-\n\
+            CGTT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            is_valid_de_bruijn_graph(&graph, &sequence_store, 3),
+            Err(DeBruijnViolation::DuplicateKmer { .. })
+        ));
+    }
+
+    #[test]
+    fn test_corrupted_de_bruijn_graph() {
+        let test_file: &'static [u8] = b">0 LN:i:4 L:+:1:+\n\
+            AACG\n\
+            >1 LN:i:4 L:-:0:-\n\
+            GGAT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            is_valid_de_bruijn_graph(&graph, &sequence_store, 3),
+            Err(DeBruijnViolation::OverlapMismatch { .. })
+        ));
+        assert!(matches!(
+            is_valid_de_bruijn_graph(&graph, &sequence_store, 5),
+            Err(DeBruijnViolation::SequenceTooShort { .. })
+        ));
+    }
+}
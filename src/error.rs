@@ -22,6 +22,23 @@ pub enum Error {
     #[error("dot io error: {0}")]
     DotIoError(#[from] crate::io::wtdbg2::dot::error::DotIoError),
 
+    #[error("gaf io error: {0}")]
+    GafIoError(#[from] crate::io::gaf::error::GafIoError),
+
     #[error("gfa io error: {0}")]
     GfaIoError(#[from] crate::io::gfa::error::GfaIoError),
+
+    #[error("unitig merge error: {0}")]
+    UnitigMergeError(#[from] crate::algorithms::transform::UnitigMergeError),
+
+    #[error("unknown graph format, the input starts with: '{start}'")]
+    UnknownGraphFormat { start: String },
+
+    #[error(
+        "reading graphs in format {format:?} is not supported, only bcalm2 and gfa can be read"
+    )]
+    UnsupportedGraphFormat { format: crate::io::GraphFormat },
+
+    #[error("the k-mer size must be at least one")]
+    ZeroKmerSize,
 }
@@ -95,6 +95,92 @@ pub struct GenericEdge {
     pub to_side: bool,
 }
 
+/// An iterator adaptor over [`GenericNode`]s that removes redundant edges from each node.
+///
+/// An edge is redundant if the same edge or its mirror was already returned for the node.
+/// The mirror of an edge stored at a node is only stored at the same node if the edge is a self-loop,
+/// so besides exact duplicates only one of the two directions of a self-loop is kept.
+/// Edges to other nodes are never removed, since the converters rely on them to connect the nodes.
+#[derive(Debug, Clone)]
+pub struct CanonicalEdgeFilter<Iter> {
+    iter: Iter,
+}
+
+impl<Iter: Iterator> CanonicalEdgeFilter<Iter>
+where
+    Iter::Item: GenericNode,
+{
+    /// Wrap the given nodes into a canonical edge filter.
+    pub fn new(nodes: impl IntoIterator<IntoIter = Iter>) -> Self {
+        Self {
+            iter: nodes.into_iter(),
+        }
+    }
+}
+
+impl<Iter: Iterator> Iterator for CanonicalEdgeFilter<Iter>
+where
+    Iter::Item: GenericNode,
+{
+    type Item = CanonicalNode<Iter::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.iter.next()?;
+        let id = node.id();
+        let mut edges: Vec<GenericEdge> = Vec::new();
+        for edge in node.edges() {
+            let mirror_edge = GenericEdge {
+                from_side: !edge.to_side,
+                to_node: id,
+                to_side: !edge.from_side,
+            };
+            let is_redundant =
+                edges.contains(&edge) || (edge.to_node == id && edges.contains(&mirror_edge));
+            if !is_redundant {
+                edges.push(edge);
+            }
+        }
+
+        Some(CanonicalNode { node, edges })
+    }
+}
+
+/// A node returned by [`CanonicalEdgeFilter`], whose edges are free of redundancy.
+///
+/// To convert it into edge data, implement `From<CanonicalNode<Node>>` for the edge data,
+/// e.g. by delegating to the conversion from [`CanonicalNode::into_inner`].
+#[derive(Debug, Clone)]
+pub struct CanonicalNode<Node> {
+    node: Node,
+    edges: Vec<GenericEdge>,
+}
+
+impl<Node> CanonicalNode<Node> {
+    /// Returns the wrapped node.
+    pub fn inner(&self) -> &Node {
+        &self.node
+    }
+
+    /// Returns the wrapped node, which still contains the redundant edges.
+    pub fn into_inner(self) -> Node {
+        self.node
+    }
+}
+
+impl<Node: GenericNode> GenericNode for CanonicalNode<Node> {
+    fn id(&self) -> usize {
+        self.node.id()
+    }
+
+    fn is_self_complemental(&self) -> bool {
+        self.node.is_self_complemental()
+    }
+
+    fn edges(&self) -> impl Iterator<Item = GenericEdge> {
+        self.edges.iter().copied()
+    }
+}
+
 /// Read a genome graph in bcalm2 fasta format into an edge-centric representation.
 pub fn convert_generic_node_centric_bigraph_to_edge_centric<
     GenomeSequenceStoreHandle,
@@ -293,3 +379,123 @@ where
 
     Ok(graph)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::generic::{
+        convert_generic_node_centric_bigraph_to_edge_centric, CanonicalEdgeFilter, CanonicalNode,
+        GenericEdge, GenericNode,
+    };
+    use bigraph::implementation::node_bigraph_wrapper::NodeBigraphWrapper;
+    use bigraph::interface::BidirectedData;
+    use bigraph::traitgraph::implementation::petgraph_impl::PetGraph;
+    use bigraph::traitgraph::interface::ImmutableGraphContainer;
+
+    #[derive(Debug, Clone)]
+    struct TestNode {
+        id: usize,
+        edges: Vec<GenericEdge>,
+    }
+
+    impl GenericNode for TestNode {
+        fn id(&self) -> usize {
+            self.id
+        }
+
+        fn is_self_complemental(&self) -> bool {
+            false
+        }
+
+        fn edges(&self) -> impl Iterator<Item = GenericEdge> {
+            self.edges.iter().copied()
+        }
+    }
+
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    struct TestEdgeData {
+        id: usize,
+        forwards: bool,
+    }
+
+    impl From<TestNode> for TestEdgeData {
+        fn from(node: TestNode) -> Self {
+            Self {
+                id: node.id,
+                forwards: true,
+            }
+        }
+    }
+
+    impl From<CanonicalNode<TestNode>> for TestEdgeData {
+        fn from(node: CanonicalNode<TestNode>) -> Self {
+            node.into_inner().into()
+        }
+    }
+
+    impl BidirectedData for TestEdgeData {
+        fn mirror(&self) -> Self {
+            Self {
+                id: self.id,
+                forwards: !self.forwards,
+            }
+        }
+    }
+
+    type TestGraph = NodeBigraphWrapper<PetGraph<(), TestEdgeData>>;
+
+    fn edge(from_side: bool, to_node: usize, to_side: bool) -> GenericEdge {
+        GenericEdge {
+            from_side,
+            to_node,
+            to_side,
+        }
+    }
+
+    #[test]
+    fn test_canonical_edge_filter() {
+        let clean = vec![
+            TestNode {
+                id: 0,
+                edges: vec![edge(true, 1, true)],
+            },
+            TestNode {
+                id: 1,
+                edges: vec![edge(false, 0, false), edge(true, 1, true)],
+            },
+        ];
+        let noisy = vec![
+            TestNode {
+                id: 0,
+                edges: vec![edge(true, 1, true), edge(true, 1, true)],
+            },
+            TestNode {
+                id: 1,
+                edges: vec![
+                    edge(false, 0, false),
+                    edge(true, 1, true),
+                    edge(false, 1, false),
+                ],
+            },
+        ];
+
+        let filtered: Vec<_> = CanonicalEdgeFilter::new(noisy).collect();
+        assert_eq!(filtered[0].edges().collect::<Vec<_>>(), clean[0].edges);
+        assert_eq!(filtered[1].edges().collect::<Vec<_>>(), clean[1].edges);
+
+        let expected: TestGraph =
+            convert_generic_node_centric_bigraph_to_edge_centric::<(), _, _, _, _>(clean).unwrap();
+        let graph: TestGraph =
+            convert_generic_node_centric_bigraph_to_edge_centric::<(), _, _, _, _>(filtered)
+                .unwrap();
+
+        assert_eq!(graph.node_count(), expected.node_count());
+        assert_eq!(graph.edge_count(), expected.edge_count());
+        for (edge, expected_edge) in graph.edge_indices().zip(expected.edge_indices()) {
+            assert_eq!(
+                graph.edge_endpoints(edge),
+                expected.edge_endpoints(expected_edge)
+            );
+            assert_eq!(graph.edge_data(edge), expected.edge_data(expected_edge));
+        }
+    }
+}
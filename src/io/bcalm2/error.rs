@@ -40,4 +40,41 @@ pub enum BCalm2IoError {
 
     #[error("edge has no mirror")]
     BCalm2EdgeWithoutMirror,
+
+    #[error("node id {id} does not fit into a compact bcalm2 edge")]
+    BCalm2CompactEdgeNodeIdTooLarge { id: usize },
+
+    #[error("multiple nodes have id {id}")]
+    BCalm2DuplicateNodeId { id: usize },
+
+    #[error("no record with id {id}")]
+    BCalm2MissingRecord { id: usize },
+
+    #[error("the input contains no records")]
+    BCalm2EmptyInput,
+
+    #[error("the total abundance of node {id} is smaller than its mean abundance")]
+    BCalm2AbundanceInconsistency { id: usize },
+
+    #[error("node {from} links to node {to}, but node {to} does not link back")]
+    BCalm2AsymmetricLink { from: usize, to: usize },
+
+    #[error("the sequence of node {id} is invalid: {error}")]
+    BCalm2InvalidSequence { id: usize, error: String },
+
+    #[error("the sequence of node '{id}' is empty after trimming terminal Ns")]
+    BCalm2EmptySequenceAfterTrimming { id: String },
+
+    #[error("the bcalm2 fasta and gfa files differ: {description}")]
+    BCalm2GfaMismatch { description: String },
+
+    #[error("the sequence of node {id} has length {length}, which is shorter than k = {kmer_size}, is the k-mer size correct?")]
+    BCalm2SequenceTooShort {
+        id: usize,
+        length: usize,
+        kmer_size: usize,
+    },
+
+    #[error("node {from} links to node {to}, but their sequences do not overlap")]
+    BCalm2MissingOverlap { from: usize, to: usize },
 }
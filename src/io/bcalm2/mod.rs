@@ -1,19 +1,28 @@
+use crate::algorithms::diff::canonical_unitig_graph;
+use crate::algorithms::transform::compact_unitigs;
 use crate::bigraph::interface::dynamic_bigraph::DynamicEdgeCentricBigraph;
 use crate::bigraph::interface::dynamic_bigraph::DynamicNodeCentricBigraph;
 use crate::generic::MappedNode;
-use crate::io::SequenceData;
+use crate::io::gfa::{read_gfa_as_bigraph, BidirectedGfaEdgeData};
+use crate::io::{maybe_decompress, CommentFilteringReader, RawSequenceData, SequenceData};
+use crate::types::PetBCalm2NodeGraph;
+use bigraph::implementation::node_bigraph_wrapper::NodeBigraphWrapper;
+use bigraph::interface::static_bigraph::StaticEdgeCentricBigraph;
 use bigraph::interface::{dynamic_bigraph::DynamicBigraph, BidirectedData};
+use bigraph::traitgraph::implementation::petgraph_impl::PetGraph;
 use bigraph::traitgraph::index::GraphIndex;
-use bigraph::traitgraph::interface::GraphBase;
+use bigraph::traitgraph::interface::{Edge, GraphBase};
 use bigraph::traitgraph::traitsequence::interface::Sequence;
 use bio::io::fasta::Record;
+use compact_genome::implementation::alphabets::dna_alphabet::DnaAlphabet;
 use compact_genome::implementation::bit_vec_sequence::BitVectorGenome;
+use compact_genome::implementation::DefaultSequenceStore;
 use compact_genome::interface::alphabet::Alphabet;
 use compact_genome::interface::sequence::{GenomeSequence, OwnedGenomeSequence};
 use compact_genome::interface::sequence_store::SequenceStore;
 use error::BCalm2IoError;
-use num_traits::NumCast;
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{Debug, Write};
 use std::fs::File;
 use std::hash::Hash;
@@ -23,13 +32,86 @@ use std::path::Path;
 pub mod error;
 
 /// Node data of a bcalm2 node, containing only the data the is typically needed.
-#[derive(Debug)]
-pub struct BCalm2NodeData {
-    // TODO
+///
+/// In contrast to [`PlainBCalm2NodeData`], this does not store the redundant sequence length
+/// nor the edges, which are expected to be represented by the graph structure.
+#[derive(Debug, Clone)]
+pub struct BCalm2NodeData<GenomeSequenceStoreHandle> {
+    /// The numeric id of the bcalm2 node.
+    pub id: usize,
+    /// The sequence of the bcalm2 node.
+    pub sequence_handle: GenomeSequenceStoreHandle,
+    /// False if the sequence handle points to the reverse complement of this nodes sequence rather than the actual sequence.
+    pub forwards: bool,
+    /// The total k-mer abundance of the sequence of the bcalm2 node.
+    pub total_abundance: Option<usize>,
+    /// The mean k-mer abundance of the sequence of the bcalm2 node.
+    pub mean_abundance: Option<f64>,
+}
+
+impl<GenomeSequenceStoreHandle> From<PlainBCalm2NodeData<GenomeSequenceStoreHandle>>
+    for BCalm2NodeData<GenomeSequenceStoreHandle>
+{
+    fn from(data: PlainBCalm2NodeData<GenomeSequenceStoreHandle>) -> Self {
+        Self {
+            id: data.id,
+            sequence_handle: data.sequence_handle,
+            forwards: data.forwards,
+            total_abundance: data.total_abundance,
+            mean_abundance: data.mean_abundance,
+        }
+    }
+}
+
+impl<GenomeSequenceStoreHandle: Clone> BidirectedData
+    for BCalm2NodeData<GenomeSequenceStoreHandle>
+{
+    fn mirror(&self) -> Self {
+        let mut result = self.clone();
+        result.forwards = !result.forwards;
+        result
+    }
+}
+
+impl<AlphabetType: Alphabet, GenomeSequenceStore: SequenceStore<AlphabetType>>
+    SequenceData<AlphabetType, GenomeSequenceStore>
+    for BCalm2NodeData<GenomeSequenceStore::Handle>
+{
+    fn sequence_handle(&self) -> &GenomeSequenceStore::Handle {
+        &self.sequence_handle
+    }
+
+    fn sequence_ref<'this: 'result, 'store: 'result, 'result>(
+        &'this self,
+        source_sequence_store: &'store GenomeSequenceStore,
+    ) -> Option<&'result <GenomeSequenceStore as SequenceStore<AlphabetType>>::SequenceRef> {
+        if self.forwards {
+            Some(source_sequence_store.get(&self.sequence_handle))
+        } else {
+            None
+        }
+    }
+
+    fn sequence_owned<
+        ResultSequence: OwnedGenomeSequence<AlphabetType, ResultSubsequence>,
+        ResultSubsequence: GenomeSequence<AlphabetType, ResultSubsequence> + ?Sized,
+    >(
+        &self,
+        source_sequence_store: &GenomeSequenceStore,
+    ) -> ResultSequence {
+        if self.forwards {
+            source_sequence_store.get(&self.sequence_handle).convert()
+        } else {
+            source_sequence_store
+                .get(&self.sequence_handle)
+                .convert_with_reverse_complement()
+        }
+    }
 }
 
 /// The raw node data of a bcalm2 node, including edge information and redundant information (sequence length).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlainBCalm2NodeData<GenomeSequenceStoreHandle> {
     /// The numeric id of the bcalm2 node.
     pub id: usize,
@@ -43,12 +125,21 @@ pub struct PlainBCalm2NodeData<GenomeSequenceStoreHandle> {
     pub total_abundance: Option<usize>,
     /// The mean k-mer abundance of the sequence of the bcalm2 node.
     pub mean_abundance: Option<f64>,
+    /// The colors of the bcalm2 node, i.e. the ids of the samples that contain its sequence, as given by the `CL:Z:` tag.
+    pub colors: Option<Vec<usize>>,
+    /// The unmodified description of the fasta record of the bcalm2 node,
+    /// if it was read with [`BCalm2ReadOptions::preserve_raw_description`].
+    pub raw_description: Option<String>,
     /// The edges stored at the bcalm2 node.
     pub edges: Vec<PlainBCalm2Edge>,
 }
 
 /// The raw edge information of a bcalm2 node.
+///
+/// Edges are ordered like the links written by the writers: first all edges leaving the `+` side, then all edges leaving the `-` side.
+/// Edges leaving the same side are ordered by `to_node`, then by `to_side`, where `-` orders before `+`.
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlainBCalm2Edge {
     /// `true` means `+`, `false` means `-´
     from_side: bool,
@@ -57,6 +148,109 @@ pub struct PlainBCalm2Edge {
     to_side: bool,
 }
 
+impl Ord for PlainBCalm2Edge {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .from_side
+            .cmp(&self.from_side)
+            .then(self.to_node.cmp(&other.to_node))
+            .then(self.to_side.cmp(&other.to_side))
+    }
+}
+
+impl PartialOrd for PlainBCalm2Edge {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The raw edge information of a bcalm2 node packed into a single `u64`.
+///
+/// The two highest bits store the orientations, and the remaining 62 bits store the id of the target node.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct CompactBCalm2Edge(u64);
+
+impl CompactBCalm2Edge {
+    /// The largest node id that can be stored in a compact edge.
+    pub const MAX_NODE_ID: u64 = (1 << 62) - 1;
+    const FROM_SIDE_BIT: u64 = 1 << 63;
+    const TO_SIDE_BIT: u64 = 1 << 62;
+
+    /// Creates a new compact edge.
+    ///
+    /// Panics if `to_node` is larger than [`Self::MAX_NODE_ID`].
+    /// Converting a [`PlainBCalm2Edge`] with `try_from` returns an error instead.
+    pub fn new(from_side: bool, to_node: usize, to_side: bool) -> Self {
+        assert!(
+            to_node as u64 <= Self::MAX_NODE_ID,
+            "node id {to_node} does not fit into a compact bcalm2 edge"
+        );
+        let mut value = to_node as u64;
+        if from_side {
+            value |= Self::FROM_SIDE_BIT;
+        }
+        if to_side {
+            value |= Self::TO_SIDE_BIT;
+        }
+        Self(value)
+    }
+
+    /// `true` means `+`, `false` means `-´
+    pub fn from_side(&self) -> bool {
+        self.0 & Self::FROM_SIDE_BIT != 0
+    }
+
+    /// The id of the node this edge points to.
+    pub fn to_node(&self) -> usize {
+        (self.0 & Self::MAX_NODE_ID) as usize
+    }
+
+    /// `true` means `+`, `false` means `-´
+    pub fn to_side(&self) -> bool {
+        self.0 & Self::TO_SIDE_BIT != 0
+    }
+}
+
+impl PlainBCalm2Edge {
+    /// `true` means `+`, `false` means `-´
+    pub fn from_side(&self) -> bool {
+        self.from_side
+    }
+
+    /// The id of the node this edge points to.
+    pub fn to_node(&self) -> usize {
+        self.to_node
+    }
+
+    /// `true` means `+`, `false` means `-´
+    pub fn to_side(&self) -> bool {
+        self.to_side
+    }
+}
+
+impl TryFrom<PlainBCalm2Edge> for CompactBCalm2Edge {
+    type Error = crate::error::Error;
+
+    /// Fails if the target node id is larger than [`CompactBCalm2Edge::MAX_NODE_ID`].
+    fn try_from(edge: PlainBCalm2Edge) -> crate::error::Result<Self> {
+        if edge.to_node as u64 > Self::MAX_NODE_ID {
+            Err(BCalm2IoError::BCalm2CompactEdgeNodeIdTooLarge { id: edge.to_node }.into())
+        } else {
+            Ok(Self::new(edge.from_side, edge.to_node, edge.to_side))
+        }
+    }
+}
+
+impl From<CompactBCalm2Edge> for PlainBCalm2Edge {
+    fn from(edge: CompactBCalm2Edge) -> Self {
+        Self {
+            from_side: edge.from_side(),
+            to_node: edge.to_node(),
+            to_side: edge.to_side(),
+        }
+    }
+}
+
 impl<GenomeSequenceStoreHandle: Default> Default
     for PlainBCalm2NodeData<GenomeSequenceStoreHandle>
 {
@@ -68,6 +262,8 @@ impl<GenomeSequenceStoreHandle: Default> Default
             length: None,
             total_abundance: None,
             mean_abundance: None,
+            colors: None,
+            raw_description: None,
             edges: Vec::new(),
         }
     }
@@ -128,6 +324,113 @@ impl<AlphabetType: Alphabet, GenomeSequenceStore: SequenceStore<AlphabetType>>
     }
 }
 
+impl<GenomeSequenceStoreHandle> PlainBCalm2NodeData<GenomeSequenceStoreHandle> {
+    /// Returns this node data with its sequence handle replaced by the given handle.
+    pub fn with_sequence_handle<Handle>(
+        self,
+        sequence_handle: Handle,
+    ) -> PlainBCalm2NodeData<Handle> {
+        PlainBCalm2NodeData {
+            id: self.id,
+            sequence_handle,
+            forwards: self.forwards,
+            length: self.length,
+            total_abundance: self.total_abundance,
+            mean_abundance: self.mean_abundance,
+            colors: self.colors,
+            raw_description: self.raw_description,
+            edges: self.edges,
+        }
+    }
+
+    /// Returns an iterator over the k-mers of the sequence of this node in canonical form.
+    ///
+    /// The k-mers are taken from the sequence in the orientation of this node,
+    /// and for each k-mer the lexicographically smaller of itself and its reverse complement is returned.
+    /// If the sequence is shorter than `kmer_size`, then the iterator is empty.
+    pub fn canonical_kmers<
+        AlphabetType: Alphabet + 'static,
+        GenomeSequenceStore: SequenceStore<AlphabetType, Handle = GenomeSequenceStoreHandle>,
+    >(
+        &self,
+        source_sequence_store: &GenomeSequenceStore,
+        kmer_size: usize,
+    ) -> impl Iterator<Item = BitVectorGenome<AlphabetType>> {
+        let sequence: BitVectorGenome<AlphabetType> =
+            <Self as SequenceData<AlphabetType, GenomeSequenceStore>>::sequence_owned(
+                self,
+                source_sequence_store,
+            );
+        let kmer_count = (sequence.len() + 1).saturating_sub(kmer_size);
+
+        (0..kmer_count).map(move |offset| {
+            let kmer: BitVectorGenome<AlphabetType> = sequence
+                .suffix(sequence.len() - offset)
+                .prefix(kmer_size)
+                .convert();
+            let reverse_complement = kmer.clone_as_reverse_complement();
+
+            if reverse_complement.clone_as_vec() < kmer.clone_as_vec() {
+                reverse_complement
+            } else {
+                kmer
+            }
+        })
+    }
+
+    /// Returns the fraction of `G` and `C` characters in the sequence of this node.
+    ///
+    /// Returns `None` if the alphabet is not a nucleotide alphabet, or if the sequence is empty.
+    pub fn gc_content<
+        AlphabetType: Alphabet,
+        GenomeSequenceStore: SequenceStore<AlphabetType, Handle = GenomeSequenceStoreHandle>,
+    >(
+        &self,
+        source_sequence_store: &GenomeSequenceStore,
+    ) -> Option<f64> {
+        let (gc_count, length) = self.gc_count(source_sequence_store)?;
+        if length == 0 {
+            None
+        } else {
+            Some(gc_count as f64 / length as f64)
+        }
+    }
+
+    /// Returns the number of `G` and `C` characters in the sequence of this node, together with its length.
+    ///
+    /// Since the reverse complement has the same amount of `G` and `C` characters,
+    /// the orientation of the node does not matter.
+    pub(crate) fn gc_count<
+        AlphabetType: Alphabet,
+        GenomeSequenceStore: SequenceStore<AlphabetType, Handle = GenomeSequenceStoreHandle>,
+    >(
+        &self,
+        source_sequence_store: &GenomeSequenceStore,
+    ) -> Option<(usize, usize)> {
+        if !is_nucleotide_alphabet::<AlphabetType>() {
+            return None;
+        }
+
+        let sequence = source_sequence_store
+            .get(&self.sequence_handle)
+            .clone_as_vec();
+        let gc_count = sequence
+            .iter()
+            .filter(|&&character| character == b'G' || character == b'C')
+            .count();
+        Some((gc_count, sequence.len()))
+    }
+}
+
+/// Returns true if the alphabet contains `C` and `G`, and all its upper case letters are nucleotides.
+fn is_nucleotide_alphabet<AlphabetType: Alphabet>() -> bool {
+    AlphabetType::ascii_to_character(b'C').is_ok()
+        && AlphabetType::ascii_to_character(b'G').is_ok()
+        && (b'A'..=b'Z').all(|ascii| {
+            b"ACGTUN".contains(&ascii) || AlphabetType::ascii_to_character(ascii).is_err()
+        })
+}
+
 impl<GenomeSequenceStoreHandle: PartialEq> PartialEq
     for PlainBCalm2NodeData<GenomeSequenceStoreHandle>
 {
@@ -138,6 +441,123 @@ impl<GenomeSequenceStoreHandle: PartialEq> PartialEq
 
 impl<GenomeSequenceStoreHandle: Eq> Eq for PlainBCalm2NodeData<GenomeSequenceStoreHandle> {}
 
+/// A bcalm2 record that owns its sequence, such that it can be cached or transmitted without a sequence store.
+#[derive(Debug, Clone)]
+pub struct OwnedBCalm2Record<AlphabetType: Alphabet> {
+    /// The data of the record, without a sequence handle.
+    pub data: PlainBCalm2NodeData<()>,
+    /// The sequence of the record as stored in a sequence store.
+    /// If `data.forwards` is false, then this is the reverse complement of the sequence of the record.
+    pub sequence: BitVectorGenome<AlphabetType>,
+}
+
+impl<AlphabetType: Alphabet> OwnedBCalm2Record<AlphabetType> {
+    /// Creates an owned record from the given node data by copying its sequence out of the sequence store.
+    pub fn from_node_data<GenomeSequenceStore: SequenceStore<AlphabetType>>(
+        data: PlainBCalm2NodeData<GenomeSequenceStore::Handle>,
+        source_sequence_store: &GenomeSequenceStore,
+    ) -> Self {
+        let sequence = source_sequence_store.get(&data.sequence_handle).convert();
+        Self {
+            data: data.with_sequence_handle(()),
+            sequence,
+        }
+    }
+
+    /// Converts this record into node data by adding its sequence to the sequence store.
+    pub fn into_node_data<GenomeSequenceStore: SequenceStore<AlphabetType>>(
+        self,
+        target_sequence_store: &mut GenomeSequenceStore,
+    ) -> PlainBCalm2NodeData<GenomeSequenceStore::Handle> {
+        let sequence_handle = target_sequence_store
+            .add_from_slice_u8(&self.sequence.clone_as_vec())
+            .expect("owned sequence consists of characters from the alphabet");
+        self.data.with_sequence_handle(sequence_handle)
+    }
+
+    /// Returns the sequence of the record in its orientation.
+    pub fn oriented_sequence(&self) -> BitVectorGenome<AlphabetType> {
+        if self.data.forwards {
+            self.sequence.clone()
+        } else {
+            self.sequence.clone_as_reverse_complement()
+        }
+    }
+}
+
+/// The prefixes of the tags in the description of a bcalm2 record, excluding the variable-length `L:` tag.
+const BCALM2_TAG_PREFIXES: [&str; 5] = ["LN:i:", "KC:i:", "KM:f:", "km:f:", "CL:Z:"];
+
+/// Adds the sequences of records to a sequence store, reusing the handles of previously added sequences if enabled.
+struct SequenceDeduplicator<GenomeSequenceStoreHandle> {
+    handles: Option<HashMap<Vec<u8>, GenomeSequenceStoreHandle>>,
+}
+
+impl<GenomeSequenceStoreHandle: Clone> SequenceDeduplicator<GenomeSequenceStoreHandle> {
+    fn new(enabled: bool) -> Self {
+        Self {
+            handles: enabled.then(HashMap::new),
+        }
+    }
+
+    /// Returns the handle of the given sequence, and false if the handle points to its reverse complement.
+    fn add<
+        AlphabetType: Alphabet + 'static,
+        GenomeSequenceStore: SequenceStore<AlphabetType, Handle = GenomeSequenceStoreHandle>,
+    >(
+        &mut self,
+        target_sequence_store: &mut GenomeSequenceStore,
+        id: usize,
+        sequence: &[u8],
+    ) -> crate::error::Result<(GenomeSequenceStoreHandle, bool)> {
+        let Some(handles) = &mut self.handles else {
+            return Ok((
+                add_bcalm2_sequence(target_sequence_store, id, sequence)?,
+                true,
+            ));
+        };
+
+        let genome = BitVectorGenome::<AlphabetType>::from_slice_u8(sequence).map_err(|error| {
+            BCalm2IoError::BCalm2InvalidSequence {
+                id,
+                error: format!("{error:?}"),
+            }
+        })?;
+        let normalized_sequence = genome.clone_as_vec();
+        if let Some(handle) = handles.get(&normalized_sequence) {
+            return Ok((handle.clone(), true));
+        }
+        let reverse_complement = genome.clone_as_reverse_complement().clone_as_vec();
+        if let Some(handle) = handles.get(&reverse_complement) {
+            return Ok((handle.clone(), false));
+        }
+
+        let handle = add_bcalm2_sequence(target_sequence_store, id, sequence)?;
+        handles.insert(normalized_sequence, handle.clone());
+        Ok((handle, true))
+    }
+}
+
+/// Add the sequence of the record with the given id to the sequence store.
+fn add_bcalm2_sequence<
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+>(
+    target_sequence_store: &mut GenomeSequenceStore,
+    id: usize,
+    sequence: &[u8],
+) -> crate::error::Result<GenomeSequenceStore::Handle> {
+    target_sequence_store
+        .add_from_slice_u8(sequence)
+        .map_err(|error| {
+            BCalm2IoError::BCalm2InvalidSequence {
+                id,
+                error: format!("{error:?}"),
+            }
+            .into()
+        })
+}
+
 fn parse_bcalm2_fasta_record<
     AlphabetType: Alphabet + 'static,
     GenomeSequenceStore: SequenceStore<AlphabetType>,
@@ -145,31 +565,72 @@ fn parse_bcalm2_fasta_record<
     record: Record,
     target_sequence_store: &mut GenomeSequenceStore,
 ) -> crate::error::Result<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> {
-    let id = record
-        .id()
-        .parse()
-        .map_err(|_| BCalm2IoError::BCalm2IdError {
+    let id = parse_bcalm2_fasta_record_id(&record)?;
+    let sequence_handle = add_bcalm2_sequence(
+        target_sequence_store,
+        id,
+        bcalm2_fasta_record_sequence(&record),
+    )?;
+    let sequence_length = target_sequence_store.get(&sequence_handle).len();
+    parse_bcalm2_fasta_record_description(record, id, sequence_handle, true, sequence_length)
+}
+
+/// Like [`parse_bcalm2_fasta_record`], but adds the sequence via the given deduplicator.
+fn parse_bcalm2_fasta_record_deduplicated<
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+>(
+    record: Record,
+    target_sequence_store: &mut GenomeSequenceStore,
+    deduplicator: &mut SequenceDeduplicator<GenomeSequenceStore::Handle>,
+) -> crate::error::Result<PlainBCalm2NodeData<GenomeSequenceStore::Handle>>
+where
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    let id = parse_bcalm2_fasta_record_id(&record)?;
+    let (sequence_handle, forwards) = deduplicator.add(
+        target_sequence_store,
+        id,
+        bcalm2_fasta_record_sequence(&record),
+    )?;
+    let sequence_length = target_sequence_store.get(&sequence_handle).len();
+    parse_bcalm2_fasta_record_description(record, id, sequence_handle, forwards, sequence_length)
+}
+
+fn parse_bcalm2_fasta_record_id(record: &Record) -> crate::error::Result<usize> {
+    // Files with Windows line endings may leave a trailing carriage return in the header or sequence.
+    record.id().trim_end_matches('\r').parse().map_err(|_| {
+        BCalm2IoError::BCalm2IdError {
             id: record.id().to_owned(),
-        })?;
-    let sequence_handle = target_sequence_store
-        .add_from_slice_u8(record.seq())
-        .unwrap_or_else(|error| panic!("Genome sequence with id {id} is invalid: {error:?}"));
-    let sequence = target_sequence_store.get(&sequence_handle);
+        }
+        .into()
+    })
+}
+
+fn bcalm2_fasta_record_sequence(record: &Record) -> &[u8] {
+    record.seq().strip_suffix(b"\r").unwrap_or(record.seq())
+}
 
+/// Parse the description of a bcalm2 fasta record whose sequence was already stored under `sequence_handle`.
+/// The `LN` tag is checked against `sequence_length`.
+fn parse_bcalm2_fasta_record_description<GenomeSequenceStoreHandle>(
+    record: Record,
+    id: usize,
+    sequence_handle: GenomeSequenceStoreHandle,
+    forwards: bool,
+    sequence_length: usize,
+) -> crate::error::Result<PlainBCalm2NodeData<GenomeSequenceStoreHandle>> {
     let mut length = None;
     let mut total_abundance = None;
     let mut mean_abundance = None;
+    let mut colors = None;
     let mut edges = Vec::new();
 
+    // `split_whitespace` also splits off a trailing carriage return.
     for parameter in record.desc().unwrap_or("").split_whitespace() {
-        if parameter.len() < 5 {
-            return Err(BCalm2IoError::BCalm2UnknownParameterError {
-                parameter: parameter.to_string(),
-            }
-            .into());
-        }
-        match &parameter[0..5] {
-            "LN:i:" => {
+        // Use `get` for slicing, since stray non-ASCII characters may not end at a char boundary.
+        match parameter.get(0..5) {
+            Some("LN:i:") => {
                 if length.is_some() {
                     return Err(BCalm2IoError::BCalm2DuplicateParameterError {
                         parameter: parameter.to_string(),
@@ -183,7 +644,7 @@ fn parse_bcalm2_fasta_record<
                     }
                 })?);
             }
-            "KC:i:" => {
+            Some("KC:i:") => {
                 if total_abundance.is_some() {
                     return Err(BCalm2IoError::BCalm2DuplicateParameterError {
                         parameter: parameter.to_string(),
@@ -196,7 +657,7 @@ fn parse_bcalm2_fasta_record<
                     }
                 })?);
             }
-            "KM:f:" | "km:f:" => {
+            Some("KM:f:" | "km:f:") => {
                 if mean_abundance.is_some() {
                     return Err(BCalm2IoError::BCalm2DuplicateParameterError {
                         parameter: parameter.to_string(),
@@ -209,50 +670,77 @@ fn parse_bcalm2_fasta_record<
                     }
                 })?);
             }
-            _ => match &parameter[0..2] {
-                "L:" => {
-                    let parts: Vec<_> = parameter.split(':').collect();
-                    if parts.len() != 4 {
-                        return Err(BCalm2IoError::BCalm2MalformedParameterError {
-                            parameter: parameter.to_string(),
-                        }
-                        .into());
+            Some("CL:Z:") => {
+                if colors.is_some() {
+                    return Err(BCalm2IoError::BCalm2DuplicateParameterError {
+                        parameter: parameter.to_string(),
                     }
-                    let forward_reverse_to_bool = |c| match c {
-                        "+" => Ok(true),
-                        "-" => Ok(false),
-                        _ => Err(BCalm2IoError::BCalm2MalformedParameterError {
-                            parameter: parameter.to_owned(),
-                        }),
-                    };
-                    let from_side = forward_reverse_to_bool(parts[1])?;
-                    let to_node = parts[2].parse().map_err(|_| {
-                        BCalm2IoError::BCalm2MalformedParameterError {
-                            parameter: parameter.to_string(),
-                        }
-                    })?;
-                    let to_side = forward_reverse_to_bool(parts[3])?;
-                    edges.push(PlainBCalm2Edge {
-                        from_side,
-                        to_node,
-                        to_side,
-                    });
+                    .into());
                 }
-                _ => {
-                    return Err(BCalm2IoError::BCalm2UnknownParameterError {
+                colors = Some(
+                    parameter[5..]
+                        .split(',')
+                        .filter(|color| !color.is_empty())
+                        .map(|color| color.parse())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| BCalm2IoError::BCalm2MalformedParameterError {
+                            parameter: parameter.to_string(),
+                        })?,
+                );
+            }
+            _ if parameter.starts_with("L:") => {
+                let parts: Vec<_> = parameter.split(':').collect();
+                if parts.len() != 4 {
+                    return Err(BCalm2IoError::BCalm2MalformedParameterError {
                         parameter: parameter.to_string(),
                     }
-                    .into())
+                    .into());
                 }
-            },
+                let forward_reverse_to_bool = |c| match c {
+                    "+" => Ok(true),
+                    "-" => Ok(false),
+                    _ => Err(BCalm2IoError::BCalm2MalformedParameterError {
+                        parameter: parameter.to_owned(),
+                    }),
+                };
+                let from_side = forward_reverse_to_bool(parts[1])?;
+                let to_node =
+                    parts[2]
+                        .parse()
+                        .map_err(|_| BCalm2IoError::BCalm2MalformedParameterError {
+                            parameter: parameter.to_string(),
+                        })?;
+                let to_side = forward_reverse_to_bool(parts[3])?;
+                edges.push(PlainBCalm2Edge {
+                    from_side,
+                    to_node,
+                    to_side,
+                });
+            }
+            // Tokens that are cut off within a tag prefix are treated as malformed tags.
+            _ if BCALM2_TAG_PREFIXES
+                .iter()
+                .any(|prefix| prefix.starts_with(parameter)) =>
+            {
+                return Err(BCalm2IoError::BCalm2MalformedParameterError {
+                    parameter: parameter.to_string(),
+                }
+                .into())
+            }
+            _ => {
+                return Err(BCalm2IoError::BCalm2UnknownParameterError {
+                    parameter: parameter.to_string(),
+                }
+                .into())
+            }
         }
     }
 
     if let Some(length) = length {
-        if length != sequence.len() {
+        if length != sequence_length {
             return Err(BCalm2IoError::BCalm2LengthError {
                 length,
-                sequence_length: sequence.len(),
+                sequence_length,
             }
             .into());
         }
@@ -261,10 +749,12 @@ fn parse_bcalm2_fasta_record<
     Ok(PlainBCalm2NodeData {
         id,
         sequence_handle,
-        forwards: true,
+        forwards,
         length,
         total_abundance,
         mean_abundance,
+        colors,
+        raw_description: None,
         edges,
     })
 }
@@ -277,729 +767,5603 @@ impl<'a, GenomeSequenceStoreHandle: Clone> From<&'a PlainBCalm2NodeData<GenomeSe
     }
 }
 
-/////////////////////////////
-////// NODE CENTRIC IO //////
-/////////////////////////////
+/// Counts of the records and links seen by a filtering bcalm2 reader.
+///
+/// Nodes are counted as bcalm2 records and edges as `L` tags, so mirror nodes and edges added by the reader are not counted.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ReadReport {
+    /// The number of records in the input.
+    pub nodes_read: usize,
+    /// The number of records that passed the filter.
+    pub nodes_kept: usize,
+    /// The number of links in the input.
+    pub edges_read: usize,
+    /// The number of links between records that passed the filter.
+    pub edges_kept: usize,
+}
 
-/// Read a genome graph in bcalm2 fasta format into a node-centric representation from a file.
-pub fn read_bigraph_from_bcalm2_as_node_centric_from_file<
-    P: AsRef<Path> + Debug,
-    AlphabetType: Alphabet + 'static,
-    GenomeSequenceStore: SequenceStore<AlphabetType>,
-    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
-    EdgeData: Default + Clone,
-    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
->(
-    path: P,
-    target_sequence_store: &mut GenomeSequenceStore,
-) -> crate::error::Result<Graph> {
-    read_bigraph_from_bcalm2_as_node_centric(
-        BufReader::new(File::open(path)?),
-        target_sequence_store,
-    )
+/// How the bcalm2 readers handle sequence characters that are not part of the alphabet.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum InvalidCharPolicy {
+    /// Return [`BCalm2IoError::BCalm2InvalidSequence`].
+    #[default]
+    Error,
+    /// Skip records with invalid characters, and drop all links to them.
+    Skip,
+    /// Replace each invalid character with the given character.
+    Replace(u8),
 }
 
-/// Read a genome graph in bcalm2 fasta format into a node-centric representation.
-pub fn read_bigraph_from_bcalm2_as_node_centric<
-    R: std::io::BufRead,
-    AlphabetType: Alphabet + 'static,
-    GenomeSequenceStore: SequenceStore<AlphabetType>,
-    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
-    EdgeData: Default + Clone,
-    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
->(
-    reader: R,
-    target_sequence_store: &mut GenomeSequenceStore,
-) -> crate::error::Result<Graph> {
-    struct BiEdge {
-        from_node: usize,
-        plain_edge: PlainBCalm2Edge,
-    }
+/// How the bcalm2 readers for multiple inputs combine the ids of the records of the different inputs.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum BCalm2IdScope {
+    /// The ids are unique across all inputs, e.g. because each input continues the numbering of the previous inputs.
+    /// Links may connect records of different inputs.
+    #[default]
+    Global,
+    /// Each input numbers its records from zero, and its ids are offset by the number of records in the previous inputs,
+    /// including the target ids of its links.
+    /// Hence, links can only connect records of the same input.
+    PerInput,
+}
 
-    let reader = bio::io::fasta::Reader::new(reader);
-    let mut bigraph = Graph::default();
-    let mut edges = Vec::new();
+/// Options for reading a genome graph in bcalm2 fasta format.
+#[derive(Debug, Clone)]
+pub struct BCalm2ReadOptions {
+    /// If false, an input without records results in an error instead of an empty graph.
+    /// This helps to detect failed upstream steps that produced an empty file.
+    pub allow_empty: bool,
+    /// An estimate of the number of records in the input, used to pre-allocate the internal data structures of the reader.
+    /// The estimate does not need to be exact.
+    /// The graph itself is constructed via [`Default`] and therefore not pre-allocated.
+    pub capacity: usize,
+    /// If true, records whose total abundance is smaller than their mean abundance result in an error.
+    /// Since each record contains at least one k-mer, this indicates corrupted abundance values.
+    pub validate_abundance_consistency: bool,
+    /// If true, leading and trailing runs of `N` are removed from each sequence before it is stored,
+    /// and the `LN` tag is adjusted accordingly.
+    /// A sequence that consists only of `N`s results in an error.
+    pub trim_terminal_ns: bool,
+    /// If true, it is verified after reading that each link has a reciprocal link on its target record,
+    /// and otherwise [`BCalm2IoError::BCalm2AsymmetricLink`] is returned.
+    pub verify_link_symmetry: bool,
+    /// If true, records with identical sequences share the same sequence in the sequence store.
+    /// This includes records whose sequences are reverse complements of each other,
+    /// in which case the later record points to the reverse complement and is marked as not forwards.
+    pub deduplicate_sequences: bool,
+    /// If true, records that have their sequence on the header line are accepted,
+    /// i.e. if the last word of the description consists only of letters and the sequence line is empty,
+    /// then this word is used as the sequence.
+    /// This is not valid fasta, but some tools produce such files.
+    pub lenient: bool,
+    /// How records with characters that are not part of the alphabet are handled.
+    pub invalid_char_policy: InvalidCharPolicy,
+    /// If true, the description of each record is stored in [`PlainBCalm2NodeData::raw_description`],
+    /// such that it can be written back verbatim with [`BCalm2WriteOptions::emit_raw_description`].
+    /// This allows byte-perfect round-trips of files whose tags are not in the canonical order or format.
+    pub preserve_raw_description: bool,
+    /// If true, the edges of each record are sorted in the order of [`PlainBCalm2Edge`],
+    /// such that records do not depend on the order of the links in the input.
+    pub sort_edges_on_read: bool,
+}
 
-    for record in reader.records() {
-        let record: PlainBCalm2NodeData<GenomeSequenceStore::Handle> =
-            parse_bcalm2_fasta_record(record.map_err(BCalm2IoError::from)?, target_sequence_store)?;
-        edges.extend(record.edges.iter().map(|e| BiEdge {
-            from_node: record.id,
-            plain_edge: e.clone(),
-        }));
-        let record_id = record.id;
-        let id = bigraph.add_node(record.into());
-        debug_assert_eq!(id, record_id.into());
+impl Default for BCalm2ReadOptions {
+    fn default() -> Self {
+        Self {
+            allow_empty: true,
+            capacity: 0,
+            validate_abundance_consistency: false,
+            trim_terminal_ns: false,
+            verify_link_symmetry: false,
+            deduplicate_sequences: false,
+            lenient: false,
+            invalid_char_policy: InvalidCharPolicy::Error,
+            preserve_raw_description: false,
+            sort_edges_on_read: false,
+        }
     }
+}
 
-    bigraph.add_mirror_nodes();
-    debug_assert!(bigraph.verify_node_pairing());
+impl BCalm2ReadOptions {
+    /// Modify a fasta record before parsing according to these options.
+    fn prepare_record(&self, mut record: Record) -> crate::error::Result<Record> {
+        if self.lenient {
+            record = Self::split_inline_sequence(record);
+        }
+        if self.trim_terminal_ns {
+            record = Self::trim_terminal_ns(record)?;
+        }
+        Ok(record)
+    }
 
-    for edge in edges {
-        let from_node = if edge.plain_edge.from_side {
-            edge.from_node.into()
-        } else {
-            bigraph.mirror_node(edge.from_node.into()).unwrap()
-        };
-        let to_node = if edge.plain_edge.to_side {
-            edge.plain_edge.to_node.into()
-        } else {
-            bigraph.mirror_node(edge.plain_edge.to_node.into()).unwrap()
-        };
-        bigraph.add_edge(from_node, to_node, EdgeData::default());
+    /// Returns the description of a record to be stored in [`PlainBCalm2NodeData::raw_description`] according to these options.
+    fn raw_description(&self, record: &Record) -> Option<String> {
+        self.preserve_raw_description
+            .then(|| record.desc().unwrap_or("").to_owned())
     }
 
-    bigraph.add_node_centric_mirror_edges();
-    debug_assert!(bigraph.verify_node_mirror_property());
-    Ok(bigraph)
-}
+    /// Apply the policy for invalid characters to a record.
+    /// Returns `None` if the record should be skipped.
+    fn apply_invalid_char_policy<AlphabetType: Alphabet>(&self, record: Record) -> Option<Record> {
+        let is_invalid = |character: &u8| AlphabetType::ascii_to_character(*character).is_err();
+        let sequence = bcalm2_fasta_record_sequence(&record);
+        if !sequence.iter().any(is_invalid) {
+            return Some(record);
+        }
 
-fn write_plain_bcalm2_node_data_to_bcalm2<GenomeSequenceStoreHandle>(
-    node: &PlainBCalm2NodeData<GenomeSequenceStoreHandle>,
-    out_neighbors: Vec<(bool, usize, bool)>,
-) -> crate::error::Result<String> {
-    let mut result = String::new();
+        match self.invalid_char_policy {
+            // The error is reported when the sequence is parsed.
+            InvalidCharPolicy::Error => Some(record),
+            InvalidCharPolicy::Skip => None,
+            InvalidCharPolicy::Replace(replacement) => {
+                let sequence: Vec<_> = sequence
+                    .iter()
+                    .map(|character| {
+                        if is_invalid(character) {
+                            replacement
+                        } else {
+                            *character
+                        }
+                    })
+                    .collect();
+                Some(Record::with_attrs(record.id(), record.desc(), &sequence))
+            }
+        }
+    }
 
-    if let Some(length) = node.length {
-        if !result.is_empty() {
-            write!(result, " ").map_err(BCalm2IoError::from)?;
+    /// Move a sequence that was written on the header line of a record into the sequence of the record.
+    fn split_inline_sequence(record: Record) -> Record {
+        if !record.seq().is_empty() {
+            return record;
         }
-        write!(result, "LN:i:{length}").map_err(BCalm2IoError::from)?;
+        let Some(description) = record.desc() else {
+            return record;
+        };
+        let (description, sequence) = match description.trim_end().rsplit_once(char::is_whitespace)
+        {
+            Some((description, sequence)) => (Some(description.trim_end()), sequence),
+            None => (None, description.trim_end()),
+        };
+        if sequence.is_empty()
+            || !sequence
+                .bytes()
+                .all(|character| character.is_ascii_alphabetic())
+        {
+            return record;
+        }
+
+        Record::with_attrs(record.id(), description, sequence.as_bytes())
     }
 
-    if let Some(total_abundance) = node.total_abundance {
-        if !result.is_empty() {
-            write!(result, " ").map_err(BCalm2IoError::from)?;
+    /// Remove leading and trailing runs of `N` from the sequence of a record and adjust its `LN` tag.
+    fn trim_terminal_ns(record: Record) -> crate::error::Result<Record> {
+        let sequence = record.seq().strip_suffix(b"\r").unwrap_or(record.seq());
+        let is_n = |character: &u8| character.eq_ignore_ascii_case(&b'N');
+        let start = sequence
+            .iter()
+            .position(|character| !is_n(character))
+            .ok_or_else(|| BCalm2IoError::BCalm2EmptySequenceAfterTrimming {
+                id: record.id().to_owned(),
+            })?;
+        let end = sequence
+            .iter()
+            .rposition(|character| !is_n(character))
+            .unwrap()
+            + 1;
+        if start == 0 && end == sequence.len() {
+            return Ok(record);
         }
-        write!(result, "KC:i:{total_abundance}").map_err(BCalm2IoError::from)?;
+
+        let trimmed_length = end - start;
+        let description = record.desc().map(|description| {
+            description
+                .split_whitespace()
+                .map(|parameter| {
+                    if parameter.starts_with("LN:i:") {
+                        format!("LN:i:{trimmed_length}")
+                    } else {
+                        parameter.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        Ok(Record::with_attrs(
+            record.id(),
+            description.as_deref(),
+            &sequence[start..end],
+        ))
     }
 
-    if let Some(mean_abundance) = node.mean_abundance {
-        if !result.is_empty() {
-            write!(result, " ").map_err(BCalm2IoError::from)?;
+    /// Normalize a parsed record according to these options.
+    fn normalize_record<GenomeSequenceStoreHandle>(
+        &self,
+        record: &mut PlainBCalm2NodeData<GenomeSequenceStoreHandle>,
+    ) {
+        if self.sort_edges_on_read {
+            record.edges.sort_unstable();
         }
-        write!(result, "km:f:{mean_abundance:.1}").map_err(BCalm2IoError::from)?;
     }
 
-    for (node_type, neighbor_id, neighbor_type) in out_neighbors {
-        if !result.is_empty() {
-            write!(result, " ").map_err(BCalm2IoError::from)?;
+    /// Check a parsed record according to these options.
+    fn check_record<GenomeSequenceStoreHandle>(
+        &self,
+        record: &PlainBCalm2NodeData<GenomeSequenceStoreHandle>,
+    ) -> crate::error::Result<()> {
+        // Tolerates floating point inaccuracies in the mean abundance.
+        const ABUNDANCE_EPSILON: f64 = 1e-6;
+
+        if self.validate_abundance_consistency {
+            if let (Some(total_abundance), Some(mean_abundance)) =
+                (record.total_abundance, record.mean_abundance)
+            {
+                if (total_abundance as f64) + ABUNDANCE_EPSILON < mean_abundance {
+                    return Err(
+                        BCalm2IoError::BCalm2AbundanceInconsistency { id: record.id }.into(),
+                    );
+                }
+            }
         }
-        write!(
-            result,
-            "L:{}:{}:{}",
-            if node_type { "+" } else { "-" },
-            <usize as NumCast>::from(neighbor_id)
-                .ok_or_else(|| BCalm2IoError::BCalm2NodeIdOutOfPrintingRange)?,
-            if neighbor_type { "+" } else { "-" }
-        )
-        .map_err(BCalm2IoError::from)?;
+        Ok(())
     }
-    Ok(result)
 }
 
-/// Write a genome graph in bcalm2 fasta format from a node-centric representation to a file.
-pub fn write_node_centric_bigraph_to_bcalm2_to_file<
-    P: AsRef<Path>,
-    AlphabetType: Alphabet,
-    GenomeSequenceStore: SequenceStore<AlphabetType>,
-    NodeData, //: Into<PlainBCalm2NodeData<IndexType>>,
-    EdgeData: Default + Clone,
-    Graph: DynamicBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
->(
-    graph: &Graph,
-    source_sequence_store: &GenomeSequenceStore,
-    path: P,
-) -> crate::error::Result<()>
-where
-    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
-{
-    write_node_centric_bigraph_to_bcalm2(
-        graph,
-        source_sequence_store,
-        bio::io::fasta::Writer::to_file(path).map_err(BCalm2IoError::from)?,
-    )
+/// Check that for each link `L:<s>:<to>:<t>` of a record `from` the record `to` has the reciprocal link `L:<!t>:<from>:<!s>`.
+/// The links are given as pairs of the id of the record and the link.
+/// Returns [`BCalm2IoError::BCalm2AsymmetricLink`] for the first link without reciprocal link.
+fn verify_link_symmetry<'a>(
+    links: impl Iterator<Item = (usize, &'a PlainBCalm2Edge)> + Clone,
+) -> crate::error::Result<()> {
+    let link_set: HashSet<_> = links
+        .clone()
+        .map(|(from_node, edge)| (from_node, edge.from_side, edge.to_node, edge.to_side))
+        .collect();
+
+    for (from_node, edge) in links {
+        if !link_set.contains(&(edge.to_node, !edge.to_side, from_node, !edge.from_side)) {
+            return Err(BCalm2IoError::BCalm2AsymmetricLink {
+                from: from_node,
+                to: edge.to_node,
+            }
+            .into());
+        }
+    }
+
+    Ok(())
 }
 
-/// Write a genome graph in bcalm2 fasta format from a node-centric representation.
-pub fn write_node_centric_bigraph_to_bcalm2<
-    W: std::io::Write,
-    AlphabetType: Alphabet,
-    GenomeSequenceStore: SequenceStore<AlphabetType>,
-    NodeData,
-    EdgeData: Default + Clone,
-    Graph: DynamicBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
->(
-    graph: &Graph,
-    source_sequence_store: &GenomeSequenceStore,
-    mut writer: bio::io::fasta::Writer<W>,
-) -> crate::error::Result<()>
-where
-    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
-{
-    let mut output_nodes = vec![false; graph.node_count()];
+/// Options for writing a genome graph in bcalm2 fasta format.
+#[derive(Debug, Clone, Default)]
+pub struct BCalm2WriteOptions {
+    /// If true, the written records are numbered consecutively starting from zero, instead of using the ids stored in the node data.
+    /// The references of the edges are rewritten accordingly.
+    /// This produces a valid file even if the ids in the node data have gaps or collide, e.g. after graph transformations.
+    /// If false, the node-centric writers return an error if two mirror pairs have the same id,
+    /// since the written file would contain duplicate records.
+    pub reassign_ids: bool,
+    /// Selects which element of each mirror pair is written as record, and hence the strand of the written sequence.
+    pub representative: RepresentativeChoice,
+    /// The k-mer size of the graph, required by options that derive data from k-mer counts.
+    pub kmer_size: Option<usize>,
+    /// If true, the mean abundance of records without one is derived as `KC / (LN - k + 1)`,
+    /// if the total abundance and the length are known.
+    /// Requires `kmer_size` to be set.
+    pub derive_mean_abundance: bool,
+    /// If true, the node-centric writers write both nodes of each mirror pair as separate records,
+    /// each record immediately followed by the record of its mirror node.
+    /// The records are numbered consecutively starting from zero, so a mirror pair gets the ids `2i` and `2i + 1`
+    /// unless self-mirror nodes precede it, which are written only once.
+    /// All links of a record point to the `+` side of the target records, and `reassign_ids` is ignored.
+    /// The output is useful for debugging, but does not collapse reverse complements like bcalm2 does.
+    pub emit_both_strands: bool,
+    /// If true, records whose node data contains a [`PlainBCalm2NodeData::raw_description`] are written with that description verbatim,
+    /// instead of a description generated from the node data and the graph.
+    /// This is only correct if the graph was not modified after reading, and is ignored if `reassign_ids` or `emit_both_strands` is set.
+    /// Since the links of a raw description refer to the strands as read, records that are written in reverse complement,
+    /// e.g. due to [`representative`](Self::representative), or that link to such records, are described from the node data instead.
+    pub emit_raw_description: bool,
+    /// If true, the edge-centric writers output the records sorted by the ids stored in the edge data,
+    /// instead of in the order of the edges.
+    /// Only the ids of the records are collected and sorted, together with the indices of their edges,
+    /// which takes two words of memory per record.
+    /// The records themselves are still formatted and written one by one, so the output is never buffered.
+    /// If `reassign_ids` is set as well, then the new ids are assigned in the sorted order.
+    pub sort_by_id: bool,
+}
 
-    for node_id in graph.node_indices() {
-        if !output_nodes[graph
-            .mirror_node(node_id)
-            .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?
-            .as_usize()]
-        {
-            output_nodes[node_id.as_usize()] = true;
+impl BCalm2WriteOptions {
+    fn validate(&self) -> crate::error::Result<()> {
+        if self.derive_mean_abundance && self.kmer_size.is_none() {
+            return Err(BCalm2IoError::BCalm2MissingParameterError {
+                parameter: "kmer_size".to_string(),
+            }
+            .into());
         }
+        Ok(())
     }
 
-    for node_id in graph.node_indices() {
-        if output_nodes[node_id.as_usize()] {
-            let node_data = PlainBCalm2NodeData::from(graph.node_data(node_id));
-            let mirror_node_id = graph
-                .mirror_node(node_id)
-                .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?;
-            /*let mirror_node_data = PlainBCalm2NodeData::<IndexType>::from(
-                graph
-                    .node_data(mirror_node_id)
-                    .ok_or_else(|| Error::from(ErrorKind::BCalm2NodeWithoutMirror))?,
-            );*/
-            let mut out_neighbors_plus = Vec::new();
-            let mut out_neighbors_minus = Vec::new();
-
-            for neighbor in graph.out_neighbors(node_id) {
-                let neighbor_node_id = neighbor.node_id.as_usize();
-
-                out_neighbors_plus.push((
-                    true,
-                    if output_nodes[neighbor_node_id] {
-                        neighbor.node_id.as_usize()
-                    } else {
-                        graph
-                            .mirror_node(neighbor.node_id)
-                            .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?
-                            .as_usize()
-                    },
-                    output_nodes[neighbor_node_id],
-                ));
+    /// Returns the description of the record of a node according to these options.
+    /// The raw description of the node is used only if `strands_unchanged` is true,
+    /// i.e. if the node and all its neighbors are written in the orientation in which they were read.
+    /// If the description is generated from the node data instead, the data of the raw description that is lost is reported in `warnings`.
+    fn describe_node<GenomeSequenceStoreHandle>(
+        &self,
+        node: &PlainBCalm2NodeData<GenomeSequenceStoreHandle>,
+        out_neighbors: Vec<(bool, usize, bool)>,
+        strands_unchanged: bool,
+        warnings: &mut Vec<WriteWarning>,
+    ) -> crate::error::Result<String> {
+        match &node.raw_description {
+            Some(raw_description)
+                if self.emit_raw_description && !self.reassign_ids && strands_unchanged =>
+            {
+                Ok(raw_description.clone())
+            }
+            _ => {
+                self.collect_regenerated_description_warnings(node, warnings);
+                write_plain_bcalm2_node_data_to_bcalm2(node, out_neighbors)
+            }
+        }
+    }
+
+    /// Collect the warnings for a node whose description is generated from the node data, even though it has a raw description.
+    fn collect_regenerated_description_warnings<GenomeSequenceStoreHandle>(
+        &self,
+        node: &PlainBCalm2NodeData<GenomeSequenceStoreHandle>,
+        warnings: &mut Vec<WriteWarning>,
+    ) {
+        if let Some(raw_description) = &node.raw_description {
+            if self.emit_raw_description {
+                warnings.push(WriteWarning::RegeneratedDescription { node_id: node.id });
             }
-            for neighbor in graph.out_neighbors(mirror_node_id) {
-                let neighbor_node_id = neighbor.node_id.as_usize();
 
-                out_neighbors_minus.push((
-                    false,
-                    if output_nodes[neighbor_node_id] {
-                        neighbor.node_id.as_usize()
-                    } else {
-                        graph
-                            .mirror_node(neighbor.node_id)
-                            .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?
-                            .as_usize()
-                    },
-                    output_nodes[neighbor_node_id],
-                ));
+            for tag in raw_description.split_whitespace() {
+                // Links are generated from the graph, so they are never dropped.
+                let is_written = match tag.get(0..5) {
+                    Some("LN:i:") => node.length.is_some(),
+                    Some("KC:i:") => node.total_abundance.is_some(),
+                    Some("KM:f:" | "km:f:") => node.mean_abundance.is_some(),
+                    Some("CL:Z:") => node.colors.is_some(),
+                    _ => tag.starts_with("L:"),
+                };
+                if !is_written {
+                    warnings.push(WriteWarning::DroppedTag {
+                        node_id: node.id,
+                        tag: tag.to_owned(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Fill in the data of a node that can be derived according to these options.
+    fn derive_node_data<GenomeSequenceStoreHandle>(
+        &self,
+        node: &mut PlainBCalm2NodeData<GenomeSequenceStoreHandle>,
+    ) {
+        if let (true, Some(kmer_size), None, Some(total_abundance), Some(length)) = (
+            self.derive_mean_abundance,
+            self.kmer_size,
+            node.mean_abundance,
+            node.total_abundance,
+            node.length,
+        ) {
+            if length >= kmer_size {
+                node.mean_abundance =
+                    Some(total_abundance as f64 / (length - kmer_size + 1) as f64);
             }
+        }
+    }
+}
 
-            out_neighbors_plus.sort_unstable();
-            out_neighbors_minus.sort_unstable();
-            out_neighbors_plus.append(&mut out_neighbors_minus);
-            let out_neighbors = out_neighbors_plus;
+/// Selects which element of a mirror pair the bcalm2 writers output as record.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum RepresentativeChoice {
+    /// Output the element with the lower index.
+    #[default]
+    LowerIndex,
+    /// Output the element whose data is in forwards orientation.
+    /// If both or none of the elements are forwards, the one with the lower index is output.
+    Forward,
+    /// Output the element with the lexicographically smaller sequence.
+    /// If both sequences are equal, the one with the lower index is output.
+    Canonical,
+}
 
-            let mut printed_node_id = String::new();
-            write!(printed_node_id, "{}", node_data.id).map_err(BCalm2IoError::from)?;
-            let node_description =
-                write_plain_bcalm2_node_data_to_bcalm2(&node_data, out_neighbors)?;
-            let node_sequence = source_sequence_store
-                .get(&node_data.sequence_handle)
-                .clone_as_vec();
+/// Data of a node that was not written exactly by a bcalm2 writer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WriteWarning {
+    /// The mean abundance was rounded to one decimal place.
+    RoundedMeanAbundance {
+        /// The id of the node in its node data.
+        node_id: usize,
+        /// The mean abundance before rounding.
+        mean_abundance: f64,
+    },
+    /// A tag of the raw description of the node was not written, because the node data does not contain its value.
+    /// This happens if the raw description contains a tag that is not part of [`PlainBCalm2NodeData`],
+    /// or if the corresponding field was cleared after reading.
+    DroppedTag {
+        /// The id of the node in its node data.
+        node_id: usize,
+        /// The dropped tag as it appears in the raw description.
+        tag: String,
+    },
+    /// The raw description of the node was not written verbatim despite [`BCalm2WriteOptions::emit_raw_description`],
+    /// so the record was described from the node data instead.
+    RegeneratedDescription {
+        /// The id of the node in its node data.
+        node_id: usize,
+    },
+}
 
-            writer
-                .write(&printed_node_id, Some(&node_description), &node_sequence)
-                .map_err(BCalm2IoError::from)?;
+fn collect_write_warnings<GenomeSequenceStoreHandle>(
+    node: &PlainBCalm2NodeData<GenomeSequenceStoreHandle>,
+    warnings: &mut Vec<WriteWarning>,
+) {
+    if let Some(mean_abundance) = node.mean_abundance {
+        if format!("{mean_abundance:.1}").parse::<f64>() != Ok(mean_abundance) {
+            warnings.push(WriteWarning::RoundedMeanAbundance {
+                node_id: node.id,
+                mean_abundance,
+            });
+        }
+    }
+}
+
+/// Returns true if the element with `index` and `data` should be output instead of its mirror.
+fn is_representative<AlphabetType: Alphabet, GenomeSequenceStore: SequenceStore<AlphabetType>>(
+    index: usize,
+    data: &PlainBCalm2NodeData<GenomeSequenceStore::Handle>,
+    mirror_index: usize,
+    mirror_data: &PlainBCalm2NodeData<GenomeSequenceStore::Handle>,
+    source_sequence_store: &GenomeSequenceStore,
+    representative: RepresentativeChoice,
+) -> bool {
+    match representative {
+        RepresentativeChoice::LowerIndex => {}
+        RepresentativeChoice::Forward => {
+            if data.forwards != mirror_data.forwards {
+                return data.forwards;
+            }
+        }
+        RepresentativeChoice::Canonical => {
+            let sequence = oriented_sequence_vec(data, source_sequence_store);
+            let mirror_sequence = oriented_sequence_vec(mirror_data, source_sequence_store);
+            if sequence != mirror_sequence {
+                return sequence < mirror_sequence;
+            }
         }
     }
 
-    Ok(())
+    index <= mirror_index
+}
+
+/// Returns the sequence of the given node data in its orientation as ASCII characters.
+pub(crate) fn oriented_sequence_vec<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+>(
+    data: &PlainBCalm2NodeData<GenomeSequenceStore::Handle>,
+    source_sequence_store: &GenomeSequenceStore,
+) -> Vec<u8> {
+    let sequence = source_sequence_store.get(&data.sequence_handle);
+    if data.forwards {
+        sequence.clone_as_vec()
+    } else {
+        sequence
+            .reverse_complement_iter()
+            .map(|c| c.into())
+            .collect()
+    }
 }
 
 /////////////////////////////
-////// EDGE CENTRIC IO //////
+////// NODE CENTRIC IO //////
 /////////////////////////////
 
-/// Read a genome graph in bcalm2 fasta format into an edge-centric representation from a file.
-pub fn read_bigraph_from_bcalm2_as_edge_centric_from_file<
+/// Read a genome graph in bcalm2 fasta format into a node-centric representation from a file.
+pub fn read_bigraph_from_bcalm2_as_node_centric_from_file<
     P: AsRef<Path> + Debug,
-    AlphabetType: Alphabet + 'static + Hash + Eq + Clone,
+    AlphabetType: Alphabet + 'static,
     GenomeSequenceStore: SequenceStore<AlphabetType>,
-    NodeData: Default + Clone,
-    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
-    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
 >(
     path: P,
     target_sequence_store: &mut GenomeSequenceStore,
-    kmer_size: usize,
 ) -> crate::error::Result<Graph>
 where
     <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
 {
-    read_bigraph_from_bcalm2_as_edge_centric(
-        BufReader::new(File::open(path)?),
+    read_bigraph_from_bcalm2_as_node_centric(
+        maybe_decompress(BufReader::new(File::open(path)?))?,
         target_sequence_store,
-        kmer_size,
     )
 }
 
-fn get_or_create_node<
-    Graph: DynamicBigraph,
-    AlphabetType: Alphabet,
-    Genome: OwnedGenomeSequence<AlphabetType, GenomeSubsequence> + Hash + Eq + Clone,
-    GenomeSubsequence: GenomeSequence<AlphabetType, GenomeSubsequence> + ?Sized,
+/// Read a genome graph in bcalm2 fasta format into a node-centric representation from stdin.
+///
+/// Stdin is locked for the duration of the read, and gzip-compressed input is decompressed like for files.
+/// Since stdin cannot be re-read, the consumed input is lost if reading fails.
+pub fn read_bigraph_from_bcalm2_as_node_centric_from_stdin<
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
 >(
-    bigraph: &mut Graph,
-    id_map: &mut HashMap<Genome, <Graph as GraphBase>::NodeIndex>,
-    genome: Genome,
-) -> <Graph as GraphBase>::NodeIndex
+    target_sequence_store: &mut GenomeSequenceStore,
+) -> crate::error::Result<Graph>
 where
-    <Graph as GraphBase>::NodeData: Default,
-    <Graph as GraphBase>::EdgeData: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
 {
-    if let Some(node) = id_map.get(&genome) {
-        *node
-    } else {
-        let node = bigraph.add_node(Default::default());
-        let reverse_complement = genome.clone_as_reverse_complement();
-
-        if reverse_complement == genome {
-            bigraph.set_mirror_nodes(node, node);
-        } else {
-            let mirror_node = bigraph.add_node(Default::default());
-            id_map.insert(reverse_complement, mirror_node);
-            bigraph.set_mirror_nodes(node, mirror_node);
-        }
-
-        id_map.insert(genome, node);
-
-        node
-    }
+    read_bigraph_from_bcalm2_as_node_centric(
+        maybe_decompress(std::io::stdin().lock())?,
+        target_sequence_store,
+    )
 }
 
-/// Read a genome graph in bcalm2 fasta format into an edge-centric representation.
-#[allow(dead_code)]
-fn read_bigraph_from_bcalm2_as_edge_centric_old<
-    R: std::io::BufRead,
-    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+/// Read a genome graph in bcalm2 fasta format that is split across multiple files into a single node-centric representation.
+///
+/// The ids of the records of the different files are combined as given by `id_scope`.
+/// All files are opened before reading, and each file may be gzip-compressed.
+pub fn read_bigraph_from_bcalm2_as_node_centric_from_files<
+    P: AsRef<Path>,
+    AlphabetType: Alphabet + 'static,
     GenomeSequenceStore: SequenceStore<AlphabetType>,
-    NodeData: Default + Clone,
-    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
-    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
 >(
-    reader: R,
+    paths: &[P],
     target_sequence_store: &mut GenomeSequenceStore,
-    kmer_size: usize,
+    id_scope: BCalm2IdScope,
 ) -> crate::error::Result<Graph>
 where
-    <Graph as GraphBase>::NodeIndex: Clone,
     <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
 {
-    let reader = bio::io::fasta::Reader::new(reader);
-    let mut bigraph = Graph::default();
-    let mut id_map = HashMap::new();
-    let node_kmer_size = kmer_size - 1;
-
-    for record in reader.records() {
-        let record: PlainBCalm2NodeData<GenomeSequenceStore::Handle> =
-            parse_bcalm2_fasta_record(record.map_err(BCalm2IoError::from)?, target_sequence_store)?;
-        let sequence = target_sequence_store.get(&record.sequence_handle);
-        let prefix = sequence.prefix(node_kmer_size);
-        let suffix = sequence.suffix(node_kmer_size);
+    let readers = paths
+        .iter()
+        .map(|path| maybe_decompress(BufReader::new(File::open(path)?)))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    read_bigraph_from_bcalm2_as_node_centric_from_readers(readers, target_sequence_store, id_scope)
+}
 
-        let pre_plus: BitVectorGenome<AlphabetType> = prefix.convert();
-        let pre_minus: BitVectorGenome<AlphabetType> = suffix.convert_with_reverse_complement();
-        let succ_plus: BitVectorGenome<AlphabetType> = suffix.convert();
-        let succ_minus: BitVectorGenome<AlphabetType> = prefix.convert_with_reverse_complement();
+/// Read a genome graph in bcalm2 fasta format that is split across multiple readers into a single node-centric representation.
+///
+/// The records of all readers are concatenated in order,
+/// and the ids of the records of the different readers are combined as given by `id_scope`.
+pub fn read_bigraph_from_bcalm2_as_node_centric_from_readers<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    readers: impl IntoIterator<Item = R>,
+    target_sequence_store: &mut GenomeSequenceStore,
+    id_scope: BCalm2IdScope,
+) -> crate::error::Result<Graph>
+where
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    let mut readers = readers
+        .into_iter()
+        .map(|reader| bio::io::fasta::Reader::new(reader).records());
+    let mut current_records = readers.next();
+    let mut record_count = 0;
+    let mut offset = 0;
+    let records = std::iter::from_fn(move || loop {
+        let records = current_records.as_mut()?;
+        match records.next() {
+            Some(Ok(record)) => {
+                record_count += 1;
+                return Some(Ok(offset_bcalm2_record(record, offset)));
+            }
+            Some(Err(error)) => return Some(Err(error)),
+            None => {
+                current_records = readers.next();
+                if id_scope == BCalm2IdScope::PerInput {
+                    offset = record_count;
+                }
+            }
+        }
+    });
 
-        let pre_plus = get_or_create_node(&mut bigraph, &mut id_map, pre_plus);
-        let pre_minus = get_or_create_node(&mut bigraph, &mut id_map, pre_minus);
-        let succ_plus = get_or_create_node(&mut bigraph, &mut id_map, succ_plus);
-        let succ_minus = get_or_create_node(&mut bigraph, &mut id_map, succ_minus);
+    read_bigraph_from_bcalm2_records_as_node_centric(
+        records,
+        target_sequence_store,
+        &BCalm2ReadOptions::default(),
+        |_, _| EdgeData::default(),
+    )
+}
 
-        bigraph.add_edge(pre_plus, succ_plus, record.clone().into());
-        bigraph.add_edge(pre_minus, succ_minus, record.mirror().into());
+/// Add the given offset to the id of a bcalm2 record and to the target ids of its links.
+///
+/// Ids that cannot be parsed are left unchanged, such that the parser reports them.
+fn offset_bcalm2_record(record: Record, offset: usize) -> Record {
+    if offset == 0 {
+        return record;
     }
 
-    debug_assert!(bigraph.verify_node_pairing());
-    debug_assert!(bigraph.verify_edge_mirror_property());
-    Ok(bigraph)
+    let id = match record.id().parse::<usize>() {
+        Ok(id) => (id + offset).to_string(),
+        Err(_) => record.id().to_string(),
+    };
+    let description = record.desc().map(|description| {
+        description
+            .split_whitespace()
+            .map(|parameter| {
+                let mut columns = parameter.splitn(4, ':');
+                match (
+                    columns.next(),
+                    columns.next(),
+                    columns
+                        .next()
+                        .and_then(|to_node| to_node.parse::<usize>().ok()),
+                    columns.next(),
+                ) {
+                    (Some("L"), Some(from_side), Some(to_node), Some(to_side)) => {
+                        format!("L:{from_side}:{}:{to_side}", to_node + offset)
+                    }
+                    _ => parameter.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    Record::with_attrs(&id, description.as_deref(), record.seq())
 }
 
-/// Read a genome graph in bcalm2 fasta format into an edge-centric representation.
-pub fn read_bigraph_from_bcalm2_as_edge_centric<
+/// Read a genome graph in bcalm2 fasta format into a node-centric representation.
+///
+/// The sequences can be stored in any [`SequenceStore`], for example in the two-bit packed
+/// [`BitVectorSequenceStore`](compact_genome::implementation::bit_vec_sequence_store::BitVectorSequenceStore)
+/// to save memory.
+///
+/// Sequences with IUPAC ambiguity codes such as `N`, `R` or `Y` are rejected by
+/// [`DnaAlphabet`], but can be read with an alphabet that contains them, such as
+/// [`DnaIupacNucleicAcidAlphabet`](compact_genome::implementation::alphabets::dna_iupac_nucleic_acid_alphabet::DnaIupacNucleicAcidAlphabet).
+/// Reverse complements are computed by the alphabet, which maps e.g. `R` to `Y` and `N` to itself.
+///
+/// The ids of the records must be unique, but do not need to be contiguous or sorted,
+/// so files written from filtered or renumbered graphs can be read back.
+/// The records become the nodes in the order in which they appear, followed by their mirror nodes.
+///
+/// Links from a record to itself are self-loops.
+/// A link `L:+:0:+` results in an edge from node 0 to itself, and is mirrored by the link `L:-:0:-`.
+/// A link `L:+:0:-` results in an edge from node 0 to its mirror node, which is its own mirror edge.
+pub fn read_bigraph_from_bcalm2_as_node_centric<
     R: std::io::BufRead,
-    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    AlphabetType: Alphabet + 'static,
     GenomeSequenceStore: SequenceStore<AlphabetType>,
-    NodeData: Default + Clone,
-    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
-    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+) -> crate::error::Result<Graph>
+where
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_as_node_centric_with_options(
+        reader,
+        target_sequence_store,
+        &BCalm2ReadOptions::default(),
+    )
+}
+
+/// Read a genome graph in bcalm2 fasta format into a node-centric representation and compact all non-branching chains.
+///
+/// bcalm2 outputs maximal unitigs, but a graph written after editing may contain chains of nodes that could be merged.
+/// The chains are merged with [`compact_unitigs`], whose sequences overlap by `kmer_size - 1` characters,
+/// and the merged sequences are added to `target_sequence_store`.
+/// Merged nodes keep the id of the first node of their chain.
+pub fn read_and_compact_bcalm2<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<
+            NodeData = PlainBCalm2NodeData<GenomeSequenceStore::Handle>,
+            EdgeData = EdgeData,
+        > + Default,
 >(
     reader: R,
     target_sequence_store: &mut GenomeSequenceStore,
     kmer_size: usize,
 ) -> crate::error::Result<Graph>
 where
-    <Graph as GraphBase>::NodeIndex: Clone,
     <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
 {
-    let reader = bio::io::fasta::Reader::new(reader);
-    let mut node_map: Vec<MappedNode<Graph>> = Vec::new();
-    let mut graph = Graph::default();
+    let mut graph: Graph = read_bigraph_from_bcalm2_as_node_centric(reader, target_sequence_store)?;
+    compact_unitigs(&mut graph, target_sequence_store, kmer_size)?;
+    Ok(graph)
+}
 
-    for record in reader.records() {
-        let record: PlainBCalm2NodeData<GenomeSequenceStore::Handle> =
-            parse_bcalm2_fasta_record(record?, target_sequence_store)?;
+/// Read a genome graph in bcalm2 fasta format into a node-centric representation with the given options.
+pub fn read_bigraph_from_bcalm2_as_node_centric_with_options<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    options: &BCalm2ReadOptions,
+) -> crate::error::Result<Graph>
+where
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_as_node_centric_with_edge_data(
+        reader,
+        target_sequence_store,
+        options,
+        |_, _| EdgeData::default(),
+    )
+}
 
-        let sequence = target_sequence_store.get(&record.sequence_handle);
-        let edge_is_self_mirror = sequence
-            .iter()
-            .zip(sequence.reverse_complement_iter())
-            .take(kmer_size - 1)
-            .all(|(a, b)| *a == b);
+/// Read a genome graph in bcalm2 fasta format into a node-centric representation with the given options,
+/// deriving the edge data from the links of the records.
+///
+/// The function is called for each link with the id of the record that lists it.
+/// Mirror edges that are not listed as links in the input receive a clone of the data of their mirror.
+pub fn read_bigraph_from_bcalm2_as_node_centric_with_edge_data<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    options: &BCalm2ReadOptions,
+    edge_data: impl FnMut(usize, &PlainBCalm2Edge) -> EdgeData,
+) -> crate::error::Result<Graph>
+where
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_records_as_node_centric(
+        bio::io::fasta::Reader::new(reader).records(),
+        target_sequence_store,
+        options,
+        edge_data,
+    )
+}
 
-        let n1 = record.id * 2;
-        let n2 = record.id * 2 + 1;
+/// Read the given bcalm2 fasta records into a node-centric representation with the given options,
+/// deriving the edge data from the links of the records.
+fn read_bigraph_from_bcalm2_records_as_node_centric<
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    records: impl Iterator<Item = std::io::Result<Record>>,
+    target_sequence_store: &mut GenomeSequenceStore,
+    options: &BCalm2ReadOptions,
+    mut edge_data: impl FnMut(usize, &PlainBCalm2Edge) -> EdgeData,
+) -> crate::error::Result<Graph>
+where
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    struct BiEdge {
+        from_node: usize,
+        plain_edge: PlainBCalm2Edge,
+    }
 
-        let n1_is_self_mirror = record.edges.contains(&PlainBCalm2Edge {
-            from_side: false,
-            to_node: record.id,
-            to_side: true,
-        });
-        let n2_is_self_mirror = record.edges.contains(&PlainBCalm2Edge {
-            from_side: true,
-            to_node: record.id,
-            to_side: false,
-        });
+    let mut bigraph = Graph::default();
+    // Each record has usually at most four edges, of which only half are not yet covered by mirrors.
+    let mut edges = Vec::with_capacity(options.capacity * 2);
+    let mut deduplicator = SequenceDeduplicator::new(options.deduplicate_sequences);
+    // Maps record ids to node indices, or to `None` if the record was skipped.
+    // The ids do not need to be contiguous or sorted, e.g. after writing a filtered graph.
+    let mut node_indices: HashMap<usize, Option<usize>> = HashMap::with_capacity(options.capacity);
 
-        if node_map.len() <= n2 {
-            node_map.resize(n2 + 1, MappedNode::Unmapped);
+    for record in records {
+        let record = options.prepare_record(record.map_err(BCalm2IoError::from)?)?;
+        let id = parse_bcalm2_fasta_record_id(&record)?;
+        if node_indices.contains_key(&id) {
+            return Err(BCalm2IoError::BCalm2DuplicateNodeId { id }.into());
         }
+        let Some(record) = options.apply_invalid_char_policy::<AlphabetType>(record) else {
+            node_indices.insert(id, None);
+            continue;
+        };
 
-        // If the record has no known incoming binode yet
-        if node_map[n1] == MappedNode::Unmapped {
-            let mut assign_to_neighbors = false;
+        let raw_description = options.raw_description(&record);
+        let mut record: PlainBCalm2NodeData<GenomeSequenceStore::Handle> =
+            parse_bcalm2_fasta_record_deduplicated(
+                record,
+                target_sequence_store,
+                &mut deduplicator,
+            )?;
+        record.raw_description = raw_description;
+        options.normalize_record(&mut record);
+        options.check_record(&record)?;
+        edges.extend(record.edges.iter().map(|e| BiEdge {
+            from_node: record.id,
+            plain_edge: e.clone(),
+        }));
+        node_indices.insert(id, Some(bigraph.node_count()));
+        bigraph.add_node(record.into());
+    }
 
-            // If the record has no known incoming binode yet, first search if one of the neighbors exist
-            for edge in record
-                .edges
-                .iter()
-                // Incoming edges to n1 are outgoing on its reverse complement
-                .filter(|edge| !edge.from_side)
-            {
-                // Location of the to_node of the edge in the node_map
-                let to_node = edge.to_node * 2 + if edge.to_side { 0 } else { 1 };
+    if !options.allow_empty && bigraph.node_count() == 0 {
+        return Err(BCalm2IoError::BCalm2EmptyInput.into());
+    }
+    if let Some(edge) = edges
+        .iter()
+        .find(|edge| !node_indices.contains_key(&edge.plain_edge.to_node))
+    {
+        return Err(BCalm2IoError::BCalm2MissingRecord {
+            id: edge.plain_edge.to_node,
+        }
+        .into());
+    }
+    edges.retain(|edge| node_indices[&edge.plain_edge.to_node].is_some());
+    if options.verify_link_symmetry {
+        verify_link_symmetry(edges.iter().map(|edge| (edge.from_node, &edge.plain_edge)))?;
+    }
 
-                if node_map.len() <= to_node {
-                    node_map.resize(to_node + 1, MappedNode::Unmapped);
-                }
-                if node_map[to_node] != MappedNode::Unmapped {
-                    node_map[n1] = if !edge.to_side {
-                        node_map[to_node]
-                    } else {
-                        // If the edge changes sides, the node is mirrored
-                        node_map[to_node].mirror()
-                    };
-                    assign_to_neighbors = true;
-                    break;
-                }
-            }
+    bigraph.add_mirror_nodes();
+    debug_assert!(bigraph.verify_node_pairing());
 
-            // If no neighbor was found, create a new binode and also assign it to the neighbors
-            if node_map[n1] == MappedNode::Unmapped {
-                if n1_is_self_mirror {
-                    let n1s = graph.add_node(NodeData::default());
-                    graph.set_mirror_nodes(n1s, n1s);
-                    node_map[n1] = MappedNode::SelfMirror(n1s);
-                } else {
-                    let n1f = graph.add_node(NodeData::default());
-                    let n1r = graph.add_node(NodeData::default());
-                    graph.set_mirror_nodes(n1f, n1r);
-                    node_map[n1] = MappedNode::Normal {
-                        forward: n1f,
-                        backward: n1r,
-                    };
-                }
-                assign_to_neighbors = true;
-            }
+    for edge in edges {
+        let (Some(from_index), Some(to_index)) = (
+            node_indices[&edge.from_node],
+            node_indices[&edge.plain_edge.to_node],
+        ) else {
+            continue;
+        };
+        let from_node = if edge.plain_edge.from_side {
+            from_index.into()
+        } else {
+            bigraph
+                .mirror_node(from_index.into())
+                .ok_or(BCalm2IoError::BCalm2NodeWithoutMirror)?
+        };
+        let to_node = if edge.plain_edge.to_side {
+            to_index.into()
+        } else {
+            bigraph
+                .mirror_node(to_index.into())
+                .ok_or(BCalm2IoError::BCalm2NodeWithoutMirror)?
+        };
+        bigraph.add_edge(
+            from_node,
+            to_node,
+            edge_data(edge.from_node, &edge.plain_edge),
+        );
+    }
 
-            if assign_to_neighbors {
-                // Assign the new node also to the neighbors
-                for edge in record
-                    .edges
-                    .iter()
-                    // Incoming edges to n1 are outgoing on its reverse complement
-                    .filter(|edge| !edge.from_side)
-                {
-                    // Location of the to_node of the edge in the node_map
-                    let to_node = edge.to_node * 2 + if edge.to_side { 0 } else { 1 };
-                    node_map[to_node] = if !edge.to_side {
-                        node_map[n1]
-                    } else {
-                        // If the edge changes sides, the node is mirrored
-                        node_map[n1].mirror()
-                    };
-                }
-            }
+    bigraph.add_node_centric_mirror_edges();
+    debug_assert!(bigraph.verify_node_mirror_property());
+    Ok(bigraph)
+}
+
+/// Read only the topology of a genome graph in bcalm2 fasta format into a node-centric representation.
+///
+/// The ids, tags and links of the records are parsed as usual, but the sequences are not stored anywhere,
+/// so the node data carries the unit type as sequence handle.
+/// This is much faster and uses less memory than a full read, if only the graph structure is of interest.
+/// The ids of the records must be unique, but do not need to be contiguous or sorted.
+pub fn read_bcalm2_topology_only<
+    R: std::io::BufRead,
+    NodeData: From<PlainBCalm2NodeData<()>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+) -> crate::error::Result<Graph> {
+    let reader = bio::io::fasta::Reader::new(reader);
+    let mut bigraph = Graph::default();
+    let mut edges = Vec::new();
+    let mut node_indices = HashMap::new();
+
+    for record in reader.records() {
+        let record = record.map_err(BCalm2IoError::from)?;
+        let id = parse_bcalm2_fasta_record_id(&record)?;
+        if node_indices.insert(id, bigraph.node_count()).is_some() {
+            return Err(BCalm2IoError::BCalm2DuplicateNodeId { id }.into());
         }
 
-        // If the record has no known outgoing binode yet
-        if node_map[n2] == MappedNode::Unmapped {
-            let mut assign_to_neighbors = false;
+        let sequence_length = bcalm2_fasta_record_sequence(&record).len();
+        let record = parse_bcalm2_fasta_record_description(record, id, (), true, sequence_length)?;
+        edges.extend(record.edges.iter().map(|edge| (record.id, edge.clone())));
+        bigraph.add_node(record.into());
+    }
 
-            if edge_is_self_mirror {
-                node_map[n2] = node_map[n1].mirror();
-                // not sure if needed, but should be rare enough that it is not worth to think about it
-                assign_to_neighbors = true;
-            } else {
-                // If the record has no known outgoing binode yet, first search if one of the neighbors exist
-                for edge in record
-                    .edges
-                    .iter()
-                    // Outgoing edges from n1 are outgoing from its forward variant
-                    .filter(|edge| edge.from_side)
-                {
-                    // Location of the to_node of the edge in the node_map
-                    let to_node = edge.to_node * 2 + if edge.to_side { 0 } else { 1 };
+    if let Some((_, edge)) = edges
+        .iter()
+        .find(|(_, edge)| !node_indices.contains_key(&edge.to_node))
+    {
+        return Err(BCalm2IoError::BCalm2MissingRecord { id: edge.to_node }.into());
+    }
 
-                    if node_map.len() <= to_node {
-                        node_map.resize(to_node + 1, MappedNode::Unmapped);
-                    }
-                    if node_map[to_node] != MappedNode::Unmapped {
-                        node_map[n2] = if edge.to_side {
-                            node_map[to_node]
-                        } else {
-                            // If the edge changes sides, the node is mirrored
-                            node_map[to_node].mirror()
-                        };
-                        assign_to_neighbors = true;
-                        break;
-                    }
-                }
+    bigraph.add_mirror_nodes();
+    debug_assert!(bigraph.verify_node_pairing());
 
-                // If no neighbor was found, create a new binode and also assign it to the neighbors
-                if node_map[n2] == MappedNode::Unmapped {
-                    if n2_is_self_mirror {
-                        let n2s = graph.add_node(NodeData::default());
-                        graph.set_mirror_nodes(n2s, n2s);
-                        node_map[n2] = MappedNode::SelfMirror(n2s);
-                    } else {
-                        let n2f = graph.add_node(NodeData::default());
-                        let n2r = graph.add_node(NodeData::default());
-                        graph.set_mirror_nodes(n2f, n2r);
-                        node_map[n2] = MappedNode::Normal {
-                            forward: n2f,
-                            backward: n2r,
-                        };
-                    }
-                    assign_to_neighbors = true;
-                }
-            }
+    for (from_id, edge) in edges {
+        let from_index = node_indices[&from_id];
+        let to_index = node_indices[&edge.to_node];
+        let from_node = if edge.from_side {
+            from_index.into()
+        } else {
+            bigraph
+                .mirror_node(from_index.into())
+                .ok_or(BCalm2IoError::BCalm2NodeWithoutMirror)?
+        };
+        let to_node = if edge.to_side {
+            to_index.into()
+        } else {
+            bigraph
+                .mirror_node(to_index.into())
+                .ok_or(BCalm2IoError::BCalm2NodeWithoutMirror)?
+        };
+        bigraph.add_edge(from_node, to_node, EdgeData::default());
+    }
 
-            if assign_to_neighbors {
-                // Assign the new node also to the neighbors
-                for edge in record
-                    .edges
-                    .iter()
-                    // Outgoing edges from n1 are outgoing from its forward variant
-                    .filter(|edge| edge.from_side)
-                {
-                    // Location of the to_node of the edge in the node_map
-                    let to_node = edge.to_node * 2 + if edge.to_side { 0 } else { 1 };
-                    node_map[to_node] = if edge.to_side {
-                        node_map[n2]
-                    } else {
-                        // If the edge changes sides, the node is mirrored
-                        node_map[n2].mirror()
-                    };
-                }
-            }
+    bigraph.add_node_centric_mirror_edges();
+    debug_assert!(bigraph.verify_node_mirror_property());
+    Ok(bigraph)
+}
+
+/// Parse a genome graph in bcalm2 fasta format from a byte slice into a node-centric representation.
+///
+/// This function does not panic on any input, which makes it suitable as an entry point for fuzzing.
+/// Malformed input of any kind is reported as an error.
+/// The sequences are stored in a new sequence store that is returned together with the graph.
+#[allow(clippy::type_complexity)]
+pub fn try_parse_bcalm2(
+    bytes: &[u8],
+) -> crate::error::Result<(
+    PetBCalm2NodeGraph<<DefaultSequenceStore<DnaAlphabet> as SequenceStore<DnaAlphabet>>::Handle>,
+    DefaultSequenceStore<DnaAlphabet>,
+)> {
+    let mut sequence_store = DefaultSequenceStore::default();
+    let graph = read_bigraph_from_bcalm2_as_node_centric(bytes, &mut sequence_store)?;
+    Ok((graph, sequence_store))
+}
+
+/// Read a genome graph in bcalm2 fasta format into a node-centric representation,
+/// pre-allocating the internal data structures of the reader for `capacity` records.
+///
+/// The capacity is only an estimate and does not need to be exact.
+pub fn read_bigraph_from_bcalm2_as_node_centric_with_capacity<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    capacity: usize,
+) -> crate::error::Result<Graph>
+where
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_as_node_centric_with_options(
+        reader,
+        target_sequence_store,
+        &BCalm2ReadOptions {
+            capacity,
+            ..Default::default()
+        },
+    )
+}
+
+/// Read a genome graph in bcalm2 fasta format into a node-centric representation,
+/// skipping all records with a sequence shorter than `min_length`.
+///
+/// Edges to skipped records are dropped as well.
+/// The ids of the remaining records are not changed, so they may not be contiguous anymore.
+/// Writing the graph back with [`write_node_centric_bigraph_to_bcalm2`] keeps these ids,
+/// and the output can be read again by the node-centric readers, which do not require contiguous ids.
+pub fn read_bigraph_from_bcalm2_as_node_centric_min_length<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    min_length: usize,
+) -> crate::error::Result<Graph> {
+    read_bigraph_from_bcalm2_as_node_centric_min_length_with_report(
+        reader,
+        target_sequence_store,
+        min_length,
+    )
+    .map(|(graph, _)| graph)
+}
+
+/// Like [`read_bigraph_from_bcalm2_as_node_centric_min_length`], but additionally returns how many records and links were kept.
+pub fn read_bigraph_from_bcalm2_as_node_centric_min_length_with_report<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    min_length: usize,
+) -> crate::error::Result<(Graph, ReadReport)> {
+    read_filtered_bigraph_from_bcalm2_as_node_centric(reader, target_sequence_store, |record| {
+        bcalm2_fasta_record_sequence(record).len() >= min_length
+    })
+}
+
+/// Read a genome graph in bcalm2 fasta format into a node-centric representation,
+/// skipping all records for which `keep` returns false, as well as all edges to skipped records.
+/// In contrast to [`read_bigraph_from_bcalm2_as_node_centric`], links to skipped records are dropped instead of resulting in an error.
+fn read_filtered_bigraph_from_bcalm2_as_node_centric<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    keep: impl FnMut(&Record) -> bool,
+) -> crate::error::Result<(Graph, ReadReport)> {
+    read_filtered_bigraph_from_bcalm2_records_as_node_centric(
+        bio::io::fasta::Reader::new(reader).records(),
+        target_sequence_store,
+        keep,
+    )
+}
+
+/// Like [`read_filtered_bigraph_from_bcalm2_as_node_centric`], but takes the fasta records directly.
+fn read_filtered_bigraph_from_bcalm2_records_as_node_centric<
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    records: impl IntoIterator<Item = std::io::Result<Record>>,
+    target_sequence_store: &mut GenomeSequenceStore,
+    mut keep: impl FnMut(&Record) -> bool,
+) -> crate::error::Result<(Graph, ReadReport)> {
+    struct BiEdge<NodeIndex> {
+        from_node: NodeIndex,
+        plain_edge: PlainBCalm2Edge,
+    }
+
+    let mut bigraph = Graph::default();
+    let mut edges = Vec::new();
+    let mut id_map = HashMap::new();
+    let mut report = ReadReport::default();
+
+    for record in records {
+        let record = record.map_err(BCalm2IoError::from)?;
+        report.nodes_read += 1;
+        if !keep(&record) {
+            report.edges_read += record
+                .desc()
+                .unwrap_or("")
+                .split_whitespace()
+                .filter(|parameter| parameter.starts_with("L:"))
+                .count();
+            continue;
         }
+        report.nodes_kept += 1;
 
-        debug_assert_ne!(node_map[n1], MappedNode::Unmapped);
-        debug_assert_ne!(node_map[n2], MappedNode::Unmapped);
+        let record: PlainBCalm2NodeData<GenomeSequenceStore::Handle> =
+            parse_bcalm2_fasta_record(record, target_sequence_store)?;
+        let record_id = record.id;
+        let record_edges = record.edges.clone();
+        report.edges_read += record_edges.len();
+        let node_id = bigraph.add_node(record.into());
+        if id_map.insert(record_id, node_id).is_some() {
+            return Err(BCalm2IoError::BCalm2DuplicateNodeId { id: record_id }.into());
+        }
+        edges.extend(record_edges.into_iter().map(|plain_edge| BiEdge {
+            from_node: node_id,
+            plain_edge,
+        }));
+    }
 
-        let (n1f, n1r) = match node_map[n1] {
-            MappedNode::Unmapped => unreachable!(),
-            MappedNode::Normal { forward, backward } => (forward, backward),
-            MappedNode::SelfMirror(node) => (node, node),
+    bigraph.add_mirror_nodes();
+    debug_assert!(bigraph.verify_node_pairing());
+
+    for edge in edges {
+        let Some(&to_node) = id_map.get(&edge.plain_edge.to_node) else {
+            continue;
         };
-        let (n2f, n2r) = match node_map[n2] {
-            MappedNode::Unmapped => unreachable!(),
-            MappedNode::Normal { forward, backward } => (forward, backward),
-            MappedNode::SelfMirror(node) => (node, node),
+        report.edges_kept += 1;
+        let from_node = if edge.plain_edge.from_side {
+            edge.from_node
+        } else {
+            bigraph.mirror_node(edge.from_node).unwrap()
         };
-
-        let edge_data: EdgeData = record.into();
-        graph.add_edge(n1f, n2f, edge_data.clone());
-        graph.add_edge(n2r, n1r, edge_data.mirror());
+        let to_node = if edge.plain_edge.to_side {
+            to_node
+        } else {
+            bigraph.mirror_node(to_node).unwrap()
+        };
+        bigraph.add_edge(from_node, to_node, EdgeData::default());
     }
 
-    Ok(graph)
+    bigraph.add_node_centric_mirror_edges();
+    debug_assert!(bigraph.verify_node_mirror_property());
+    Ok((bigraph, report))
 }
 
-/// Write a genome graph in bcalm2 fasta format from an edge-centric representation to a file.
-pub fn write_edge_centric_bigraph_to_bcalm2_to_file<
-    P: AsRef<Path>,
-    AlphabetType: Alphabet,
+/// Returns the id of a bcalm2 fasta record without parsing the rest of the record.
+fn bcalm2_record_id(record: &Record) -> Option<usize> {
+    record.id().trim_end_matches('\r').parse().ok()
+}
+
+/// Returns the ids of the neighbors of a bcalm2 fasta record without parsing the rest of the record.
+/// Malformed links are skipped.
+fn bcalm2_record_neighbor_ids(record: &Record) -> impl '_ + Iterator<Item = usize> {
+    record
+        .desc()
+        .unwrap_or("")
+        .split_whitespace()
+        .filter(|parameter| parameter.starts_with("L:"))
+        .filter_map(|parameter| parameter.split(':').nth(2)?.parse().ok())
+}
+
+/// Read the subgraph induced by the records with ids in `allowed_ids` from a genome graph in bcalm2 fasta format
+/// into a node-centric representation.
+///
+/// If `include_neighbors` is true, then the direct neighbors of the allowed records are kept as well.
+/// In this case, the records are buffered in memory, since neighbors may appear before the allowed records.
+/// Edges to records that are not kept are dropped.
+/// The ids of the kept records are not changed, so they may not be contiguous anymore.
+/// Writing the graph back with [`write_node_centric_bigraph_to_bcalm2`] keeps these ids,
+/// and the output can be read again by the node-centric readers, which do not require contiguous ids.
+pub fn read_bigraph_from_bcalm2_as_node_centric_subgraph<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
     GenomeSequenceStore: SequenceStore<AlphabetType>,
-    NodeData, //: Into<PlainBCalm2NodeData<IndexType>>,
-    EdgeData: BidirectedData + Clone + Eq,
-    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
 >(
-    graph: &Graph,
-    source_sequence_store: &GenomeSequenceStore,
-    path: P,
-) -> crate::error::Result<()>
-where
-    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a EdgeData>,
-{
-    write_edge_centric_bigraph_to_bcalm2(graph, source_sequence_store, File::create(path)?)
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    allowed_ids: &HashSet<usize>,
+    include_neighbors: bool,
+) -> crate::error::Result<Graph> {
+    read_bigraph_from_bcalm2_as_node_centric_subgraph_with_report(
+        reader,
+        target_sequence_store,
+        allowed_ids,
+        include_neighbors,
+    )
+    .map(|(graph, _)| graph)
 }
 
-/// Write a genome graph in bcalm2 fasta format from an edge-centric representation.
-pub fn write_edge_centric_bigraph_to_bcalm2<
-    W: std::io::Write,
-    AlphabetType: Alphabet,
+/// Like [`read_bigraph_from_bcalm2_as_node_centric_subgraph`], but additionally returns how many records and links were kept.
+pub fn read_bigraph_from_bcalm2_as_node_centric_subgraph_with_report<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
     GenomeSequenceStore: SequenceStore<AlphabetType>,
-    NodeData,
-    EdgeData: BidirectedData + Clone + Eq,
-    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
 >(
-    graph: &Graph,
-    source_sequence_store: &GenomeSequenceStore,
-    writer: W,
-) -> crate::error::Result<()>
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    allowed_ids: &HashSet<usize>,
+    include_neighbors: bool,
+) -> crate::error::Result<(Graph, ReadReport)> {
+    // Records with unparseable ids are kept such that the parser reports them.
+    let is_allowed =
+        |record: &Record| bcalm2_record_id(record).map_or(true, |id| allowed_ids.contains(&id));
+
+    if !include_neighbors {
+        return read_filtered_bigraph_from_bcalm2_as_node_centric(
+            reader,
+            target_sequence_store,
+            is_allowed,
+        );
+    }
+
+    let records = bio::io::fasta::Reader::new(reader)
+        .records()
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(BCalm2IoError::from)?;
+    let mut kept_ids = HashSet::new();
+    for record in &records {
+        let Some(id) = bcalm2_record_id(record) else {
+            continue;
+        };
+        if allowed_ids.contains(&id) {
+            kept_ids.insert(id);
+            kept_ids.extend(bcalm2_record_neighbor_ids(record));
+        } else if bcalm2_record_neighbor_ids(record)
+            .any(|neighbor_id| allowed_ids.contains(&neighbor_id))
+        {
+            kept_ids.insert(id);
+        }
+    }
+
+    read_filtered_bigraph_from_bcalm2_records_as_node_centric(
+        records.into_iter().map(Ok),
+        target_sequence_store,
+        |record| bcalm2_record_id(record).map_or(true, |id| kept_ids.contains(&id)),
+    )
+}
+
+/// Read a genome graph in bcalm2 fasta format into a node-centric representation,
+/// ignoring all lines that start with one of the given comment prefixes.
+pub fn read_bigraph_from_bcalm2_as_node_centric_with_comments<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + BidirectedData,
+    EdgeData: Default + Clone,
+    Graph: DynamicNodeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    comment_prefixes: &[&str],
+) -> crate::error::Result<Graph>
 where
-    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a EdgeData>,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
 {
-    let mut writer = bio::io::fasta::Writer::new(writer);
-    let mut output_edges = vec![false; graph.edge_count()];
+    read_bigraph_from_bcalm2_as_node_centric(
+        CommentFilteringReader::new(reader, comment_prefixes),
+        target_sequence_store,
+    )
+}
+
+/// Format the id of a bcalm2 record for writing.
+///
+/// `usize::MAX` is the id of default-constructed node data, so it marks a node without a valid id and is rejected.
+fn format_node_id(id: usize) -> crate::error::Result<String> {
+    if id == usize::MAX {
+        Err(BCalm2IoError::BCalm2NodeIdOutOfPrintingRange.into())
+    } else {
+        Ok(id.to_string())
+    }
+}
+
+fn write_plain_bcalm2_node_data_to_bcalm2<GenomeSequenceStoreHandle>(
+    node: &PlainBCalm2NodeData<GenomeSequenceStoreHandle>,
+    out_neighbors: Vec<(bool, usize, bool)>,
+) -> crate::error::Result<String> {
+    let mut result = String::new();
+
+    if let Some(length) = node.length {
+        if !result.is_empty() {
+            write!(result, " ").map_err(BCalm2IoError::from)?;
+        }
+        write!(result, "LN:i:{length}").map_err(BCalm2IoError::from)?;
+    }
+
+    if let Some(total_abundance) = node.total_abundance {
+        if !result.is_empty() {
+            write!(result, " ").map_err(BCalm2IoError::from)?;
+        }
+        write!(result, "KC:i:{total_abundance}").map_err(BCalm2IoError::from)?;
+    }
+
+    if let Some(mean_abundance) = node.mean_abundance {
+        if !result.is_empty() {
+            write!(result, " ").map_err(BCalm2IoError::from)?;
+        }
+        write!(result, "km:f:{mean_abundance:.1}").map_err(BCalm2IoError::from)?;
+    }
+
+    if let Some(colors) = &node.colors {
+        if !result.is_empty() {
+            write!(result, " ").map_err(BCalm2IoError::from)?;
+        }
+        write!(result, "CL:Z:").map_err(BCalm2IoError::from)?;
+        for (index, color) in colors.iter().enumerate() {
+            if index > 0 {
+                write!(result, ",").map_err(BCalm2IoError::from)?;
+            }
+            write!(result, "{color}").map_err(BCalm2IoError::from)?;
+        }
+    }
+
+    for (node_type, neighbor_id, neighbor_type) in out_neighbors {
+        if !result.is_empty() {
+            write!(result, " ").map_err(BCalm2IoError::from)?;
+        }
+        write!(
+            result,
+            "L:{}:{}:{}",
+            if node_type { "+" } else { "-" },
+            format_node_id(neighbor_id)?,
+            if neighbor_type { "+" } else { "-" }
+        )
+        .map_err(BCalm2IoError::from)?;
+    }
+    Ok(result)
+}
+
+/// Returns `None` for an empty description, such that the header of a tagless record is written without trailing space.
+fn non_empty_description(description: &str) -> Option<&str> {
+    if description.is_empty() {
+        None
+    } else {
+        Some(description)
+    }
+}
+
+/// Write a genome graph in bcalm2 fasta format from a node-centric representation to a file.
+pub fn write_node_centric_bigraph_to_bcalm2_to_file<
+    P: AsRef<Path>,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData, //: Into<PlainBCalm2NodeData<IndexType>>,
+    EdgeData: Default + Clone,
+    Graph: DynamicBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    path: P,
+) -> crate::error::Result<()>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    write_node_centric_bigraph_to_bcalm2(
+        graph,
+        source_sequence_store,
+        bio::io::fasta::Writer::to_file(path).map_err(BCalm2IoError::from)?,
+    )
+}
+
+/// Write a genome graph in bcalm2 fasta format from a node-centric representation to a bgzip-compressed file.
+///
+/// The file can be read back with [`read_bigraph_from_bcalm2_as_node_centric_from_file`].
+#[cfg(feature = "bgzip")]
+pub fn write_node_centric_bigraph_to_bcalm2_to_bgzip_file<
+    P: AsRef<Path>,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    EdgeData: Default + Clone,
+    Graph: DynamicBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    path: P,
+) -> crate::error::Result<()>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    let mut writer = crate::io::bgzf::BgzfWriter::new(File::create(path)?);
+    write_node_centric_bigraph_to_bcalm2(
+        graph,
+        source_sequence_store,
+        bio::io::fasta::Writer::new(&mut writer),
+    )?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Write a genome graph in bcalm2 fasta format from a node-centric representation.
+///
+/// The tags of each record are always written in the order `LN`, `KC`, `km`, `L`.
+/// Hence, files with a different tag order round-trip with equal values, but not byte by byte.
+/// Mean abundances are written in fixed-point notation with one decimal place,
+/// so a mean abundance read from scientific notation such as `km:f:3.0e0` is written as `km:f:3.0`.
+pub fn write_node_centric_bigraph_to_bcalm2<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    EdgeData: Default + Clone,
+    Graph: DynamicBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    writer: bio::io::fasta::Writer<W>,
+) -> crate::error::Result<()>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    write_node_centric_bigraph_to_bcalm2_with_options(
+        graph,
+        source_sequence_store,
+        writer,
+        &BCalm2WriteOptions::default(),
+    )
+}
+
+/// Write a genome graph in bcalm2 fasta format from a node-centric representation with the given options.
+pub fn write_node_centric_bigraph_to_bcalm2_with_options<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    EdgeData: Default + Clone,
+    Graph: DynamicBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    writer: bio::io::fasta::Writer<W>,
+    options: &BCalm2WriteOptions,
+) -> crate::error::Result<()>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    write_node_centric_bigraph_to_bcalm2_with_warnings(
+        graph,
+        source_sequence_store,
+        writer,
+        options,
+    )
+    .map(|_| ())
+}
+
+/// Write a genome graph in bcalm2 fasta format from a node-centric representation with the given options.
+/// Returns warnings about all data that the bcalm2 format cannot represent exactly.
+pub fn write_node_centric_bigraph_to_bcalm2_with_warnings<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    EdgeData: Default + Clone,
+    Graph: DynamicBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    mut writer: bio::io::fasta::Writer<W>,
+    options: &BCalm2WriteOptions,
+) -> crate::error::Result<Vec<WriteWarning>>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    options.validate()?;
+    let mut warnings = Vec::new();
+    let mut output_nodes = vec![false; graph.node_count()];
+
+    for node_id in graph.node_indices() {
+        let mirror_node_id = graph
+            .mirror_node(node_id)
+            .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?;
+        output_nodes[node_id.as_usize()] = is_representative(
+            node_id.as_usize(),
+            &PlainBCalm2NodeData::from(graph.node_data(node_id)),
+            mirror_node_id.as_usize(),
+            &PlainBCalm2NodeData::from(graph.node_data(mirror_node_id)),
+            source_sequence_store,
+            options.representative,
+        );
+    }
+
+    if options.emit_both_strands {
+        return write_node_centric_bigraph_to_bcalm2_both_strands(
+            graph,
+            source_sequence_store,
+            writer,
+            options,
+            &output_nodes,
+        );
+    }
+
+    // The records are written in the order of the first node of their mirror pair,
+    // such that they stay in the order in which they were read, even if the representative is the second node.
+    let mut output_order = Vec::with_capacity(graph.node_count() / 2);
+    for node_id in graph.node_indices() {
+        let mirror_node_id = graph
+            .mirror_node(node_id)
+            .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?;
+        if node_id.as_usize() <= mirror_node_id.as_usize() {
+            output_order.push(if output_nodes[node_id.as_usize()] {
+                node_id
+            } else {
+                mirror_node_id
+            });
+        }
+    }
+
+    let printed_ids = if options.reassign_ids {
+        let mut printed_ids = vec![usize::MAX; graph.node_count()];
+        for (printed_id, node_id) in output_order.iter().enumerate() {
+            printed_ids[node_id.as_usize()] = printed_id;
+        }
+        Some(printed_ids)
+    } else {
+        let mut ids = HashSet::new();
+        for &node_id in &output_order {
+            let id = PlainBCalm2NodeData::from(graph.node_data(node_id)).id;
+            if !ids.insert(id) {
+                return Err(BCalm2IoError::BCalm2DuplicateNodeId { id }.into());
+            }
+        }
+        None
+    };
+
+    for node_id in output_order {
+        let mut node_data = PlainBCalm2NodeData::from(graph.node_data(node_id));
+        options.derive_node_data(&mut node_data);
+        let mirror_node_id = graph
+            .mirror_node(node_id)
+            .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?;
+        /*let mirror_node_data = PlainBCalm2NodeData::<IndexType>::from(
+            graph
+                .node_data(mirror_node_id)
+                .ok_or_else(|| Error::from(ErrorKind::BCalm2NodeWithoutMirror))?,
+        );*/
+        let mut out_neighbors_plus = Vec::new();
+        let mut out_neighbors_minus = Vec::new();
+        let mut strands_unchanged = node_data.forwards;
+
+        for neighbor in graph.out_neighbors(node_id) {
+            let neighbor_node_id = neighbor.node_id.as_usize();
+            let neighbor_representative = if output_nodes[neighbor_node_id] {
+                neighbor.node_id
+            } else {
+                graph
+                    .mirror_node(neighbor.node_id)
+                    .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?
+            };
+            strands_unchanged &=
+                PlainBCalm2NodeData::from(graph.node_data(neighbor_representative)).forwards;
+
+            out_neighbors_plus.push((
+                true,
+                if let Some(printed_ids) = &printed_ids {
+                    printed_ids[neighbor_representative.as_usize()]
+                } else {
+                    PlainBCalm2NodeData::from(graph.node_data(neighbor_representative)).id
+                },
+                output_nodes[neighbor_node_id],
+            ));
+        }
+        for neighbor in graph.out_neighbors(mirror_node_id) {
+            let neighbor_node_id = neighbor.node_id.as_usize();
+            let neighbor_representative = if output_nodes[neighbor_node_id] {
+                neighbor.node_id
+            } else {
+                graph
+                    .mirror_node(neighbor.node_id)
+                    .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?
+            };
+            strands_unchanged &=
+                PlainBCalm2NodeData::from(graph.node_data(neighbor_representative)).forwards;
+
+            out_neighbors_minus.push((
+                false,
+                if let Some(printed_ids) = &printed_ids {
+                    printed_ids[neighbor_representative.as_usize()]
+                } else {
+                    PlainBCalm2NodeData::from(graph.node_data(neighbor_representative)).id
+                },
+                output_nodes[neighbor_node_id],
+            ));
+        }
+
+        out_neighbors_plus.sort_unstable();
+        out_neighbors_minus.sort_unstable();
+        out_neighbors_plus.append(&mut out_neighbors_minus);
+        let out_neighbors = out_neighbors_plus;
+
+        let printed_node_id = format_node_id(
+            printed_ids
+                .as_ref()
+                .map_or(node_data.id, |printed_ids| printed_ids[node_id.as_usize()]),
+        )?;
+        let node_description =
+            options.describe_node(&node_data, out_neighbors, strands_unchanged, &mut warnings)?;
+        let node_sequence = oriented_sequence_vec(&node_data, source_sequence_store);
+        collect_write_warnings(&node_data, &mut warnings);
+
+        writer
+            .write(
+                &printed_node_id,
+                non_empty_description(&node_description),
+                &node_sequence,
+            )
+            .map_err(BCalm2IoError::from)?;
+    }
+
+    Ok(warnings)
+}
+
+/// Write both nodes of each mirror pair as separate records, as described by [`BCalm2WriteOptions::emit_both_strands`].
+/// The representatives given by `output_nodes` are written first within their pair.
+fn write_node_centric_bigraph_to_bcalm2_both_strands<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    Graph: DynamicBigraph<NodeData = NodeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    mut writer: bio::io::fasta::Writer<W>,
+    options: &BCalm2WriteOptions,
+    output_nodes: &[bool],
+) -> crate::error::Result<Vec<WriteWarning>>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    let mut warnings = Vec::new();
+    let mut output_order = Vec::with_capacity(graph.node_count());
+    for node_id in graph.node_indices() {
+        if output_nodes[node_id.as_usize()] {
+            let mirror_node_id = graph
+                .mirror_node(node_id)
+                .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?;
+            output_order.push(node_id);
+            if mirror_node_id != node_id {
+                output_order.push(mirror_node_id);
+            }
+        }
+    }
+
+    let mut printed_ids = vec![usize::MAX; graph.node_count()];
+    for (printed_id, node_id) in output_order.iter().enumerate() {
+        printed_ids[node_id.as_usize()] = printed_id;
+    }
+
+    for node_id in output_order {
+        let mut node_data = PlainBCalm2NodeData::from(graph.node_data(node_id));
+        options.derive_node_data(&mut node_data);
+        let mirror_node_id = graph
+            .mirror_node(node_id)
+            .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?;
+
+        let mut out_neighbors_plus: Vec<_> = graph
+            .out_neighbors(node_id)
+            .map(|neighbor| (true, printed_ids[neighbor.node_id.as_usize()], true))
+            .collect();
+        let mut out_neighbors_minus: Vec<_> = graph
+            .out_neighbors(mirror_node_id)
+            .map(|neighbor| (false, printed_ids[neighbor.node_id.as_usize()], true))
+            .collect();
+        out_neighbors_plus.sort_unstable();
+        out_neighbors_minus.sort_unstable();
+        out_neighbors_plus.append(&mut out_neighbors_minus);
+
+        let printed_node_id = format_node_id(printed_ids[node_id.as_usize()])?;
+        options.collect_regenerated_description_warnings(&node_data, &mut warnings);
+        let node_description =
+            write_plain_bcalm2_node_data_to_bcalm2(&node_data, out_neighbors_plus)?;
+        let node_sequence = oriented_sequence_vec(&node_data, source_sequence_store);
+        collect_write_warnings(&node_data, &mut warnings);
+
+        writer
+            .write(
+                &printed_node_id,
+                non_empty_description(&node_description),
+                &node_sequence,
+            )
+            .map_err(BCalm2IoError::from)?;
+    }
+
+    Ok(warnings)
+}
+
+/// Write the part of a genome graph induced by the given nodes in bcalm2 fasta format from a node-centric representation.
+///
+/// A node is written if it or its mirror is contained in `component_nodes`, and edges to other nodes are dropped.
+/// The written records are numbered consecutively starting from zero, such that the output is a valid bcalm2 file on its own.
+pub fn write_node_centric_component_to_bcalm2<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Clone,
+    EdgeData: Default + Clone,
+    Graph: DynamicBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    component_nodes: &[Graph::NodeIndex],
+    writer: bio::io::fasta::Writer<W>,
+) -> crate::error::Result<()>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a NodeData>,
+{
+    let mut contained = vec![false; graph.node_count()];
+    for &node_id in component_nodes {
+        let mirror_node_id = graph
+            .mirror_node(node_id)
+            .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?;
+        contained[node_id.as_usize()] = true;
+        contained[mirror_node_id.as_usize()] = true;
+    }
+
+    let mut component = Graph::default();
+    let mut node_map = vec![None; graph.node_count()];
+    for node_id in graph.node_indices() {
+        if contained[node_id.as_usize()] {
+            node_map[node_id.as_usize()] =
+                Some(component.add_node(graph.node_data(node_id).clone()));
+        }
+    }
+    for node_id in graph.node_indices() {
+        if let Some(component_node_id) = node_map[node_id.as_usize()] {
+            let mirror_node_id = graph
+                .mirror_node(node_id)
+                .ok_or_else(|| BCalm2IoError::BCalm2NodeWithoutMirror)?;
+            if node_id.as_usize() <= mirror_node_id.as_usize() {
+                component.set_mirror_nodes(
+                    component_node_id,
+                    node_map[mirror_node_id.as_usize()].unwrap(),
+                );
+            }
+        }
+    }
+    for edge_id in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge_id);
+        if let (Some(from_node), Some(to_node)) =
+            (node_map[from_node.as_usize()], node_map[to_node.as_usize()])
+        {
+            component.add_edge(from_node, to_node, graph.edge_data(edge_id).clone());
+        }
+    }
+
+    write_node_centric_bigraph_to_bcalm2_with_options(
+        &component,
+        source_sequence_store,
+        writer,
+        &BCalm2WriteOptions {
+            reassign_ids: true,
+            ..Default::default()
+        },
+    )
+}
+
+/////////////////////////////
+////// EDGE CENTRIC IO //////
+/////////////////////////////
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation from a file.
+pub fn read_bigraph_from_bcalm2_as_edge_centric_from_file<
+    P: AsRef<Path> + Debug,
+    AlphabetType: Alphabet + 'static + Hash + Eq + Clone,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    path: P,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+) -> crate::error::Result<Graph>
+where
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_as_edge_centric(
+        maybe_decompress(BufReader::new(File::open(path)?))?,
+        target_sequence_store,
+        kmer_size,
+    )
+}
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation from stdin.
+///
+/// Stdin is locked for the duration of the read, and gzip-compressed input is decompressed like for files.
+/// Since stdin cannot be re-read, the consumed input is lost if reading fails.
+pub fn read_bigraph_from_bcalm2_as_edge_centric_from_stdin<
+    AlphabetType: Alphabet + 'static + Hash + Eq + Clone,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+) -> crate::error::Result<Graph>
+where
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_as_edge_centric(
+        maybe_decompress(std::io::stdin().lock())?,
+        target_sequence_store,
+        kmer_size,
+    )
+}
+
+fn get_or_create_node<
+    Graph: DynamicBigraph,
+    AlphabetType: Alphabet,
+    Genome: OwnedGenomeSequence<AlphabetType, GenomeSubsequence> + Hash + Eq + Clone,
+    GenomeSubsequence: GenomeSequence<AlphabetType, GenomeSubsequence> + ?Sized,
+>(
+    bigraph: &mut Graph,
+    id_map: &mut HashMap<Genome, <Graph as GraphBase>::NodeIndex>,
+    genome: Genome,
+) -> <Graph as GraphBase>::NodeIndex
+where
+    <Graph as GraphBase>::NodeData: Default,
+    <Graph as GraphBase>::EdgeData: Clone,
+{
+    if let Some(node) = id_map.get(&genome) {
+        *node
+    } else {
+        let node = bigraph.add_node(Default::default());
+        let reverse_complement = genome.clone_as_reverse_complement();
+
+        if reverse_complement == genome {
+            bigraph.set_mirror_nodes(node, node);
+        } else {
+            let mirror_node = bigraph.add_node(Default::default());
+            id_map.insert(reverse_complement, mirror_node);
+            bigraph.set_mirror_nodes(node, mirror_node);
+        }
+
+        id_map.insert(genome, node);
+
+        node
+    }
+}
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation.
+///
+/// This is a reference implementation of [`read_bigraph_from_bcalm2_as_edge_centric`].
+/// Instead of using the edges stored in the records, it identifies the nodes of the graph by hashing the `kmer_size - 1` prefixes and suffixes of the unitigs.
+/// It is slower and uses more memory, and is kept to cross-validate the main implementation.
+/// Both functions produce identical graphs for bcalm2 files whose edges are complete.
+pub fn read_bigraph_from_bcalm2_as_edge_centric_old<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+) -> crate::error::Result<Graph>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    let reader = bio::io::fasta::Reader::new(reader);
+    let mut bigraph = Graph::default();
+    let mut id_map = HashMap::new();
+    let node_kmer_size = kmer_size - 1;
+
+    for record in reader.records() {
+        let record: PlainBCalm2NodeData<GenomeSequenceStore::Handle> =
+            parse_bcalm2_fasta_record(record.map_err(BCalm2IoError::from)?, target_sequence_store)?;
+        let sequence = target_sequence_store.get(&record.sequence_handle);
+        let prefix = sequence.prefix(node_kmer_size);
+        let suffix = sequence.suffix(node_kmer_size);
+
+        let pre_plus: BitVectorGenome<AlphabetType> = prefix.convert();
+        let pre_minus: BitVectorGenome<AlphabetType> = suffix.convert_with_reverse_complement();
+        let succ_plus: BitVectorGenome<AlphabetType> = suffix.convert();
+        let succ_minus: BitVectorGenome<AlphabetType> = prefix.convert_with_reverse_complement();
+
+        let pre_plus = get_or_create_node(&mut bigraph, &mut id_map, pre_plus);
+        let pre_minus = get_or_create_node(&mut bigraph, &mut id_map, pre_minus);
+        let succ_plus = get_or_create_node(&mut bigraph, &mut id_map, succ_plus);
+        let succ_minus = get_or_create_node(&mut bigraph, &mut id_map, succ_minus);
+
+        bigraph.add_edge(pre_plus, succ_plus, record.clone().into());
+        bigraph.add_edge(pre_minus, succ_minus, record.mirror().into());
+    }
+
+    debug_assert!(bigraph.verify_node_pairing());
+    debug_assert!(bigraph.verify_edge_mirror_property());
+    Ok(bigraph)
+}
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation.
+///
+/// Like for [`read_bigraph_from_bcalm2_as_node_centric`], sequences with ambiguity codes can be read with an alphabet
+/// that contains them, and the mirror edges spell the reverse complements computed by the alphabet.
+pub fn read_bigraph_from_bcalm2_as_edge_centric<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+) -> crate::error::Result<Graph>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_as_edge_centric_with_options(
+        reader,
+        target_sequence_store,
+        kmer_size,
+        &BCalm2ReadOptions::default(),
+    )
+}
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation,
+/// taking the k-mer size from a leading header comment like `; k=31` if `kmer_size` is `None`.
+///
+/// A leading header comment is skipped even if `kmer_size` is given, in which case `kmer_size` takes precedence.
+/// If `kmer_size` is `None` and the input has no header comment with a k-mer size,
+/// then [`BCalm2IoError::BCalm2MissingParameterError`] is returned.
+pub fn read_bigraph_from_bcalm2_as_edge_centric_with_optional_kmer_size<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    mut reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: Option<usize>,
+) -> crate::error::Result<Graph>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    let header_kmer_size = read_bcalm2_header_kmer_size(&mut reader)?;
+    let kmer_size = kmer_size.or(header_kmer_size).ok_or_else(|| {
+        BCalm2IoError::BCalm2MissingParameterError {
+            parameter: "k".to_string(),
+        }
+    })?;
+
+    read_bigraph_from_bcalm2_as_edge_centric(reader, target_sequence_store, kmer_size)
+}
+
+/// Consume a leading header comment like `; k=31` from the reader, if present, and return the k-mer size it specifies.
+///
+/// Returns `None` if the input does not start with a comment, or if the comment does not specify a k-mer size.
+fn read_bcalm2_header_kmer_size(
+    reader: &mut impl std::io::BufRead,
+) -> crate::error::Result<Option<usize>> {
+    if !reader
+        .fill_buf()
+        .map_err(BCalm2IoError::from)?
+        .starts_with(b";")
+    {
+        return Ok(None);
+    }
+
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(BCalm2IoError::from)?;
+    line[1..]
+        .split_whitespace()
+        .find_map(|token| token.strip_prefix("k="))
+        .map(|kmer_size| {
+            kmer_size
+                .parse()
+                .map_err(|_| BCalm2IoError::BCalm2MalformedParameterError {
+                    parameter: format!("k={kmer_size}"),
+                })
+        })
+        .transpose()
+        .map_err(Into::into)
+}
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation with the given options.
+pub fn read_bigraph_from_bcalm2_as_edge_centric_with_options<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+    options: &BCalm2ReadOptions,
+) -> crate::error::Result<Graph>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_as_edge_centric_with_edge_factory(
+        reader,
+        target_sequence_store,
+        kmer_size,
+        options,
+        |record, is_mirror| {
+            let edge_data: EdgeData = record.clone().into();
+            if is_mirror {
+                edge_data.mirror()
+            } else {
+                edge_data
+            }
+        },
+    )
+}
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation with the given options,
+/// creating the edge data with the given factory.
+///
+/// The factory is called twice for each record, first with `is_mirror` set to `false` to create the data of the edge
+/// in the orientation of the record, and then with `is_mirror` set to `true` to create the data of its mirror edge.
+/// In contrast to [`read_bigraph_from_bcalm2_as_edge_centric_with_options`], the edge data does not need to be
+/// convertible from a [`PlainBCalm2NodeData`].
+pub fn read_bigraph_from_bcalm2_as_edge_centric_with_edge_factory<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+    options: &BCalm2ReadOptions,
+    edge_factory: impl FnMut(&PlainBCalm2NodeData<GenomeSequenceStore::Handle>, bool) -> EdgeData,
+) -> crate::error::Result<Graph>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_as_edge_centric_core(
+        reader,
+        target_sequence_store,
+        Some(kmer_size),
+        options,
+        edge_factory,
+        |_, _, _| {},
+    )
+}
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation with the given options,
+/// additionally returning a map from the ids of the records to their edges and sequences.
+#[allow(clippy::type_complexity)]
+pub fn read_bigraph_from_bcalm2_as_edge_centric_with_id_map<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+    options: &BCalm2ReadOptions,
+) -> crate::error::Result<(
+    Graph,
+    BCalm2IdMap<Graph::EdgeIndex, GenomeSequenceStore::Handle>,
+)>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    let mut id_map = BCalm2IdMap {
+        entries: HashMap::with_capacity(options.capacity),
+    };
+    let graph = read_bigraph_from_bcalm2_as_edge_centric_core(
+        reader,
+        target_sequence_store,
+        Some(kmer_size),
+        options,
+        |record, is_mirror| {
+            let edge_data: EdgeData = record.clone().into();
+            if is_mirror {
+                edge_data.mirror()
+            } else {
+                edge_data
+            }
+        },
+        |record, edge, mirror_edge| {
+            id_map.entries.insert(
+                record.id,
+                BCalm2IdMapEntry {
+                    edge,
+                    mirror_edge,
+                    sequence_handle: record.sequence_handle.clone(),
+                    forwards: record.forwards,
+                },
+            );
+        },
+    )?;
+    Ok((graph, id_map))
+}
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation with the given options,
+/// inferring the overlap of each link from the sequences of the linked records.
+///
+/// This allows to read variable-order de Bruijn graphs, where `kmer_size` is only the nominal k.
+/// Hence, the records only need to be non-empty, and the nodes of the graph are identified by the links of the records only,
+/// not by the `kmer_size - 1`-prefixes and suffixes of their sequences.
+/// The overlap of a link is the length of the longest suffix of its tail record that is a prefix of its head record,
+/// but at most `kmer_size - 1`.
+///
+/// The overlaps are returned keyed by the links, as pairs of the edge of the tail record and the edge of the head record,
+/// each in the orientation in which the link walks the record.
+/// The mirror of each link is contained as well, as pair of the mirror edges in reverse order.
+/// If the sequences of two linked records do not overlap at all, then [`BCalm2IoError::BCalm2MissingOverlap`] is returned.
+pub fn read_bigraph_from_bcalm2_as_edge_centric_with_overlaps<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+    options: &BCalm2ReadOptions,
+) -> crate::error::Result<(Graph, HashMap<(Graph::EdgeIndex, Graph::EdgeIndex), usize>)>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    if kmer_size == 0 {
+        return Err(crate::error::Error::ZeroKmerSize);
+    }
+
+    let mut id_map = BCalm2IdMap {
+        entries: HashMap::with_capacity(options.capacity),
+    };
+    let mut links = Vec::new();
+    let graph: Graph = read_bigraph_from_bcalm2_as_edge_centric_core(
+        reader,
+        target_sequence_store,
+        None,
+        options,
+        |record, is_mirror| {
+            let edge_data: EdgeData = record.clone().into();
+            if is_mirror {
+                edge_data.mirror()
+            } else {
+                edge_data
+            }
+        },
+        |record, edge, mirror_edge| {
+            links.extend(record.edges.iter().map(|link| (record.id, link.clone())));
+            id_map.entries.insert(
+                record.id,
+                BCalm2IdMapEntry {
+                    edge,
+                    mirror_edge,
+                    sequence_handle: record.sequence_handle.clone(),
+                    forwards: record.forwards,
+                },
+            );
+        },
+    )?;
+
+    let oriented_sequence = |id, forwards| -> Option<BitVectorGenome<AlphabetType>> {
+        let sequence = id_map.sequence_by_original_id(id, target_sequence_store)?;
+        Some(if forwards {
+            sequence
+        } else {
+            sequence.clone_as_reverse_complement()
+        })
+    };
+
+    let oriented_edge = |id, forwards| {
+        if forwards {
+            id_map.edge(id)
+        } else {
+            id_map.mirror_edge(id)
+        }
+        .ok_or(BCalm2IoError::BCalm2MissingRecord { id })
+    };
+
+    let mut overlaps = HashMap::new();
+    for (from_id, link) in links {
+        // Links to skipped records were not added to the graph.
+        let (Some(from_sequence), Some(to_sequence)) = (
+            oriented_sequence(from_id, link.from_side),
+            oriented_sequence(link.to_node, link.to_side),
+        ) else {
+            continue;
+        };
+
+        let max_overlap = (kmer_size - 1)
+            .min(from_sequence.len())
+            .min(to_sequence.len());
+        let overlap = (1..=max_overlap)
+            .rev()
+            .find(|&overlap| {
+                from_sequence
+                    .suffix(overlap)
+                    .iter()
+                    .eq(to_sequence.prefix(overlap).iter())
+            })
+            .ok_or(BCalm2IoError::BCalm2MissingOverlap {
+                from: from_id,
+                to: link.to_node,
+            })?;
+
+        let from_edge = oriented_edge(from_id, link.from_side)?;
+        let to_edge = oriented_edge(link.to_node, link.to_side)?;
+        let mirror_from_edge = oriented_edge(from_id, !link.from_side)?;
+        let mirror_to_edge = oriented_edge(link.to_node, !link.to_side)?;
+        overlaps.insert((from_edge, to_edge), overlap);
+        overlaps.insert((mirror_to_edge, mirror_from_edge), overlap);
+    }
+
+    Ok((graph, overlaps))
+}
+
+/// A map from the ids of bcalm2 records to the edges of an edge-centric graph read from them,
+/// as returned by [`read_bigraph_from_bcalm2_as_edge_centric_with_id_map`].
+#[derive(Debug, Clone)]
+pub struct BCalm2IdMap<EdgeIndex, GenomeSequenceStoreHandle> {
+    entries: HashMap<usize, BCalm2IdMapEntry<EdgeIndex, GenomeSequenceStoreHandle>>,
+}
+
+#[derive(Debug, Clone)]
+struct BCalm2IdMapEntry<EdgeIndex, GenomeSequenceStoreHandle> {
+    edge: EdgeIndex,
+    mirror_edge: EdgeIndex,
+    sequence_handle: GenomeSequenceStoreHandle,
+    forwards: bool,
+}
+
+impl<EdgeIndex: Copy, GenomeSequenceStoreHandle> BCalm2IdMap<EdgeIndex, GenomeSequenceStoreHandle> {
+    /// Returns the edge of the record with the given id in the orientation of the record.
+    pub fn edge(&self, id: usize) -> Option<EdgeIndex> {
+        self.entries.get(&id).map(|entry| entry.edge)
+    }
+
+    /// Returns the mirror edge of the record with the given id, i.e. the edge of its reverse complement.
+    pub fn mirror_edge(&self, id: usize) -> Option<EdgeIndex> {
+        self.entries.get(&id).map(|entry| entry.mirror_edge)
+    }
+
+    /// Returns the sequence of the record with the given id in the orientation of the record.
+    pub fn sequence_by_original_id<
+        AlphabetType: Alphabet,
+        GenomeSequenceStore: SequenceStore<AlphabetType, Handle = GenomeSequenceStoreHandle>,
+    >(
+        &self,
+        id: usize,
+        source_sequence_store: &GenomeSequenceStore,
+    ) -> Option<BitVectorGenome<AlphabetType>> {
+        let entry = self.entries.get(&id)?;
+        let sequence = source_sequence_store.get(&entry.sequence_handle);
+        Some(if entry.forwards {
+            sequence.iter().cloned().collect()
+        } else {
+            sequence.reverse_complement_iter().collect()
+        })
+    }
+
+    /// Returns the number of records in this map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if this map contains no records.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The shared implementation of the edge-centric bcalm2 readers.
+/// Calls `on_record` for each record with the edges created for it, first the edge in the orientation of the record and then its mirror.
+///
+/// If `kmer_size` is `None`, then the records may overlap by different amounts, so they only need to be non-empty,
+/// and the nodes between them are identified by their links only, instead of also by their `kmer_size - 1`-prefixes and suffixes.
+fn read_bigraph_from_bcalm2_as_edge_centric_core<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: Option<usize>,
+    options: &BCalm2ReadOptions,
+    mut edge_factory: impl FnMut(&PlainBCalm2NodeData<GenomeSequenceStore::Handle>, bool) -> EdgeData,
+    mut on_record: impl FnMut(
+        &PlainBCalm2NodeData<GenomeSequenceStore::Handle>,
+        Graph::EdgeIndex,
+        Graph::EdgeIndex,
+    ),
+) -> crate::error::Result<Graph>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    if kmer_size == Some(0) {
+        return Err(crate::error::Error::ZeroKmerSize);
+    }
+    let min_length = kmer_size.unwrap_or(1);
+
+    let reader = bio::io::fasta::Reader::new(reader);
+    let mut node_map: Vec<MappedNode<Graph>> = Vec::with_capacity(options.capacity * 2);
+    let mut graph = Graph::default();
+    let mut links = Vec::new();
+    let mut deduplicator = SequenceDeduplicator::new(options.deduplicate_sequences);
+    let mut skipped_ids = HashSet::new();
+
+    for record in reader.records() {
+        let record = options.prepare_record(record?)?;
+        let id = parse_bcalm2_fasta_record_id(&record)?;
+        let Some(record) = options.apply_invalid_char_policy::<AlphabetType>(record) else {
+            skipped_ids.insert(id);
+            continue;
+        };
+        let raw_description = options.raw_description(&record);
+        let mut record: PlainBCalm2NodeData<GenomeSequenceStore::Handle> =
+            parse_bcalm2_fasta_record_deduplicated(
+                record,
+                target_sequence_store,
+                &mut deduplicator,
+            )?;
+        record.raw_description = raw_description;
+        options.normalize_record(&mut record);
+        options.check_record(&record)?;
+        // The nodes of the edge-centric graph are the k - 1-prefixes and suffixes of the sequences,
+        // and each unitig contains at least one k-mer.
+        let length = target_sequence_store.get(&record.sequence_handle).len();
+        if length < min_length {
+            return Err(BCalm2IoError::BCalm2SequenceTooShort {
+                id: record.id,
+                length,
+                kmer_size: min_length,
+            }
+            .into());
+        }
+        if options.verify_link_symmetry {
+            links.extend(record.edges.iter().map(|edge| (record.id, edge.clone())));
+        }
+
+        let edge_is_self_mirror = kmer_size.is_some_and(|kmer_size| {
+            is_self_complemental(target_sequence_store, &record.sequence_handle, kmer_size)
+        });
+
+        let n1 = record.id * 2;
+        let n2 = record.id * 2 + 1;
+
+        let n1_is_self_mirror = record.edges.contains(&PlainBCalm2Edge {
+            from_side: false,
+            to_node: record.id,
+            to_side: true,
+        });
+        let n2_is_self_mirror = record.edges.contains(&PlainBCalm2Edge {
+            from_side: true,
+            to_node: record.id,
+            to_side: false,
+        });
+
+        if node_map.len() <= n2 {
+            node_map.resize(n2 + 1, MappedNode::Unmapped);
+        }
+
+        // If the record has no known incoming binode yet
+        if node_map[n1] == MappedNode::Unmapped {
+            let mut assign_to_neighbors = false;
+
+            // If the record has no known incoming binode yet, first search if one of the neighbors exist
+            for edge in record
+                .edges
+                .iter()
+                // Incoming edges to n1 are outgoing on its reverse complement
+                .filter(|edge| !edge.from_side)
+            {
+                // Location of the to_node of the edge in the node_map
+                let to_node = edge.to_node * 2 + if edge.to_side { 0 } else { 1 };
+
+                if node_map.len() <= to_node {
+                    node_map.resize(to_node + 1, MappedNode::Unmapped);
+                }
+                if node_map[to_node] != MappedNode::Unmapped {
+                    node_map[n1] = if !edge.to_side {
+                        node_map[to_node]
+                    } else {
+                        // If the edge changes sides, the node is mirrored
+                        node_map[to_node].mirror()
+                    };
+                    assign_to_neighbors = true;
+                    break;
+                }
+            }
+
+            // If no neighbor was found, create a new binode and also assign it to the neighbors
+            if node_map[n1] == MappedNode::Unmapped {
+                if n1_is_self_mirror {
+                    let n1s = graph.add_node(NodeData::default());
+                    graph.set_mirror_nodes(n1s, n1s);
+                    node_map[n1] = MappedNode::SelfMirror(n1s);
+                } else {
+                    let n1f = graph.add_node(NodeData::default());
+                    let n1r = graph.add_node(NodeData::default());
+                    graph.set_mirror_nodes(n1f, n1r);
+                    node_map[n1] = MappedNode::Normal {
+                        forward: n1f,
+                        backward: n1r,
+                    };
+                }
+                assign_to_neighbors = true;
+            }
+
+            if assign_to_neighbors {
+                // Assign the new node also to the neighbors
+                for edge in record
+                    .edges
+                    .iter()
+                    // Incoming edges to n1 are outgoing on its reverse complement
+                    .filter(|edge| !edge.from_side)
+                {
+                    // Location of the to_node of the edge in the node_map
+                    let to_node = edge.to_node * 2 + if edge.to_side { 0 } else { 1 };
+                    node_map[to_node] = if !edge.to_side {
+                        node_map[n1]
+                    } else {
+                        // If the edge changes sides, the node is mirrored
+                        node_map[n1].mirror()
+                    };
+                }
+            }
+        }
+
+        // If the record has no known outgoing binode yet
+        if node_map[n2] == MappedNode::Unmapped {
+            let mut assign_to_neighbors = false;
+
+            if edge_is_self_mirror {
+                node_map[n2] = node_map[n1].mirror();
+                // not sure if needed, but should be rare enough that it is not worth to think about it
+                assign_to_neighbors = true;
+            } else {
+                // If the record has no known outgoing binode yet, first search if one of the neighbors exist
+                for edge in record
+                    .edges
+                    .iter()
+                    // Outgoing edges from n1 are outgoing from its forward variant
+                    .filter(|edge| edge.from_side)
+                {
+                    // Location of the to_node of the edge in the node_map
+                    let to_node = edge.to_node * 2 + if edge.to_side { 0 } else { 1 };
+
+                    if node_map.len() <= to_node {
+                        node_map.resize(to_node + 1, MappedNode::Unmapped);
+                    }
+                    if node_map[to_node] != MappedNode::Unmapped {
+                        node_map[n2] = if edge.to_side {
+                            node_map[to_node]
+                        } else {
+                            // If the edge changes sides, the node is mirrored
+                            node_map[to_node].mirror()
+                        };
+                        assign_to_neighbors = true;
+                        break;
+                    }
+                }
+
+                // If no neighbor was found, create a new binode and also assign it to the neighbors
+                if node_map[n2] == MappedNode::Unmapped {
+                    if n2_is_self_mirror {
+                        let n2s = graph.add_node(NodeData::default());
+                        graph.set_mirror_nodes(n2s, n2s);
+                        node_map[n2] = MappedNode::SelfMirror(n2s);
+                    } else {
+                        let n2f = graph.add_node(NodeData::default());
+                        let n2r = graph.add_node(NodeData::default());
+                        graph.set_mirror_nodes(n2f, n2r);
+                        node_map[n2] = MappedNode::Normal {
+                            forward: n2f,
+                            backward: n2r,
+                        };
+                    }
+                    assign_to_neighbors = true;
+                }
+            }
+
+            if assign_to_neighbors {
+                // Assign the new node also to the neighbors
+                for edge in record
+                    .edges
+                    .iter()
+                    // Outgoing edges from n1 are outgoing from its forward variant
+                    .filter(|edge| edge.from_side)
+                {
+                    // Location of the to_node of the edge in the node_map
+                    let to_node = edge.to_node * 2 + if edge.to_side { 0 } else { 1 };
+                    node_map[to_node] = if edge.to_side {
+                        node_map[n2]
+                    } else {
+                        // If the edge changes sides, the node is mirrored
+                        node_map[n2].mirror()
+                    };
+                }
+            }
+        }
+
+        debug_assert_ne!(node_map[n1], MappedNode::Unmapped);
+        debug_assert_ne!(node_map[n2], MappedNode::Unmapped);
+
+        let (n1f, n1r) = match node_map[n1] {
+            MappedNode::Unmapped => unreachable!(),
+            MappedNode::Normal { forward, backward } => (forward, backward),
+            MappedNode::SelfMirror(node) => (node, node),
+        };
+        let (n2f, n2r) = match node_map[n2] {
+            MappedNode::Unmapped => unreachable!(),
+            MappedNode::Normal { forward, backward } => (forward, backward),
+            MappedNode::SelfMirror(node) => (node, node),
+        };
+
+        let edge = graph.add_edge(n1f, n2f, edge_factory(&record, false));
+        let mirror_edge = graph.add_edge(n2r, n1r, edge_factory(&record, true));
+        on_record(&record, edge, mirror_edge);
+    }
+
+    if !options.allow_empty && graph.edge_count() == 0 {
+        return Err(BCalm2IoError::BCalm2EmptyInput.into());
+    }
+    if options.verify_link_symmetry {
+        links.retain(|(_, edge)| !skipped_ids.contains(&edge.to_node));
+        verify_link_symmetry(links.iter().map(|(from_node, edge)| (*from_node, edge)))?;
+    }
+
+    Ok(graph)
+}
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation,
+/// pre-allocating the internal data structures of the reader for `capacity` records.
+///
+/// The capacity is only an estimate and does not need to be exact.
+pub fn read_bigraph_from_bcalm2_as_edge_centric_with_capacity<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+    capacity: usize,
+) -> crate::error::Result<Graph>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_as_edge_centric_with_options(
+        reader,
+        target_sequence_store,
+        kmer_size,
+        &BCalm2ReadOptions {
+            capacity,
+            ..Default::default()
+        },
+    )
+}
+
+/// Read a genome graph in bcalm2 fasta format into an edge-centric representation,
+/// ignoring all lines that start with one of the given comment prefixes.
+pub fn read_bigraph_from_bcalm2_as_edge_centric_with_comments<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> + Clone + Eq + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+    comment_prefixes: &[&str],
+) -> crate::error::Result<Graph>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
+{
+    read_bigraph_from_bcalm2_as_edge_centric(
+        CommentFilteringReader::new(reader, comment_prefixes),
+        target_sequence_store,
+        kmer_size,
+    )
+}
+
+/// Returns true if the `k - 1`-prefix of the given sequence equals the reverse complement of its `k - 1`-suffix.
+/// In an edge-centric graph, both ends of such a sequence are attached to the same self-mirror node.
+fn is_self_complemental<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+>(
+    source_sequence_store: &GenomeSequenceStore,
+    sequence_handle: &GenomeSequenceStore::Handle,
+    kmer_size: usize,
+) -> bool {
+    let sequence = source_sequence_store.get(sequence_handle);
+    sequence
+        .iter()
+        .zip(sequence.reverse_complement_iter())
+        .take(kmer_size - 1)
+        .all(|(a, b)| *a == b)
+}
+
+/// List the ids of the records of a bcalm2 file whose `k - 1`-prefix equals the reverse complement of their `k - 1`-suffix.
+///
+/// These are the palindromic unitigs that the edge-centric readers attach to self-mirror nodes.
+/// The sequences of all records are added to the sequence store.
+/// Returns an error if `kmer_size` is zero.
+pub fn find_self_complemental_unitigs<
+    R: std::io::BufRead,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+>(
+    reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+) -> crate::error::Result<Vec<usize>> {
+    if kmer_size == 0 {
+        return Err(crate::error::Error::ZeroKmerSize);
+    }
+
+    let mut ids = Vec::new();
+    for record in bio::io::fasta::Reader::new(reader).records() {
+        let record =
+            parse_bcalm2_fasta_record(record.map_err(BCalm2IoError::from)?, target_sequence_store)?;
+        if is_self_complemental(target_sequence_store, &record.sequence_handle, kmer_size) {
+            ids.push(record.id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Returns the links of the record of `edge_id` as tuples `(from_side, to_id, to_side)` in the order they are written.
+/// The mirror pair representatives are given by `output_edges`, and the id of a representative by `id_of`.
+fn edge_centric_out_neighbors<Graph: StaticEdgeCentricBigraph>(
+    graph: &Graph,
+    edge_id: Graph::EdgeIndex,
+    output_edges: &[bool],
+    id_of: impl Fn(Graph::EdgeIndex) -> usize,
+) -> crate::error::Result<Vec<(bool, usize, bool)>> {
+    let mirror_edge_id = graph
+        .mirror_edge_edge_centric(edge_id)
+        .ok_or_else(|| BCalm2IoError::BCalm2EdgeWithoutMirror)?;
+    let to_node_plus = graph.edge_endpoints(edge_id).to_node;
+    let to_node_minus = graph.edge_endpoints(mirror_edge_id).to_node;
+
+    let mut out_neighbors_plus = Vec::new();
+    let mut out_neighbors_minus = Vec::new();
+
+    for (to_node, out_neighbors, from_side) in [
+        (to_node_plus, &mut out_neighbors_plus, true),
+        (to_node_minus, &mut out_neighbors_minus, false),
+    ] {
+        for neighbor in graph.out_neighbors(to_node) {
+            let neighbor_edge_id = neighbor.edge_id.as_usize();
+            let neighbor_representative = if output_edges[neighbor_edge_id] {
+                neighbor.edge_id
+            } else {
+                graph
+                    .mirror_edge_edge_centric(neighbor.edge_id)
+                    .ok_or_else(|| BCalm2IoError::BCalm2EdgeWithoutMirror)?
+            };
+
+            out_neighbors.push((
+                from_side,
+                id_of(neighbor_representative),
+                output_edges[neighbor_edge_id],
+            ));
+        }
+    }
+
+    out_neighbors_plus.sort_unstable();
+    out_neighbors_minus.sort_unstable();
+    out_neighbors_plus.append(&mut out_neighbors_minus);
+    Ok(out_neighbors_plus)
+}
+
+/// List the links of an edge-centric bcalm2 graph in bcalm2 `L:` notation.
+///
+/// The links are resolved in the same way as by [`write_edge_centric_bigraph_to_bcalm2`] with default options.
+/// Each link is prefixed by the id of the record it belongs to, e.g. `0 L:+:1:-`.
+pub fn iter_links<
+    GenomeSequenceStoreHandle,
+    Graph: StaticEdgeCentricBigraph<EdgeData = PlainBCalm2NodeData<GenomeSequenceStoreHandle>>,
+>(
+    graph: &Graph,
+) -> crate::error::Result<impl Iterator<Item = String>> {
+    let mut output_edges = vec![false; graph.edge_count()];
+    for edge_id in graph.edge_indices() {
+        let mirror_edge_id = graph
+            .mirror_edge_edge_centric(edge_id)
+            .ok_or_else(|| BCalm2IoError::BCalm2EdgeWithoutMirror)?;
+        output_edges[edge_id.as_usize()] = edge_id.as_usize() <= mirror_edge_id.as_usize();
+    }
+
+    let mut links = Vec::new();
+    for edge_id in graph.edge_indices() {
+        if output_edges[edge_id.as_usize()] {
+            let id = graph.edge_data(edge_id).id;
+            for (from_side, to_id, to_side) in
+                edge_centric_out_neighbors(graph, edge_id, &output_edges, |representative| {
+                    graph.edge_data(representative).id
+                })?
+            {
+                links.push(format!(
+                    "{id} L:{}:{to_id}:{}",
+                    if from_side { "+" } else { "-" },
+                    if to_side { "+" } else { "-" }
+                ));
+            }
+        }
+    }
+
+    Ok(links.into_iter())
+}
+
+/// Write a genome graph in bcalm2 fasta format from an edge-centric representation to a file.
+pub fn write_edge_centric_bigraph_to_bcalm2_to_file<
+    P: AsRef<Path>,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData, //: Into<PlainBCalm2NodeData<IndexType>>,
+    EdgeData: BidirectedData + Clone + Eq,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    path: P,
+) -> crate::error::Result<()>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a EdgeData>,
+{
+    write_edge_centric_bigraph_to_bcalm2(graph, source_sequence_store, File::create(path)?)
+}
+
+/// Write a genome graph in bcalm2 fasta format from an edge-centric representation to a bgzip-compressed file.
+///
+/// The file can be read back with [`read_bigraph_from_bcalm2_as_edge_centric_from_file`].
+#[cfg(feature = "bgzip")]
+pub fn write_edge_centric_bigraph_to_bcalm2_to_bgzip_file<
+    P: AsRef<Path>,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    EdgeData: BidirectedData + Clone + Eq,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    path: P,
+) -> crate::error::Result<()>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a EdgeData>,
+{
+    let mut writer = crate::io::bgzf::BgzfWriter::new(File::create(path)?);
+    write_edge_centric_bigraph_to_bcalm2(graph, source_sequence_store, &mut writer)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Write a genome graph in bcalm2 fasta format from an edge-centric representation.
+///
+/// The tags of each record are always written in the order `LN`, `KC`, `km`, `L`.
+/// Hence, files with a different tag order round-trip with equal values, but not byte by byte.
+/// Mean abundances are written in fixed-point notation with one decimal place,
+/// so a mean abundance read from scientific notation such as `km:f:3.0e0` is written as `km:f:3.0`.
+pub fn write_edge_centric_bigraph_to_bcalm2<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    EdgeData: BidirectedData + Clone + Eq,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    writer: W,
+) -> crate::error::Result<()>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a EdgeData>,
+{
+    write_edge_centric_bigraph_to_bcalm2_with_options(
+        graph,
+        source_sequence_store,
+        writer,
+        &BCalm2WriteOptions::default(),
+    )
+}
+
+/// Write a genome graph in bcalm2 fasta format from an edge-centric representation with the given options.
+pub fn write_edge_centric_bigraph_to_bcalm2_with_options<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    EdgeData: BidirectedData + Clone + Eq,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    writer: W,
+    options: &BCalm2WriteOptions,
+) -> crate::error::Result<()>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a EdgeData>,
+{
+    write_edge_centric_bigraph_to_bcalm2_with_warnings(
+        graph,
+        source_sequence_store,
+        writer,
+        options,
+    )
+    .map(|_| ())
+}
+
+/// Write a genome graph in bcalm2 fasta format from an edge-centric representation with the given options.
+/// Returns warnings about all data that the bcalm2 format cannot represent exactly.
+pub fn write_edge_centric_bigraph_to_bcalm2_with_warnings<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData,
+    EdgeData: BidirectedData + Clone + Eq,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    writer: W,
+    options: &BCalm2WriteOptions,
+) -> crate::error::Result<Vec<WriteWarning>>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a EdgeData>,
+{
+    options.validate()?;
+    let mut warnings = Vec::new();
+    let mut writer = bio::io::fasta::Writer::new(writer);
+    let output_edges =
+        edge_centric_representatives(graph, source_sequence_store, options.representative)?;
+    let output_order: Vec<_> = if options.sort_by_id {
+        let mut ids: Vec<_> = graph
+            .edge_indices()
+            .filter(|edge_id| output_edges[edge_id.as_usize()])
+            .map(|edge_id| {
+                (
+                    PlainBCalm2NodeData::from(graph.edge_data(edge_id)).id,
+                    edge_id,
+                )
+            })
+            .collect();
+        ids.sort_unstable_by_key(|&(id, edge_id)| (id, edge_id.as_usize()));
+        ids.into_iter().map(|(_, edge_id)| edge_id).collect()
+    } else {
+        graph
+            .edge_indices()
+            .filter(|edge_id| output_edges[edge_id.as_usize()])
+            .collect()
+    };
+
+    let printed_ids = if options.reassign_ids {
+        let mut printed_ids = vec![usize::MAX; graph.edge_count()];
+        for (printed_id, edge_id) in output_order.iter().enumerate() {
+            printed_ids[edge_id.as_usize()] = printed_id;
+        }
+        Some(printed_ids)
+    } else {
+        None
+    };
+
+    for edge_id in output_order {
+        let mut node_data = PlainBCalm2NodeData::from(graph.edge_data(edge_id));
+        options.derive_node_data(&mut node_data);
+        let strands_unchanged = Cell::new(node_data.forwards);
+        let out_neighbors =
+            edge_centric_out_neighbors(graph, edge_id, &output_edges, |representative| {
+                let representative_data =
+                    PlainBCalm2NodeData::from(graph.edge_data(representative));
+                strands_unchanged.set(strands_unchanged.get() && representative_data.forwards);
+                if let Some(printed_ids) = &printed_ids {
+                    printed_ids[representative.as_usize()]
+                } else {
+                    representative_data.id
+                }
+            })?;
+
+        let printed_node_id = format_node_id(
+            printed_ids
+                .as_ref()
+                .map_or(node_data.id, |printed_ids| printed_ids[edge_id.as_usize()]),
+        )?;
+        let node_description = options.describe_node(
+            &node_data,
+            out_neighbors,
+            strands_unchanged.get(),
+            &mut warnings,
+        )?;
+        let node_sequence = oriented_sequence_vec(&node_data, source_sequence_store);
+        collect_write_warnings(&node_data, &mut warnings);
+
+        writer
+            .write(
+                &printed_node_id,
+                non_empty_description(&node_description),
+                &node_sequence,
+            )
+            .map_err(BCalm2IoError::from)?;
+    }
+
+    Ok(warnings)
+}
+
+/// Returns for each edge of an edge-centric graph if it is the representative of its mirror pair.
+fn edge_centric_representatives<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    EdgeData,
+    Graph: StaticEdgeCentricBigraph<EdgeData = EdgeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    representative: RepresentativeChoice,
+) -> crate::error::Result<Vec<bool>>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a EdgeData>,
+{
+    let mut output_edges = vec![false; graph.edge_count()];
+
+    for edge_id in graph.edge_indices() {
+        let mirror_edge_id = graph
+            .mirror_edge_edge_centric(edge_id)
+            .ok_or_else(|| BCalm2IoError::BCalm2EdgeWithoutMirror)?;
+        output_edges[edge_id.as_usize()] = is_representative(
+            edge_id.as_usize(),
+            &PlainBCalm2NodeData::from(graph.edge_data(edge_id)),
+            mirror_edge_id.as_usize(),
+            &PlainBCalm2NodeData::from(graph.edge_data(mirror_edge_id)),
+            source_sequence_store,
+            representative,
+        );
+    }
+
+    Ok(output_edges)
+}
+
+/// Convert an edge-centric representation of a genome graph into the bcalm2 records it represents.
+///
+/// Each mirror pair of edges results in one record, whose links are reconstructed from the junctions of the graph
+/// in the same way as by [`write_edge_centric_bigraph_to_bcalm2`] with default options.
+/// The records are returned in the order of their representative edges.
+pub fn edge_centric_to_records<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    EdgeData: BidirectedData + Clone + Eq,
+    Graph: StaticEdgeCentricBigraph<EdgeData = EdgeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+) -> crate::error::Result<Vec<PlainBCalm2NodeData<GenomeSequenceStore::Handle>>>
+where
+    PlainBCalm2NodeData<GenomeSequenceStore::Handle>: for<'a> From<&'a EdgeData>,
+{
+    let output_edges = edge_centric_representatives(
+        graph,
+        source_sequence_store,
+        BCalm2WriteOptions::default().representative,
+    )?;
+
+    let mut records = Vec::new();
+    for edge_id in graph.edge_indices() {
+        if output_edges[edge_id.as_usize()] {
+            let mut record = PlainBCalm2NodeData::from(graph.edge_data(edge_id));
+            record.edges =
+                edge_centric_out_neighbors(graph, edge_id, &output_edges, |representative| {
+                    PlainBCalm2NodeData::from(graph.edge_data(representative)).id
+                })?
+                .into_iter()
+                .map(|(from_side, to_node, to_side)| PlainBCalm2Edge {
+                    from_side,
+                    to_node,
+                    to_side,
+                })
+                .collect();
+            records.push(record);
+        }
+    }
+
+    Ok(records)
+}
+
+/// Verify that a bcalm2 fasta file and the gfa file converted from it describe the same graph.
+///
+/// Both files are read into node-centric graphs, which are compared by their non-branching paths:
+/// the graphs are equal if they have the same multiset of canonical unitig sequences
+/// and the same multiset of links between the oriented unitigs, see [`bidirected_isomorphic`](crate::algorithms::diff::bidirected_isomorphic).
+/// Hence, a link that is missing or differs in either file is detected, even if all sequences match.
+/// The ids in both files are ignored, so the gfa file may number its segments differently.
+///
+/// If the graphs differ or the gfa file declares a different k-mer size,
+/// a [`BCalm2IoError::BCalm2GfaMismatch`] describing the first difference is returned.
+pub fn verify_bcalm2_fasta_matches_gfa<
+    R1: std::io::BufRead,
+    R2: std::io::BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + Debug + 'static,
+>(
+    fasta_reader: R1,
+    gfa_reader: R2,
+    kmer_size: usize,
+) -> crate::error::Result<()> {
+    type FastaGraph<Handle> = NodeBigraphWrapper<PetGraph<RawSequenceData<Handle>, ()>>;
+    type GfaGraph<Handle> =
+        NodeBigraphWrapper<PetGraph<RawSequenceData<Handle>, BidirectedGfaEdgeData<()>>>;
+
+    let mismatch = |description: String| -> crate::error::Error {
+        BCalm2IoError::BCalm2GfaMismatch { description }.into()
+    };
+
+    let mut sequence_store = DefaultSequenceStore::<AlphabetType>::default();
+    let fasta_graph: FastaGraph<_> =
+        read_bigraph_from_bcalm2_as_node_centric(fasta_reader, &mut sequence_store)?;
+    let (gfa_graph, gfa_properties): (GfaGraph<_>, _) =
+        read_gfa_as_bigraph(gfa_reader, &mut sequence_store, false, false)?;
+
+    let (fasta_unitigs, fasta_edges) =
+        canonical_unitig_graph(&fasta_graph, &sequence_store, kmer_size)?;
+    let (gfa_unitigs, gfa_edges) = canonical_unitig_graph(&gfa_graph, &sequence_store, kmer_size)?;
+
+    if gfa_properties.k != kmer_size {
+        return Err(mismatch(format!(
+            "the gfa file has k = {}, but k = {kmer_size} was expected",
+            gfa_properties.k
+        )));
+    }
+    if let Some(((sequence, _), fasta_count, gfa_count)) =
+        first_count_difference(&fasta_unitigs, &gfa_unitigs)
+    {
+        return Err(mismatch(format!(
+            "the fasta file contains unitig {} {fasta_count} times, but the gfa file contains it {gfa_count} times",
+            String::from_utf8_lossy(sequence)
+        )));
+    }
+    if let Some(((from, to), fasta_count, gfa_count)) =
+        first_count_difference(&fasta_edges, &gfa_edges)
+    {
+        return Err(mismatch(format!(
+            "the fasta file contains the link from unitig {} to unitig {} {fasta_count} times, but the gfa file contains it {gfa_count} times",
+            String::from_utf8_lossy(from),
+            String::from_utf8_lossy(to)
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the smallest key whose counts differ between the two maps, together with both counts.
+fn first_count_difference<'a, Key: Ord>(
+    first: &'a BTreeMap<Key, usize>,
+    second: &'a BTreeMap<Key, usize>,
+) -> Option<(&'a Key, usize, usize)> {
+    first
+        .keys()
+        .chain(second.keys())
+        .filter_map(|key| {
+            let first_count = first.get(key).copied().unwrap_or(0);
+            let second_count = second.get(key).copied().unwrap_or(0);
+            (first_count != second_count).then_some((key, first_count, second_count))
+        })
+        .min_by(|(a, _, _), (b, _, _)| a.cmp(b))
+}
+
+/// Replace the ids of the nodes of a node-centric bcalm2 graph according to the given mapping.
+///
+/// Ids that are not contained in the mapping stay unchanged.
+/// If the new ids of two nodes that are not mirrors of each other are equal, an error is returned and the graph is not modified.
+///
+/// The writers output the records in the order of the nodes, so the written ids are in general not sorted anymore.
+/// Such files can be read back with [`read_bigraph_from_bcalm2_as_node_centric`] and the other node-centric readers,
+/// which only require the ids to be unique.
+pub fn apply_id_mapping<
+    GenomeSequenceStoreHandle,
+    Graph: DynamicBigraph<NodeData = PlainBCalm2NodeData<GenomeSequenceStoreHandle>>,
+>(
+    graph: &mut Graph,
+    mapping: &HashMap<usize, usize>,
+) -> crate::error::Result<()> {
+    let mut original_ids = HashMap::new();
+    for node_id in graph.node_indices() {
+        let id = graph.node_data(node_id).id;
+        let new_id = mapping.get(&id).copied().unwrap_or(id);
+        if let Some(original_id) = original_ids.insert(new_id, id) {
+            if original_id != id {
+                return Err(BCalm2IoError::BCalm2DuplicateNodeId { id: new_id }.into());
+            }
+        }
+    }
+
+    let node_ids: Vec<_> = graph.node_indices().collect();
+    for node_id in node_ids {
+        let node_data = graph.node_data_mut(node_id);
+        if let Some(&new_id) = mapping.get(&node_data.id) {
+            node_data.id = new_id;
+        }
+    }
+
+    Ok(())
+}
+
+///////////////////////
+////// RECORD IO //////
+///////////////////////
+
+/// Write a sequence of records in bcalm2 fasta format without constructing a graph.
+///
+/// Each record is written with its own id and the edges stored in its data.
+/// No mirror-pair representatives are selected, so the records are expected to contain each node exactly once.
+///
+/// The stored edges are relative to the forward strand of their record.
+/// If a record is not `forwards`, then its sequence is written reverse-complemented,
+/// and the side of the record is flipped in each of its edges to match.
+/// The side of the neighbor is written as stored.
+pub fn write_bcalm2_records<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    Records: IntoIterator<Item = PlainBCalm2NodeData<GenomeSequenceStore::Handle>>,
+>(
+    records: Records,
+    source_sequence_store: &GenomeSequenceStore,
+    mut writer: bio::io::fasta::Writer<W>,
+) -> crate::error::Result<()> {
+    for record in records {
+        write_bcalm2_record(&record, source_sequence_store, &mut writer)?;
+    }
+
+    Ok(())
+}
+
+/// Write a single record in bcalm2 fasta format with its own id and the edges stored in its data.
+fn write_bcalm2_record<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+>(
+    record: &PlainBCalm2NodeData<GenomeSequenceStore::Handle>,
+    source_sequence_store: &GenomeSequenceStore,
+    writer: &mut bio::io::fasta::Writer<W>,
+) -> crate::error::Result<()> {
+    let out_neighbors = record
+        .edges
+        .iter()
+        .map(|edge| {
+            (
+                edge.from_side == record.forwards,
+                edge.to_node,
+                edge.to_side,
+            )
+        })
+        .collect();
+
+    let printed_node_id = format_node_id(record.id)?;
+    let node_description = write_plain_bcalm2_node_data_to_bcalm2(record, out_neighbors)?;
+    let node_sequence = oriented_sequence_vec(record, source_sequence_store);
+
+    writer
+        .write(
+            &printed_node_id,
+            non_empty_description(&node_description),
+            &node_sequence,
+        )
+        .map_err(BCalm2IoError::from)?;
+    Ok(())
+}
+
+/// Transform a file in bcalm2 fasta format record by record, without constructing a graph.
+///
+/// Each record is passed to `transform`, and the returned record is written to `writer`.
+/// If `transform` returns `None`, the record is dropped.
+/// The edges are written as stored in the returned records, so removing edges to dropped records is up to `transform`.
+///
+/// The store is taken by value and only used as scratch space.
+/// The sequence of each record is stored in it only until the record is written,
+/// after which the store is reset to its default, so the memory usage is bounded by the largest record.
+/// Any previous contents of the store are discarded, and the store is dropped at the end.
+pub fn transform_bcalm2_streaming<
+    R: std::io::BufRead,
+    W: std::io::Write,
+    AlphabetType: Alphabet + 'static,
+    GenomeSequenceStore: SequenceStore<AlphabetType> + Default,
+    F: FnMut(
+        PlainBCalm2NodeData<GenomeSequenceStore::Handle>,
+    ) -> Option<PlainBCalm2NodeData<GenomeSequenceStore::Handle>>,
+>(
+    reader: R,
+    mut scratch_sequence_store: GenomeSequenceStore,
+    mut writer: bio::io::fasta::Writer<W>,
+    mut transform: F,
+) -> crate::error::Result<()> {
+    for record in bio::io::fasta::Reader::new(reader).records() {
+        let record = parse_bcalm2_fasta_record(
+            record.map_err(BCalm2IoError::from)?,
+            &mut scratch_sequence_store,
+        )?;
+        if let Some(record) = transform(record) {
+            write_bcalm2_record(&record, &scratch_sequence_store, &mut writer)?;
+        }
+        scratch_sequence_store = GenomeSequenceStore::default();
+    }
+
+    Ok(())
+}
+
+/// An index of the byte offsets of the records in a bcalm2 file.
+/// This allows to parse single records without reading the whole file.
+#[derive(Debug, Clone, Default)]
+pub struct BCalm2Index {
+    offsets: HashMap<usize, u64>,
+}
+
+impl BCalm2Index {
+    /// Build an index by scanning the given reader for record headers, starting from its current position.
+    /// The offsets are absolute positions in the reader, such that [`Self::get_record`] can seek to them directly.
+    pub fn build<R: std::io::BufRead + std::io::Seek>(mut reader: R) -> crate::error::Result<Self> {
+        let mut offsets = HashMap::new();
+        let mut offset = reader.stream_position().map_err(BCalm2IoError::from)?;
+        let mut line = Vec::new();
+
+        loop {
+            line.clear();
+            let length = reader
+                .read_until(b'\n', &mut line)
+                .map_err(BCalm2IoError::from)?;
+            if length == 0 {
+                break;
+            }
+
+            if let Some(header) = line.strip_prefix(b">") {
+                let id = header
+                    .split(|c| c.is_ascii_whitespace())
+                    .next()
+                    .unwrap_or(&[]);
+                let id = String::from_utf8_lossy(id);
+                let id = id
+                    .parse()
+                    .map_err(|_| BCalm2IoError::BCalm2IdError { id: id.to_string() })?;
+                if offsets.insert(id, offset).is_some() {
+                    return Err(BCalm2IoError::BCalm2DuplicateNodeId { id }.into());
+                }
+            }
+
+            offset += length as u64;
+        }
+
+        Ok(Self { offsets })
+    }
+
+    /// Returns the absolute byte offset of the record with the given id.
+    pub fn offset(&self, id: usize) -> Option<u64> {
+        self.offsets.get(&id).copied()
+    }
+
+    /// Seek to the record with the given id and parse it.
+    /// The reader must contain the same data as the reader used for building, since the offsets are absolute.
+    pub fn get_record<
+        R: std::io::Read + std::io::Seek,
+        AlphabetType: Alphabet + 'static,
+        GenomeSequenceStore: SequenceStore<AlphabetType>,
+    >(
+        &self,
+        reader: &mut R,
+        id: usize,
+        target_sequence_store: &mut GenomeSequenceStore,
+    ) -> crate::error::Result<PlainBCalm2NodeData<GenomeSequenceStore::Handle>> {
+        let offset = self
+            .offset(id)
+            .ok_or(BCalm2IoError::BCalm2MissingRecord { id })?;
+        reader
+            .seek(std::io::SeekFrom::Start(offset))
+            .map_err(BCalm2IoError::from)?;
+
+        let record = bio::io::fasta::Reader::new(reader)
+            .records()
+            .next()
+            .ok_or(BCalm2IoError::BCalm2MissingRecord { id })?
+            .map_err(BCalm2IoError::from)?;
+        parse_bcalm2_fasta_record(record, target_sequence_store)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::bcalm2::error::BCalm2IoError;
+    use crate::io::bcalm2::{
+        apply_id_mapping, edge_centric_to_records, find_self_complemental_unitigs, iter_links,
+        oriented_sequence_vec, parse_bcalm2_fasta_record, read_and_compact_bcalm2,
+        read_bcalm2_topology_only, read_bigraph_from_bcalm2_as_edge_centric,
+        read_bigraph_from_bcalm2_as_edge_centric_old,
+        read_bigraph_from_bcalm2_as_edge_centric_with_capacity,
+        read_bigraph_from_bcalm2_as_edge_centric_with_edge_factory,
+        read_bigraph_from_bcalm2_as_edge_centric_with_id_map,
+        read_bigraph_from_bcalm2_as_edge_centric_with_optional_kmer_size,
+        read_bigraph_from_bcalm2_as_edge_centric_with_options,
+        read_bigraph_from_bcalm2_as_edge_centric_with_overlaps,
+        read_bigraph_from_bcalm2_as_node_centric,
+        read_bigraph_from_bcalm2_as_node_centric_from_files,
+        read_bigraph_from_bcalm2_as_node_centric_min_length,
+        read_bigraph_from_bcalm2_as_node_centric_min_length_with_report,
+        read_bigraph_from_bcalm2_as_node_centric_subgraph,
+        read_bigraph_from_bcalm2_as_node_centric_with_capacity,
+        read_bigraph_from_bcalm2_as_node_centric_with_comments,
+        read_bigraph_from_bcalm2_as_node_centric_with_edge_data,
+        read_bigraph_from_bcalm2_as_node_centric_with_options, transform_bcalm2_streaming,
+        try_parse_bcalm2, verify_bcalm2_fasta_matches_gfa, write_bcalm2_records,
+        write_edge_centric_bigraph_to_bcalm2, write_edge_centric_bigraph_to_bcalm2_with_options,
+        write_node_centric_bigraph_to_bcalm2, write_node_centric_bigraph_to_bcalm2_with_options,
+        write_node_centric_bigraph_to_bcalm2_with_warnings, write_node_centric_component_to_bcalm2,
+        BCalm2IdScope, BCalm2Index, BCalm2NodeData, BCalm2ReadOptions, BCalm2WriteOptions,
+        CompactBCalm2Edge, InvalidCharPolicy, OwnedBCalm2Record, PlainBCalm2Edge,
+        PlainBCalm2NodeData, ReadReport, RepresentativeChoice, WriteWarning,
+    };
+    use crate::io::SequenceData;
+    use crate::types::{PetBCalm2EdgeGraph, PetBCalm2NodeGraph};
+    use bigraph::implementation::node_bigraph_wrapper::NodeBigraphWrapper;
+    use bigraph::interface::dynamic_bigraph::DynamicBigraph;
+    use bigraph::interface::static_bigraph::{StaticBigraph, StaticEdgeCentricBigraph};
+    use bigraph::interface::BidirectedData;
+    use bigraph::traitgraph::implementation::petgraph_impl::PetGraph;
+    use bigraph::traitgraph::index::GraphIndex;
+    use bigraph::traitgraph::interface::{Edge, ImmutableGraphContainer, MutableGraphContainer};
+    use compact_genome::implementation::bit_vec_sequence_store::BitVectorSequenceStore;
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultGenome, DefaultSequenceStore,
+    };
+    use compact_genome::interface::sequence::GenomeSequence;
+    use compact_genome::interface::sequence_store::SequenceStore;
+    use std::collections::{HashMap, HashSet};
+    use std::io::{BufReader, Seek};
+
+    #[test]
+    fn test_node_read_write() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let input = Vec::from(test_file);
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_node_read_write_self_loops() {
+        let test_file: &'static [u8] = b">0 LN:i:5 L:+:0:- L:+:0:+ L:+:1:+ L:-:0:-\n\
+            ATGAT\n\
+            >1 LN:i:5 L:-:0:-\n\
+            ATCCG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 5);
+        assert!(graph.verify_node_pairing());
+        let node = graph.node_indices().next().unwrap();
+        let mirror_node = graph.mirror_node(node).unwrap();
+        let endpoints: Vec<_> = graph
+            .edge_indices()
+            .map(|edge| {
+                let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+                (from_node, to_node)
+            })
+            .collect();
+        // Each self-loop exists exactly once, and the reverse self-loop is its own mirror.
+        for (expected_endpoints, count) in [
+            ((node, node), 1),
+            ((mirror_node, mirror_node), 1),
+            ((node, mirror_node), 1),
+            ((mirror_node, node), 0),
+        ] {
+            assert_eq!(
+                endpoints
+                    .iter()
+                    .filter(|&&endpoints| endpoints == expected_endpoints)
+                    .count(),
+                count
+            );
+        }
+
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(output, test_file);
+
+        let graph: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric_with_options(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                &BCalm2ReadOptions {
+                    verify_link_symmetry: true,
+                    ..Default::default()
+                },
+            );
+        assert_eq!(graph.unwrap().edge_count(), 5);
+    }
+
+    #[test]
+    fn test_edge_read_write() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
+        let input = Vec::from(test_file);
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        let mut old_output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+        debug_assert_eq!(
+            output,
+            old_output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(output.clone()).unwrap(),
+            String::from_utf8(old_output.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_edge_read_write_self_loops() {
+        let test_file: &'static [u8] =
+            b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:0:+ L:+:1:- L:-:0:- L:-:2:+\n\
+            AAA\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAATT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:0:+ L:-:1:-\n\
+            TTGATG\n";
+        let input = Vec::from(test_file);
+        println!("{}", String::from_utf8(input.clone()).unwrap());
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        let mut old_output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+
+        // expect self-loops
+        assert_eq!(graph.node_count(), 6);
+        assert!(graph
+            .node_indices()
+            .all(|node| !graph.is_self_mirror_node(node)));
+        assert_eq!(old_graph.node_count(), 6);
+        assert!(old_graph
+            .node_indices()
+            .all(|node| !old_graph.is_self_mirror_node(node)));
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+        debug_assert_eq!(
+            output,
+            old_output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(output.clone()).unwrap(),
+            String::from_utf8(old_output.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_edge_read_write_plus_minus_loop() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:0:- L:+:1:- L:+:2:+\n\
+            CAT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:1:- L:+:2:+\n\
+            GGTCTCGGGTAAAT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:0:- L:-:1:- L:-:2:+\n\
+            ATGATT\n";
+        let input = Vec::from(test_file);
+        println!("{}", String::from_utf8(input.clone()).unwrap());
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        let mut old_output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+
+        // expect self-mirror nodes
+        assert_eq!(graph.node_count(), 7);
+        assert!(graph
+            .node_indices()
+            .any(|node| graph.is_self_mirror_node(node)));
+        assert_eq!(old_graph.node_count(), 7);
+        assert!(old_graph
+            .node_indices()
+            .any(|node| old_graph.is_self_mirror_node(node)));
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+        debug_assert_eq!(
+            output,
+            old_output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(output.clone()).unwrap(),
+            String::from_utf8(old_output.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_edge_read_write_minus_plus_loop() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:- L:-:0:+\n\
+            ATG\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAACA\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            CAGATT\n";
+        let input = Vec::from(test_file);
+        println!("{}", String::from_utf8(input.clone()).unwrap());
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        let mut old_output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+
+        // expect self-mirror nodes
+        assert_eq!(graph.node_count(), 7);
+        assert!(graph
+            .node_indices()
+            .any(|node| graph.is_self_mirror_node(node)));
+        assert_eq!(old_graph.node_count(), 7);
+        assert!(old_graph
+            .node_indices()
+            .any(|node| old_graph.is_self_mirror_node(node)));
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+        debug_assert_eq!(
+            output,
+            old_output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(output.clone()).unwrap(),
+            String::from_utf8(old_output.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_edge_read_write_plus_minus_and_minus_plus_loop() {
+        let test_file: &'static [u8] =
+            b">0 LN:i:4 KC:i:4 km:f:3.0 L:+:0:- L:+:1:- L:+:2:+ L:-:0:+\n\
+            CGAT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:1:- L:+:2:+\n\
+            GGTCTCGGGTAAAT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:0:- L:-:1:- L:-:2:+\n\
+            ATGATG\n";
+        let input = Vec::from(test_file);
+        println!("{}", String::from_utf8(input.clone()).unwrap());
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        let mut old_output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+
+        // expect self-mirror nodes but not edges
+        assert_eq!(graph.node_count(), 6);
+        assert!(graph
+            .node_indices()
+            .any(|node| graph.is_self_mirror_node(node)));
+        assert!(graph.edge_indices().all(|edge| {
+            let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+            graph.mirror_node(from_node) != Some(to_node)
+        }));
+        assert_eq!(old_graph.node_count(), 6);
+        assert!(old_graph
+            .node_indices()
+            .any(|node| old_graph.is_self_mirror_node(node)));
+        assert!(old_graph.edge_indices().all(|edge| {
+            let Edge { from_node, to_node } = old_graph.edge_endpoints(edge);
+            old_graph.mirror_node(from_node) != Some(to_node)
+        }));
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+        debug_assert_eq!(
+            output,
+            old_output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(output.clone()).unwrap(),
+            String::from_utf8(old_output.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_edge_read_self_mirror_edge() {
+        let test_file: &'static [u8] = b">0 LN:i:5 KC:i:4 km:f:3.0 L:+:1:+ L:-:1:+\n\
+            ACTGT\n\
+            >1 LN:i:4 KC:i:2 km:f:3.2 L:-:0:- L:-:0:+ L:-:2:-\n\
+            GTTC\n\
+            >2 LN:i:4 KC:i:15 km:f:2.2 L:+:1:+\n\
+            GGGT\n";
+        let input = Vec::from(test_file);
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        let mut old_output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+
+        // expect self-mirror edges
+        assert_eq!(graph.node_count(), 6);
+        assert!(graph
+            .node_indices()
+            .all(|node| !graph.is_self_mirror_node(node)));
+        assert!(graph.edge_indices().any(|edge| {
+            let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+            graph.mirror_node(from_node) == Some(to_node)
+        }));
+        assert_eq!(old_graph.node_count(), 6);
+        assert!(old_graph
+            .node_indices()
+            .all(|node| !old_graph.is_self_mirror_node(node)));
+        assert!(old_graph.edge_indices().any(|edge| {
+            let Edge { from_node, to_node } = old_graph.edge_endpoints(edge);
+            old_graph.mirror_node(from_node) == Some(to_node)
+        }));
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+        debug_assert_eq!(
+            output,
+            old_output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(output.clone()).unwrap(),
+            String::from_utf8(old_output.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_edge_read_self_mirror_node_and_edge() {
+        let test_file: &'static [u8] = b">0 LN:i:5 KC:i:4 km:f:3.0 L:+:0:- L:+:0:+ L:+:1:+ L:+:2:- L:-:0:- L:-:0:+ L:-:1:+ L:-:2:-\n\
+            ATTAT\n\
+            >1 LN:i:5 KC:i:2 km:f:3.2 L:-:0:- L:-:0:+ L:-:1:+ L:-:2:-\n\
+            ATGTC\n\
+            >2 LN:i:4 KC:i:15 km:f:2.2 L:+:0:- L:+:0:+ L:+:1:+ L:+:2:-\n\
+            GGAT\n";
+        let input = Vec::from(test_file);
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        let mut old_output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+
+        // expect self-mirror edges
+        assert_eq!(graph.node_count(), 5);
+        assert!(graph
+            .node_indices()
+            .any(|node| graph.is_self_mirror_node(node)));
+        assert!(graph.edge_indices().any(|edge| {
+            let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+            graph.mirror_node(from_node) == Some(to_node)
+        }));
+        assert_eq!(old_graph.node_count(), 5);
+        assert!(old_graph
+            .node_indices()
+            .any(|node| old_graph.is_self_mirror_node(node)));
+        assert!(old_graph.edge_indices().any(|edge| {
+            let Edge { from_node, to_node } = old_graph.edge_endpoints(edge);
+            old_graph.mirror_node(from_node) == Some(to_node)
+        }));
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+        debug_assert_eq!(
+            output,
+            old_output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(output.clone()).unwrap(),
+            String::from_utf8(old_output.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_edge_read_write_forward_merge() {
+        let test_file: &'static [u8] = b"\
+            >0 LN:i:3 KC:i:4 km:f:3.0 L:+:2:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:2:-\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:+:0:- L:+:1:-\n\
+            AAGAAC\n";
+        let input = Vec::from(test_file);
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        let mut old_output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+        debug_assert_eq!(
+            output,
+            old_output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(output.clone()).unwrap(),
+            String::from_utf8(old_output.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_edge_read_write_multigraph() {
+        let test_file: &'static [u8] = b"\
+            >0 LN:i:7 KC:i:4 km:f:3.0 L:+:2:+ L:-:2:-\n\
+            AGTTCTC\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:2:+ L:-:2:-\n\
+            AGTCTCGGGTAATC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:+:0:+ L:+:1:+ L:-:0:- L:-:1:-\n\
+            TCGAAG\n";
+        let input = Vec::from(test_file);
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        let mut old_output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+        debug_assert_eq!(
+            output,
+            old_output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(output.clone()).unwrap(),
+            String::from_utf8(old_output.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_canonical_kmers() {
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let sequence_handle = sequence_store.add_from_slice_u8(b"AGTC").unwrap();
+        let node_data = PlainBCalm2NodeData {
+            id: 0,
+            sequence_handle,
+            forwards: true,
+            length: Some(4),
+            total_abundance: None,
+            mean_abundance: None,
+            colors: None,
+            raw_description: None,
+            edges: Vec::new(),
+        };
+
+        let kmers: Vec<_> = node_data
+            .canonical_kmers(&sequence_store, 3)
+            .map(|kmer| kmer.as_string())
+            .collect();
+        assert_eq!(kmers, vec!["ACT".to_string(), "GAC".to_string()]);
+
+        let kmers: Vec<_> = node_data
+            .mirror()
+            .canonical_kmers(&sequence_store, 3)
+            .map(|kmer| kmer.as_string())
+            .collect();
+        assert_eq!(kmers, vec!["GAC".to_string(), "ACT".to_string()]);
+
+        assert_eq!(node_data.canonical_kmers(&sequence_store, 5).count(), 0);
+    }
+
+    #[test]
+    fn test_node_write_reassign_ids() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let input = Vec::from(test_file);
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let mut graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        // Make the ids non-contiguous, as they would be after e.g. removing nodes.
+        for node_id in graph.node_indices().collect::<Vec<_>>() {
+            let node_data = graph.node_data_mut(node_id);
+            node_data.id = node_data.id * 10 + 7;
+        }
+
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2_with_options(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+            &BCalm2WriteOptions {
+                reassign_ids: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        debug_assert_eq!(
+            input,
+            output,
+            "in:\n{}\n\nout:\n{}\n",
+            String::from_utf8(input.clone()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+
+        // Insert a synthetic mirror pair whose id collides with an existing node.
+        let mut synthetic_node_data = graph.node_data(1.into()).clone();
+        synthetic_node_data.edges.clear();
+        let synthetic_mirror_node_data = synthetic_node_data.mirror();
+        let synthetic_node = graph.add_node(synthetic_node_data);
+        let synthetic_mirror_node = graph.add_node(synthetic_mirror_node_data);
+        graph.set_mirror_nodes(synthetic_node, synthetic_mirror_node);
+
+        let result = write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(Vec::new()),
+        );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2DuplicateNodeId { id: 17 }
+            ))
+        ));
+
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2_with_options(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+            &BCalm2WriteOptions {
+                reassign_ids: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mut expected = input;
+        expected.extend_from_slice(b">3 LN:i:14 KC:i:2 km:f:3.2\nGGTCTCGGGTAAGT\n");
+        assert_eq!(
+            String::from_utf8(expected).unwrap(),
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_node_read_crlf() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\r\n\
+            AGT\r\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\r\n\
+            GGTCTCGGGTAAGT\r\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\r\n\
+            ATGATG\r\n";
+        let expected: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(expected.to_vec()).unwrap(),
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_node_read_minimal_node_data() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: NodeBigraphWrapper<PetGraph<BCalm2NodeData<_>, ()>> =
+            read_bigraph_from_bcalm2_as_node_centric(
+                BufReader::new(test_file),
+                &mut sequence_store,
+            )
+            .unwrap();
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 4);
+
+        let node_data = graph.node_data(2.into());
+        assert_eq!(node_data.id, 2);
+        assert!(node_data.forwards);
+        assert_eq!(node_data.total_abundance, Some(15));
+        assert_eq!(node_data.mean_abundance, Some(2.2));
+
+        let mirror_data = graph.node_data(graph.mirror_node(2.into()).unwrap());
+        assert_eq!(mirror_data.id, 2);
+        assert!(!mirror_data.forwards);
+        let mirror_sequence: DefaultGenome<DnaAlphabet> =
+            mirror_data.sequence_owned(&sequence_store);
+        assert_eq!(mirror_sequence.clone_as_vec(), b"CATCAT".to_vec());
+    }
+
+    #[test]
+    fn test_node_write_canonical_representatives() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        // Nodes 0 and 1 are written as reverse complements, which also flips the signs of their edges.
+        // The records stay in the order in which they were read.
+        let expected: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:-:1:+\n\
+            ACT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:-:0:+ L:-:2:+\n\
+            ACTTACCCGAGACC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:+\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2_with_options(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+            &BCalm2WriteOptions {
+                representative: RepresentativeChoice::Canonical,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(expected.to_vec()).unwrap(),
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_node_write_forward_representatives() {
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let node_data = PlainBCalm2NodeData {
+            id: 0,
+            sequence_handle: sequence_store.add_from_slice_u8(b"AGT").unwrap(),
+            forwards: true,
+            length: Some(3),
+            total_abundance: None,
+            mean_abundance: None,
+            colors: None,
+            raw_description: None,
+            edges: Vec::new(),
+        };
+
+        // The reverse strand has the lower index.
+        let mut graph = PetBCalm2NodeGraph::default();
+        let reverse_node = graph.add_node(node_data.mirror());
+        let forward_node = graph.add_node(node_data);
+        graph.set_mirror_nodes(reverse_node, forward_node);
+
+        for (representative, expected) in [
+            (RepresentativeChoice::LowerIndex, ">0 LN:i:3\nACT\n"),
+            (RepresentativeChoice::Forward, ">0 LN:i:3\nAGT\n"),
+        ] {
+            let mut output = Vec::new();
+            write_node_centric_bigraph_to_bcalm2_with_options(
+                &graph,
+                &sequence_store,
+                bio::io::fasta::Writer::new(&mut output),
+                &BCalm2WriteOptions {
+                    representative,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            assert_eq!(expected, String::from_utf8(output).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_node_write_warnings() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.25 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        let warnings = write_node_centric_bigraph_to_bcalm2_with_warnings(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+            &BCalm2WriteOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            warnings,
+            vec![WriteWarning::RoundedMeanAbundance {
+                node_id: 1,
+                mean_abundance: 3.25
+            }]
+        );
+    }
+
+    #[test]
+    fn test_node_write_warnings_for_raw_description() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.25 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let mut graph: PetBCalm2NodeGraph<_> =
+            read_bigraph_from_bcalm2_as_node_centric_with_options(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                &BCalm2ReadOptions {
+                    preserve_raw_description: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let node_id = graph.node_indices().next().unwrap();
+        let node_data = graph.node_data_mut(node_id);
+        node_data.mean_abundance = None;
+        node_data
+            .raw_description
+            .as_mut()
+            .unwrap()
+            .push_str(" XY:Z:foo");
+
+        let mut output = Vec::new();
+        let warnings = write_node_centric_bigraph_to_bcalm2_with_warnings(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+            &BCalm2WriteOptions {
+                emit_raw_description: true,
+                reassign_ids: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            warnings,
+            vec![
+                WriteWarning::RegeneratedDescription { node_id: 0 },
+                WriteWarning::DroppedTag {
+                    node_id: 0,
+                    tag: "km:f:3.0".to_string()
+                },
+                WriteWarning::DroppedTag {
+                    node_id: 0,
+                    tag: "XY:Z:foo".to_string()
+                },
+                WriteWarning::RegeneratedDescription { node_id: 1 },
+                WriteWarning::RoundedMeanAbundance {
+                    node_id: 1,
+                    mean_abundance: 3.25
+                },
+                WriteWarning::RegeneratedDescription { node_id: 2 },
+            ]
+        );
+
+        // Without `emit_raw_description`, the dropped tags are still reported, but the regenerated descriptions are expected.
+        let mut output = Vec::new();
+        let warnings = write_node_centric_bigraph_to_bcalm2_with_warnings(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+            &BCalm2WriteOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                WriteWarning::DroppedTag {
+                    node_id: 0,
+                    tag: "km:f:3.0".to_string()
+                },
+                WriteWarning::DroppedTag {
+                    node_id: 0,
+                    tag: "XY:Z:foo".to_string()
+                },
+                WriteWarning::RoundedMeanAbundance {
+                    node_id: 1,
+                    mean_abundance: 3.25
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_node_read_with_comments() {
+        let test_file: &'static [u8] = b"# hand-edited file\n\
+            >0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            ; removed a node here\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGG\n\
+            # comments may also split sequences\n\
+            GTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n\
+            # trailing comment";
+        let expected: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_with_comments(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            &["#", ";"],
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(expected.to_vec()).unwrap(),
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_records() {
+        let expected: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let records: Vec<_> = [
+            (
+                b"AGT".as_slice(),
+                4,
+                3.0,
+                vec![PlainBCalm2Edge {
+                    from_side: true,
+                    to_node: 1,
+                    to_side: false,
+                }],
+            ),
+            (
+                b"GGTCTCGGGTAAGT".as_slice(),
+                2,
+                3.2,
+                vec![
+                    PlainBCalm2Edge {
+                        from_side: true,
+                        to_node: 0,
+                        to_side: false,
+                    },
+                    PlainBCalm2Edge {
+                        from_side: true,
+                        to_node: 2,
+                        to_side: true,
+                    },
+                ],
+            ),
+            (
+                b"ATGATG".as_slice(),
+                15,
+                2.2,
+                vec![PlainBCalm2Edge {
+                    from_side: false,
+                    to_node: 1,
+                    to_side: false,
+                }],
+            ),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(
+            |(id, (sequence, total_abundance, mean_abundance, edges))| PlainBCalm2NodeData {
+                id,
+                sequence_handle: sequence_store.add_from_slice_u8(sequence).unwrap(),
+                forwards: true,
+                length: Some(sequence.len()),
+                total_abundance: Some(total_abundance),
+                mean_abundance: Some(mean_abundance),
+                colors: None,
+                raw_description: None,
+                edges,
+            },
+        )
+        .collect();
+
+        let mut output = Vec::new();
+        write_bcalm2_records(
+            records.clone(),
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(expected.to_vec()).unwrap(),
+            String::from_utf8(output).unwrap()
+        );
+
+        // A reverse record is written reverse-complemented, with its own side of each edge flipped.
+        let mut reverse_record = records[1].clone();
+        reverse_record.forwards = false;
+        let mut output = Vec::new();
+        write_bcalm2_records(
+            [reverse_record],
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">1 LN:i:14 KC:i:2 km:f:3.2 L:-:0:- L:-:2:+\nACTTACCCGAGACC\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_id_mapping() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let expected: &'static [u8] = b">2 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:+ L:+:2:-\n\
+            GGTCTCGGGTAAGT\n\
+            >0 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let mut graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            apply_id_mapping(&mut graph, &HashMap::from([(0, 1)])),
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2DuplicateNodeId { id: 1 }
+            ))
+        ));
+
+        apply_id_mapping(&mut graph, &HashMap::from([(0, 2), (2, 0)])).unwrap();
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(expected.to_vec()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+
+        // The unsorted ids are read back unchanged.
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(output.as_slice()),
+            &mut sequence_store,
+        )
+        .unwrap();
+        assert_eq!(
+            (0..3usize)
+                .map(|node| graph.node_data(node.into()).id)
+                .collect::<Vec<_>>(),
+            vec![2, 1, 0]
+        );
+        let mut round_trip_output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut round_trip_output),
+        )
+        .unwrap();
+        assert_eq!(round_trip_output, expected);
+    }
+
+    #[test]
+    fn test_node_read_write_reordered_tags() {
+        let test_file: &'static [u8] = b">0 L:+:1:- LN:i:3 km:f:3.0 KC:i:4\n\
+            AGT\n\
+            >1 L:+:0:- LN:i:14 L:+:2:+ KC:i:2 km:f:3.2\n\
+            GGTCTCGGGTAAGT\n\
+            >2 KC:i:15 L:-:1:- km:f:2.2 LN:i:6\n\
+            ATGATG\n";
+        let expected: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        let node_data = graph.node_data(1.into());
+        assert_eq!(node_data.length, Some(14));
+        assert_eq!(node_data.total_abundance, Some(2));
+        assert_eq!(node_data.mean_abundance, Some(3.2));
+        assert_eq!(node_data.edges.len(), 2);
+
+        // The values round-trip, but the tags are written in canonical order.
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(expected.to_vec()).unwrap(),
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_index_get_record() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let mut reader = std::io::Cursor::new(test_file);
+
+        let index = BCalm2Index::build(&mut reader).unwrap();
+        assert_eq!(index.offset(0), Some(0));
+        assert_eq!(index.offset(3), None);
+
+        let record = index
+            .get_record(&mut reader, 2, &mut sequence_store)
+            .unwrap();
+        assert_eq!(record.id, 2);
+        assert_eq!(record.length, Some(6));
+        assert_eq!(record.total_abundance, Some(15));
+        assert_eq!(
+            sequence_store.get(&record.sequence_handle).clone_as_vec(),
+            b"ATGATG".to_vec()
+        );
+        assert!(index
+            .get_record(&mut reader, 3, &mut sequence_store)
+            .is_err());
+
+        // If the records do not start at the beginning of the reader, the offsets are still absolute.
+        let mut prefixed_file = b"; bcalm2 output\n".to_vec();
+        let prefix_length = prefixed_file.len() as u64;
+        prefixed_file.extend_from_slice(test_file);
+        let mut reader = std::io::Cursor::new(prefixed_file);
+        reader
+            .seek(std::io::SeekFrom::Start(prefix_length))
+            .unwrap();
+
+        let index = BCalm2Index::build(&mut reader).unwrap();
+        assert_eq!(index.offset(0), Some(prefix_length));
+        let record = index
+            .get_record(&mut reader, 1, &mut sequence_store)
+            .unwrap();
+        assert_eq!(record.id, 1);
+        assert_eq!(
+            sequence_store.get(&record.sequence_handle).clone_as_vec(),
+            b"GGTCTCGGGTAAGT".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_read_empty() {
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_with_options(
+            BufReader::new(b"".as_slice()),
+            &mut sequence_store,
+            &BCalm2ReadOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(graph.node_count(), 0);
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_with_options(
+            BufReader::new(b"".as_slice()),
+            &mut sequence_store,
+            3,
+            &BCalm2ReadOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(graph.edge_count(), 0);
+
+        let options = BCalm2ReadOptions {
+            allow_empty: false,
+            ..Default::default()
+        };
+        let result: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric_with_options(
+                BufReader::new(b"".as_slice()),
+                &mut sequence_store,
+                &options,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2EmptyInput
+            ))
+        ));
+        let result: crate::error::Result<PetBCalm2EdgeGraph<_>> =
+            read_bigraph_from_bcalm2_as_edge_centric_with_options(
+                BufReader::new(b"".as_slice()),
+                &mut sequence_store,
+                3,
+                &options,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2EmptyInput
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_iter_links() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut links: Vec<_> = iter_links(&graph).unwrap().collect();
+        links.sort();
+        assert_eq!(
+            links,
+            vec!["0 L:+:1:-", "1 L:+:0:-", "1 L:+:2:+", "2 L:-:1:-"]
+        );
+    }
+
+    #[test]
+    fn test_node_read_min_length() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let expected: &'static [u8] = b">1 LN:i:14 KC:i:2 km:f:3.2 L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_min_length(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            5,
+        )
+        .unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+
+        // Carriage returns do not count towards the sequence length.
+        let crlf_file = String::from_utf8(test_file.to_vec())
+            .unwrap()
+            .replace('\n', "\r\n");
+        let mut crlf_sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let crlf_graph: PetBCalm2NodeGraph<_> =
+            read_bigraph_from_bcalm2_as_node_centric_min_length(
+                BufReader::new(crlf_file.as_bytes()),
+                &mut crlf_sequence_store,
+                7,
+            )
+            .unwrap();
+        assert_eq!(crlf_graph.node_count(), 2);
+        assert_eq!(crlf_graph.edge_count(), 0);
+
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(expected.to_vec()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+
+        // The output starts at id 1, but can still be read back.
+        let mut reread_sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let reread: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(output.as_slice()),
+            &mut reread_sequence_store,
+        )
+        .unwrap();
+        assert_eq!(reread.node_count(), 4);
+        assert_eq!(reread.edge_count(), 2);
+        assert_eq!(reread.node_data(0.into()).id, 1);
+        assert_eq!(reread.node_data(1.into()).id, 2);
+    }
+
+    #[test]
+    fn test_read_with_capacity() {
+        let record_count = 1000;
+        let mut test_file = String::new();
+        for id in 0..record_count {
+            test_file.push_str(&format!(">{id} LN:i:4"));
+            if id > 0 {
+                test_file.push_str(&format!(" L:-:{}:-", id - 1));
+            }
+            if id < record_count - 1 {
+                test_file.push_str(&format!(" L:+:{}:+", id + 1));
+            }
+            test_file.push_str("\nAAAC\n");
+        }
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        // The capacity is deliberately inexact.
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_with_capacity(
+            BufReader::new(test_file.as_bytes()),
+            &mut sequence_store,
+            record_count / 2,
+        )
+        .unwrap();
+        assert_eq!(graph.node_count(), 2 * record_count);
+        assert_eq!(graph.edge_count(), 2 * (record_count - 1));
+
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_with_capacity(
+            BufReader::new(test_file.as_bytes()),
+            &mut sequence_store,
+            3,
+            record_count / 2,
+        )
+        .unwrap();
+        assert_eq!(graph.edge_count(), 2 * record_count);
+        assert_eq!(graph.node_count(), 2 * (record_count + 1));
+    }
+
+    #[test]
+    fn test_node_read_descriptions() {
+        let test_file: &'static [u8] = b">0\n\
+            AGT\n\
+            >1  \t \n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6\tKC:i:15 \tkm:f:2.2\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 0);
+
+        for node_id in 0..2 {
+            let node_data = graph.node_data(node_id.into());
+            assert_eq!(node_data.length, None);
+            assert_eq!(node_data.total_abundance, None);
+            assert_eq!(node_data.mean_abundance, None);
+        }
+        let node_data = graph.node_data(2.into());
+        assert_eq!(node_data.length, Some(6));
+        assert_eq!(node_data.total_abundance, Some(15));
+        assert_eq!(node_data.mean_abundance, Some(2.2));
+    }
+
+    #[test]
+    fn test_node_read_stray_tokens() {
+        for token in ["a", "ab", "LN:ié", "aé:+:1:+"] {
+            let test_file = format!(">0 LN:i:3 {token}\nAGT\n");
+            let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+            let result: crate::error::Result<PetBCalm2NodeGraph<_>> =
+                read_bigraph_from_bcalm2_as_node_centric(
+                    BufReader::new(test_file.as_bytes()),
+                    &mut sequence_store,
+                );
+            assert!(
+                matches!(
+                    result,
+                    Err(crate::error::Error::BCalm2IoError(
+                        BCalm2IoError::BCalm2UnknownParameterError { .. }
+                    ))
+                ),
+                "{token}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_node_read_short_tokens() {
+        for token in ["L", "LN", "L:", "KC:", "LN:i", "km:f", "L:++", "LN:i:"] {
+            let test_file = format!(">0 LN:i:3 {token}\nAGT\n");
+            let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+            let result: crate::error::Result<PetBCalm2NodeGraph<_>> =
+                read_bigraph_from_bcalm2_as_node_centric(
+                    BufReader::new(test_file.as_bytes()),
+                    &mut sequence_store,
+                );
+            assert!(
+                matches!(
+                    result,
+                    Err(crate::error::Error::BCalm2IoError(
+                        BCalm2IoError::BCalm2MalformedParameterError { .. }
+                    ))
+                ),
+                "{token}"
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let node_data = PlainBCalm2NodeData {
+            id: 3,
+            sequence_handle: 7usize,
+            forwards: false,
+            length: Some(14),
+            total_abundance: Some(2),
+            mean_abundance: Some(3.2),
+            colors: None,
+            raw_description: None,
+            edges: vec![
+                PlainBCalm2Edge {
+                    from_side: true,
+                    to_node: 0,
+                    to_side: false,
+                },
+                PlainBCalm2Edge {
+                    from_side: false,
+                    to_node: 2,
+                    to_side: true,
+                },
+            ],
+        };
+
+        let json = serde_json::to_string(&node_data).unwrap();
+        let parsed: PlainBCalm2NodeData<usize> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.id, node_data.id);
+        assert_eq!(parsed.sequence_handle, node_data.sequence_handle);
+        assert_eq!(parsed.forwards, node_data.forwards);
+        assert_eq!(parsed.length, node_data.length);
+        assert_eq!(parsed.total_abundance, node_data.total_abundance);
+        assert_eq!(parsed.mean_abundance, node_data.mean_abundance);
+        assert_eq!(parsed.edges, node_data.edges);
+    }
+
+    #[test]
+    fn test_compact_edge() {
+        for from_side in [false, true] {
+            for to_side in [false, true] {
+                for to_node in [
+                    0,
+                    1,
+                    12345,
+                    usize::try_from(CompactBCalm2Edge::MAX_NODE_ID).unwrap_or(usize::MAX),
+                ] {
+                    let edge = PlainBCalm2Edge {
+                        from_side,
+                        to_node,
+                        to_side,
+                    };
+                    let compact = CompactBCalm2Edge::try_from(edge.clone()).unwrap();
+                    assert_eq!(compact.from_side(), from_side);
+                    assert_eq!(compact.to_node(), to_node);
+                    assert_eq!(compact.to_side(), to_side);
+                    assert_eq!(PlainBCalm2Edge::from(compact), edge);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(target_pointer_width = "64")]
+    fn test_compact_edge_id_too_large() {
+        CompactBCalm2Edge::new(true, CompactBCalm2Edge::MAX_NODE_ID as usize + 1, true);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_compact_edge_try_from_id_too_large() {
+        let edge = PlainBCalm2Edge {
+            from_side: true,
+            to_node: CompactBCalm2Edge::MAX_NODE_ID as usize + 1,
+            to_side: false,
+        };
+        assert!(matches!(
+            CompactBCalm2Edge::try_from(edge),
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2CompactEdgeNodeIdTooLarge { id }
+            )) if id == CompactBCalm2Edge::MAX_NODE_ID as usize + 1
+        ));
+    }
+
+    #[test]
+    fn test_node_read_subgraph() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let allowed_ids: HashSet<_> = [2].into_iter().collect();
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_subgraph(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            &allowed_ids,
+            false,
+        )
+        .unwrap();
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.node_data(0.into()).id, 2);
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_subgraph(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            &allowed_ids,
+            true,
+        )
+        .unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+        let mut ids: Vec<_> = graph
+            .node_indices()
+            .map(|node_id| graph.node_data(node_id).id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids, vec![1, 2]);
+        assert!(graph.contains_edge_between(0.into(), 1.into()));
+
+        // The written subgraph does not start at id 0, but can still be read back.
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        let mut reread_sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let reread: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(output.as_slice()),
+            &mut reread_sequence_store,
+        )
+        .unwrap();
+        assert_eq!(reread.node_count(), 4);
+        assert_eq!(reread.edge_count(), 2);
+        assert_eq!(reread.node_data(0.into()).id, 1);
+        assert_eq!(reread.node_data(1.into()).id, 2);
+    }
+
+    #[test]
+    fn test_read_write_bit_vector_sequence_store() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = BitVectorSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(output, test_file);
+
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
+        let mut sequence_store = BitVectorSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
+        assert_eq!(output, test_file);
+    }
+
+    #[test]
+    fn test_node_write_derive_mean_abundance() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:14 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        let options = BCalm2WriteOptions {
+            kmer_size: Some(3),
+            derive_mean_abundance: true,
+            ..Default::default()
+        };
+        let warnings = write_node_centric_bigraph_to_bcalm2_with_warnings(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+            &options,
+        )
+        .unwrap();
+
+        // Node 0 has 3 - 3 + 1 = 1 k-mer, and node 1 has 14 - 3 + 1 = 12 k-mers.
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">0 LN:i:3 KC:i:4 km:f:4.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:0.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:14 km:f:2.2 L:-:1:-\n\
+            ATGATG\n"
+        );
+        assert_eq!(
+            warnings,
+            vec![WriteWarning::RoundedMeanAbundance {
+                node_id: 1,
+                mean_abundance: 2.0 / 12.0
+            }]
+        );
+
+        let options = BCalm2WriteOptions {
+            derive_mean_abundance: true,
+            ..Default::default()
+        };
+        let result = write_node_centric_bigraph_to_bcalm2_with_options(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(Vec::new()),
+            &options,
+        );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2MissingParameterError { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_node_write_component() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:3 L:-:0:-\n\
+            ACG\n\
+            >2 LN:i:3 L:+:3:+\n\
+            GGT\n\
+            >3 LN:i:3 L:-:2:-\n\
+            GTT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_node_centric_component_to_bcalm2(
+            &graph,
+            &sequence_store,
+            &[2.into(), 3.into()],
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output.clone()).unwrap(),
+            ">0 LN:i:3 L:+:1:+\nGGT\n>1 LN:i:3 L:-:0:-\nGTT\n"
+        );
+
+        let component: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(output.as_slice()),
+            &mut sequence_store,
+        )
+        .unwrap();
+        assert_eq!(component.node_count(), 4);
+        assert_eq!(component.edge_count(), 2);
+    }
+
+    #[test]
+    fn test_read_abundance_consistency() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
+        let options = BCalm2ReadOptions {
+            validate_abundance_consistency: true,
+            ..Default::default()
+        };
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric(
+                BufReader::new(test_file),
+                &mut sequence_store,
+            );
+        assert!(graph.is_ok());
+
+        let result: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric_with_options(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                &options,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2AbundanceInconsistency { id: 1 }
+            ))
+        ));
+        let result: crate::error::Result<PetBCalm2EdgeGraph<_>> =
+            read_bigraph_from_bcalm2_as_edge_centric_with_options(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                3,
+                &options,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2AbundanceInconsistency { id: 1 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_verify_bcalm2_fasta_matches_gfa() {
+        let fasta: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
+        let gfa: &'static [u8] = b"H\tVN:Z:1.0\tKL:Z:3\n\
+            S\t1\tAGT\n\
+            S\t2\tGTTTACCCGAGATT\n\
+            S\t3\tACGAGG\n\
+            L\t1\t+\t2\t+\t2M\n\
+            L\t2\t-\t3\t+\t2M\n";
+        verify_bcalm2_fasta_matches_gfa::<_, _, DnaAlphabet>(
+            BufReader::new(fasta),
+            BufReader::new(gfa),
+            3,
+        )
+        .unwrap();
+
+        let mismatched_gfa: &'static [u8] = b"H\tVN:Z:1.0\tKL:Z:3\n\
+            S\t1\tAGT\n\
+            S\t2\tGTTTACCCGTGATT\n\
+            S\t3\tACGAGG\n\
+            L\t1\t+\t2\t+\t2M\n\
+            L\t2\t-\t3\t+\t2M\n";
+        assert!(matches!(
+            verify_bcalm2_fasta_matches_gfa::<_, _, DnaAlphabet>(
+                BufReader::new(fasta),
+                BufReader::new(mismatched_gfa),
+                3,
+            ),
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2GfaMismatch { .. }
+            ))
+        ));
+
+        // Same segments, but the link between the second and third segment is missing.
+        let missing_link_gfa: &'static [u8] = b"H\tVN:Z:1.0\tKL:Z:3\n\
+            S\t1\tAGT\n\
+            S\t2\tGTTTACCCGAGATT\n\
+            S\t3\tACGAGG\n\
+            L\t1\t+\t2\t+\t2M\n";
+        assert!(matches!(
+            verify_bcalm2_fasta_matches_gfa::<_, _, DnaAlphabet>(
+                BufReader::new(fasta),
+                BufReader::new(missing_link_gfa),
+                3,
+            ),
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2GfaMismatch { description }
+            )) if description.contains("link")
+        ));
+
+        assert!(matches!(
+            verify_bcalm2_fasta_matches_gfa::<_, _, DnaAlphabet>(
+                BufReader::new(fasta),
+                BufReader::new(gfa),
+                0,
+            ),
+            Err(crate::error::Error::ZeroKmerSize)
+        ));
+    }
+
+    #[test]
+    fn test_transform_streaming() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut output = Vec::new();
+        transform_bcalm2_streaming(
+            BufReader::new(test_file),
+            DefaultSequenceStore::<DnaAlphabet>::default(),
+            bio::io::fasta::Writer::new(&mut output),
+            |mut record| {
+                if record.id % 2 == 1 {
+                    None
+                } else {
+                    record.edges.retain(|edge| edge.to_node % 2 == 0);
+                    Some(record)
+                }
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">0 LN:i:3 KC:i:4 km:f:3.0\nAGT\n>2 LN:i:6 KC:i:15 km:f:2.2\nATGATG\n"
+        );
+    }
+
+    #[test]
+    fn test_write_records_large_ids() {
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let sequence_handle = sequence_store.add_from_slice_u8(b"AGT").unwrap();
+        let record = |id, to_node| PlainBCalm2NodeData {
+            id,
+            sequence_handle: sequence_handle.clone(),
+            forwards: true,
+            length: None,
+            total_abundance: None,
+            mean_abundance: None,
+            colors: None,
+            raw_description: None,
+            edges: vec![PlainBCalm2Edge {
+                from_side: true,
+                to_node,
+                to_side: true,
+            }],
+        };
+
+        let mut output = Vec::new();
+        write_bcalm2_records(
+            [record(usize::MAX - 1, usize::MAX - 1)],
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(">{0} L:+:{0}:+\nAGT\n", usize::MAX - 1)
+        );
+
+        for (id, to_node) in [(usize::MAX, 0), (0, usize::MAX)] {
+            assert!(matches!(
+                write_bcalm2_records(
+                    [record(id, to_node)],
+                    &sequence_store,
+                    bio::io::fasta::Writer::new(Vec::new()),
+                ),
+                Err(crate::error::Error::BCalm2IoError(
+                    BCalm2IoError::BCalm2NodeIdOutOfPrintingRange
+                ))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_node_read_write_scientific_notation_mean_abundance() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0e0\n\
+            AGT\n";
+        let expected: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0\n\
+            AGT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        assert_eq!(graph.node_data(0.into()).mean_abundance, Some(3.0));
+
+        // Writing normalizes the scientific notation to fixed-point notation.
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(expected.to_vec()).unwrap(),
+            String::from_utf8(output.clone()).unwrap()
+        );
+
+        // Reading the normalized form gives back the same value.
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(output.as_slice()),
+            &mut sequence_store,
+        )
+        .unwrap();
+        assert_eq!(graph.node_data(0.into()).mean_abundance, Some(3.0));
+    }
 
-    for edge_id in graph.edge_indices() {
-        if !output_edges[graph
-            .mirror_edge_edge_centric(edge_id)
-            .ok_or_else(|| BCalm2IoError::BCalm2EdgeWithoutMirror)?
-            .as_usize()]
-        {
-            output_edges[edge_id.as_usize()] = true;
-        }
+    #[test]
+    fn test_gc_content() {
+        let test_file: &'static [u8] = b">0 LN:i:4 L:+:1:-\n\
+            AGTC\n\
+            >1 LN:i:5 L:+:0:-\n\
+            GGCCA\n\
+            >2 LN:i:3\n\
+            ATA\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        let gc_content = |node: usize| {
+            PlainBCalm2NodeData::from(graph.node_data(node.into())).gc_content(&sequence_store)
+        };
+        // Record i is node i, and its mirror is node 3 + i.
+        assert_eq!(gc_content(0), Some(0.5));
+        assert_eq!(gc_content(1), Some(0.8));
+        assert_eq!(gc_content(2), Some(0.0));
+        assert_eq!(gc_content(3), Some(0.5));
+        assert_eq!(gc_content(4), Some(0.8));
+        assert_eq!(gc_content(5), Some(0.0));
     }
 
-    for edge_id in graph.edge_indices() {
-        if output_edges[edge_id.as_usize()] {
-            let node_data = PlainBCalm2NodeData::from(graph.edge_data(edge_id));
-            let mirror_edge_id = graph
-                .mirror_edge_edge_centric(edge_id)
-                .ok_or_else(|| BCalm2IoError::BCalm2EdgeWithoutMirror)?;
-            let to_node_plus = graph.edge_endpoints(edge_id).to_node;
-            let to_node_minus = graph.edge_endpoints(mirror_edge_id).to_node;
-
-            let mut out_neighbors_plus = Vec::new();
-            let mut out_neighbors_minus = Vec::new();
-
-            for neighbor in graph.out_neighbors(to_node_plus) {
-                let neighbor_edge_id = neighbor.edge_id.as_usize();
-
-                out_neighbors_plus.push((
-                    true,
-                    if output_edges[neighbor_edge_id] {
-                        PlainBCalm2NodeData::from(graph.edge_data(neighbor.edge_id)).id
-                    } else {
-                        PlainBCalm2NodeData::from(
-                            graph.edge_data(
-                                graph
-                                    .mirror_edge_edge_centric(neighbor.edge_id)
-                                    .ok_or_else(|| BCalm2IoError::BCalm2EdgeWithoutMirror)?,
-                            ),
-                        )
-                        .id
-                    },
-                    output_edges[neighbor_edge_id],
-                ));
+    #[test]
+    fn test_edge_read_with_edge_factory() {
+        /// Edge data with a stamp that is ignored when comparing edges.
+        #[derive(Debug, Clone)]
+        struct StampedEdgeData {
+            id: usize,
+            forwards: bool,
+            stamp: usize,
+        }
+
+        impl PartialEq for StampedEdgeData {
+            fn eq(&self, other: &Self) -> bool {
+                self.id == other.id && self.forwards == other.forwards
             }
-            for neighbor in graph.out_neighbors(to_node_minus) {
-                let neighbor_edge_id = neighbor.edge_id.as_usize();
+        }
 
-                out_neighbors_minus.push((
-                    false,
-                    if output_edges[neighbor_edge_id] {
-                        PlainBCalm2NodeData::from(graph.edge_data(neighbor.edge_id)).id
-                    } else {
-                        PlainBCalm2NodeData::from(
-                            graph.edge_data(
-                                graph
-                                    .mirror_edge_edge_centric(neighbor.edge_id)
-                                    .ok_or_else(|| BCalm2IoError::BCalm2EdgeWithoutMirror)?,
-                            ),
-                        )
-                        .id
-                    },
-                    output_edges[neighbor_edge_id],
-                ));
+        impl Eq for StampedEdgeData {}
+
+        impl BidirectedData for StampedEdgeData {
+            fn mirror(&self) -> Self {
+                Self {
+                    id: self.id,
+                    forwards: !self.forwards,
+                    stamp: self.stamp,
+                }
             }
+        }
 
-            out_neighbors_plus.sort_unstable();
-            out_neighbors_minus.sort_unstable();
-            out_neighbors_plus.append(&mut out_neighbors_minus);
-            let out_neighbors = out_neighbors_plus;
-
-            let mut printed_node_id = String::new();
-            write!(printed_node_id, "{}", node_data.id).map_err(BCalm2IoError::from)?;
-            let node_description =
-                write_plain_bcalm2_node_data_to_bcalm2(&node_data, out_neighbors)?;
-            let node_sequence = source_sequence_store.get(&node_data.sequence_handle);
-            let node_sequence = if node_data.forwards {
-                node_sequence.clone_as_vec()
-            } else {
-                node_sequence
-                    .reverse_complement_iter()
-                    .map(|c| c.into())
-                    .collect()
-            };
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let mut counter = 0;
+        let graph: NodeBigraphWrapper<PetGraph<(), StampedEdgeData>> =
+            read_bigraph_from_bcalm2_as_edge_centric_with_edge_factory(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                3,
+                &BCalm2ReadOptions::default(),
+                |record, is_mirror| {
+                    let stamp = counter;
+                    counter += 1;
+                    StampedEdgeData {
+                        id: record.id,
+                        forwards: !is_mirror,
+                        stamp,
+                    }
+                },
+            )
+            .unwrap();
 
-            writer
-                .write(&printed_node_id, Some(&node_description), &node_sequence)
-                .map_err(BCalm2IoError::from)?;
+        assert_eq!(counter, 6);
+        assert_eq!(graph.edge_count(), 6);
+        assert!(graph.verify_edge_mirror_property());
+        for edge in graph.edge_indices() {
+            let edge_data = graph.edge_data(edge);
+            let expected_stamp = edge_data.id * 2 + if edge_data.forwards { 0 } else { 1 };
+            assert_eq!(edge_data.stamp, expected_stamp);
         }
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_edge_read_header_kmer_size() {
+        let records: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
+        let mut test_file = b"; k=3\n".to_vec();
+        test_file.extend_from_slice(records);
 
-#[cfg(test)]
-mod tests {
-    use crate::io::bcalm2::{
-        read_bigraph_from_bcalm2_as_edge_centric, read_bigraph_from_bcalm2_as_edge_centric_old,
-        read_bigraph_from_bcalm2_as_node_centric, write_edge_centric_bigraph_to_bcalm2,
-        write_node_centric_bigraph_to_bcalm2,
-    };
-    use crate::types::{PetBCalm2EdgeGraph, PetBCalm2NodeGraph};
-    use bigraph::interface::static_bigraph::StaticBigraph;
-    use bigraph::traitgraph::interface::{Edge, ImmutableGraphContainer};
-    use compact_genome::implementation::{
-        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
-    };
-    use std::io::BufReader;
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let expected: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(records),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> =
+            read_bigraph_from_bcalm2_as_edge_centric_with_optional_kmer_size(
+                BufReader::new(test_file.as_slice()),
+                &mut sequence_store,
+                None,
+            )
+            .unwrap();
+        assert_eq!(graph.node_count(), expected.node_count());
+        assert_eq!(graph.edge_count(), expected.edge_count());
+
+        // Without the comment, the k-mer size must be given explicitly.
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> =
+            read_bigraph_from_bcalm2_as_edge_centric_with_optional_kmer_size(
+                BufReader::new(records),
+                &mut sequence_store,
+                Some(3),
+            )
+            .unwrap();
+        assert_eq!(graph.node_count(), expected.node_count());
+        assert_eq!(graph.edge_count(), expected.edge_count());
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<PetBCalm2EdgeGraph<_>> =
+            read_bigraph_from_bcalm2_as_edge_centric_with_optional_kmer_size(
+                BufReader::new(records),
+                &mut sequence_store,
+                None,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2MissingParameterError { parameter }
+            )) if parameter == "k"
+        ));
+    }
 
     #[test]
-    fn test_node_read_write() {
+    fn test_node_read_min_length_report() {
         let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
             AGT\n\
             >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
             GGTCTCGGGTAAGT\n\
             >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
             ATGATG\n";
-        let input = Vec::from(test_file);
         let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
 
-        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+        let (graph, report): (PetBCalm2NodeGraph<_>, _) =
+            read_bigraph_from_bcalm2_as_node_centric_min_length_with_report(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                5,
+            )
+            .unwrap();
+        assert_eq!(graph.node_count(), 4);
+        // Record 0 and its two links are filtered out.
+        assert_eq!(
+            report,
+            ReadReport {
+                nodes_read: 3,
+                nodes_kept: 2,
+                edges_read: 4,
+                edges_kept: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_trim_terminal_ns() {
+        let test_file: &'static [u8] = b">0 LN:i:7 KC:i:4 km:f:3.0\n\
+            NNAGTNN\n\
+            >1 LN:i:4\n\
+            ACGT\n";
+        let options = BCalm2ReadOptions {
+            trim_terminal_ns: true,
+            ..Default::default()
+        };
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_with_options(
             BufReader::new(test_file),
             &mut sequence_store,
+            &options,
         )
         .unwrap();
+        let node_data = PlainBCalm2NodeData::from(graph.node_data(0.into()));
+        assert_eq!(
+            sequence_store
+                .get(&node_data.sequence_handle)
+                .clone_as_vec(),
+            b"AGT".to_vec()
+        );
+        assert_eq!(
+            sequence_store
+                .get(&graph.node_data(1.into()).sequence_handle)
+                .clone_as_vec(),
+            b"ACGT".to_vec()
+        );
+
         let mut output = Vec::new();
         write_node_centric_bigraph_to_bcalm2(
             &graph,
@@ -1007,427 +6371,1221 @@ mod tests {
             bio::io::fasta::Writer::new(&mut output),
         )
         .unwrap();
-
-        debug_assert_eq!(
-            input,
-            output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(input.clone()).unwrap(),
-            String::from_utf8(output.clone()).unwrap()
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">0 LN:i:3 KC:i:4 km:f:3.0\nAGT\n>1 LN:i:4\nACGT\n"
         );
+
+        let test_file: &'static [u8] = b">0 LN:i:3\nNNN\n";
+        let result: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric_with_options(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                &options,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2EmptySequenceAfterTrimming { .. }
+            ))
+        ));
     }
 
     #[test]
-    fn test_edge_read_write() {
+    fn test_read_verify_link_symmetry() {
+        let options = BCalm2ReadOptions {
+            verify_link_symmetry: true,
+            ..Default::default()
+        };
         let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
             AGT\n\
             >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
             AATCTCGGGTAAAC\n\
             >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
             ACGAGG\n";
-        let input = Vec::from(test_file);
         let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric_with_options(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                &options,
+            );
+        assert!(graph.is_ok());
+        let graph: crate::error::Result<PetBCalm2EdgeGraph<_>> =
+            read_bigraph_from_bcalm2_as_edge_centric_with_options(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                3,
+                &options,
+            );
+        assert!(graph.is_ok());
 
-        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+        // Record 1 does not link back to record 0.
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:3\n\
+            ACG\n";
+        let graph: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric_with_options(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                &options,
+            );
+        assert!(matches!(
+            graph,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2AsymmetricLink { from: 0, to: 1 }
+            ))
+        ));
+        let graph: crate::error::Result<PetBCalm2EdgeGraph<_>> =
+            read_bigraph_from_bcalm2_as_edge_centric_with_options(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                3,
+                &options,
+            );
+        assert!(matches!(
+            graph,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2AsymmetricLink { from: 0, to: 1 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_read_deduplicate_sequences() {
+        let test_file: &'static [u8] = b">0 LN:i:3\n\
+            AGT\n\
+            >1 LN:i:3\n\
+            ACT\n\
+            >2 LN:i:3\n\
+            AGT\n\
+            >3 LN:i:3\n\
+            GGG\n";
+        let options = BCalm2ReadOptions {
+            deduplicate_sequences: true,
+            ..Default::default()
+        };
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_with_options(
             BufReader::new(test_file),
             &mut sequence_store,
-            3,
+            &options,
         )
         .unwrap();
-        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+
+        // Record i is node i, and the mirror nodes follow after all records.
+        let node_data: Vec<_> = (0..4usize)
+            .map(|id| graph.node_data(id.into()).clone())
+            .collect();
+        assert_eq!(node_data[0].sequence_handle, node_data[1].sequence_handle);
+        assert_eq!(node_data[0].sequence_handle, node_data[2].sequence_handle);
+        assert_ne!(node_data[0].sequence_handle, node_data[3].sequence_handle);
+        assert_eq!(
+            node_data
+                .iter()
+                .map(|data| data.forwards)
+                .collect::<Vec<_>>(),
+            vec![true, false, true, true]
+        );
+
+        // Only AGT and GGG are stored, i.e. six characters in two bytes instead of twelve characters in three bytes.
+        assert_eq!(sequence_store.size_in_memory(), 2);
+        let mut duplicate_sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let _: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
             BufReader::new(test_file),
-            &mut sequence_store,
-            3,
+            &mut duplicate_sequence_store,
         )
         .unwrap();
+        assert_eq!(duplicate_sequence_store.size_in_memory(), 3);
 
+        // The records are written unchanged.
         let mut output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
-        let mut old_output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(output, test_file);
+    }
 
-        debug_assert_eq!(
-            input,
-            output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(input.clone()).unwrap(),
-            String::from_utf8(output.clone()).unwrap()
-        );
-        debug_assert_eq!(
-            output,
-            old_output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(output.clone()).unwrap(),
-            String::from_utf8(old_output.clone()).unwrap()
+    #[test]
+    fn test_read_lenient_inline_sequences() {
+        let well_formed: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let malformed: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:- AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:- ATGATG\n";
+        let options = BCalm2ReadOptions {
+            lenient: true,
+            ..Default::default()
+        };
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let expected: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(well_formed),
+            &mut sequence_store,
+        )
+        .unwrap();
+        let actual: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_with_options(
+            BufReader::new(malformed),
+            &mut sequence_store,
+            &options,
+        )
+        .unwrap();
+
+        let mut expected_output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &expected,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut expected_output),
+        )
+        .unwrap();
+        let mut actual_output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &actual,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut actual_output),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(actual_output).unwrap(),
+            String::from_utf8(expected_output).unwrap()
         );
+        assert_eq!(actual.edge_count(), expected.edge_count());
     }
 
     #[test]
-    fn test_edge_read_write_self_loops() {
-        let test_file: &'static [u8] =
-            b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:0:+ L:+:1:- L:-:0:- L:-:2:+\n\
-            AAA\n\
+    fn test_edge_centric_to_records() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
             >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
-            GGTCTCGGGTAATT\n\
-            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:0:+ L:-:1:-\n\
-            TTGATG\n";
-        let input = Vec::from(test_file);
-        println!("{}", String::from_utf8(input.clone()).unwrap());
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
         let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
-
         let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
             BufReader::new(test_file),
             &mut sequence_store,
             3,
         )
         .unwrap();
-        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+
+        let mut original_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let original_records: Vec<PlainBCalm2NodeData<_>> =
+            bio::io::fasta::Reader::new(BufReader::new(test_file))
+                .records()
+                .map(|record| parse_bcalm2_fasta_record(record.unwrap(), &mut original_store))
+                .collect::<crate::error::Result<_>>()
+                .unwrap();
+
+        let mut records = edge_centric_to_records(&graph, &sequence_store).unwrap();
+        records.sort_by_key(|record| record.id);
+        assert_eq!(records.len(), original_records.len());
+        for (record, original_record) in records.iter().zip(&original_records) {
+            assert_eq!(record.id, original_record.id);
+            assert_eq!(record.length, original_record.length);
+            assert_eq!(
+                oriented_sequence_vec(record, &sequence_store),
+                oriented_sequence_vec(original_record, &original_store)
+            );
+
+            let mut edges = record.edges.clone();
+            let mut original_edges = original_record.edges.clone();
+            edges.sort_by_key(|edge| (edge.from_side, edge.to_node, edge.to_side));
+            original_edges.sort_by_key(|edge| (edge.from_side, edge.to_node, edge.to_side));
+            assert_eq!(edges, original_edges);
+        }
+    }
+
+    #[test]
+    fn test_node_read_non_contiguous_ids() {
+        let test_file: &'static [u8] = b">5 LN:i:3 L:+:2:-\n\
+            AGT\n\
+            >2 LN:i:3 L:+:5:-\n\
+            ACT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
             BufReader::new(test_file),
             &mut sequence_store,
-            3,
         )
         .unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.node_data(0.into()).id, 5);
+        assert_eq!(graph.node_data(1.into()).id, 2);
+
+        let topology: PetBCalm2NodeGraph<()> =
+            read_bcalm2_topology_only(BufReader::new(test_file)).unwrap();
+        assert_eq!(topology.node_count(), 4);
+        assert_eq!(topology.edge_count(), 2);
 
         let mut output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
-        let mut old_output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(output, test_file);
 
-        // expect self-loops
+        let missing_record: &'static [u8] = b">5 LN:i:3 L:+:4:-\nAGT\n";
+        let duplicate_id: &'static [u8] = b">5 LN:i:3\nAGT\n>5 LN:i:3\nACT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric(
+                BufReader::new(missing_record),
+                &mut sequence_store,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2MissingRecord { id: 4 }
+            ))
+        ));
+        let result: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric(
+                BufReader::new(duplicate_id),
+                &mut sequence_store,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2DuplicateNodeId { id: 5 }
+            ))
+        ));
+        let result: crate::error::Result<PetBCalm2NodeGraph<()>> =
+            read_bcalm2_topology_only(BufReader::new(missing_record));
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2MissingRecord { id: 4 }
+            ))
+        ));
+        let result: crate::error::Result<PetBCalm2NodeGraph<()>> =
+            read_bcalm2_topology_only(BufReader::new(duplicate_id));
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2DuplicateNodeId { id: 5 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_try_parse_bcalm2_does_not_panic() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let (graph, _) = try_parse_bcalm2(test_file).unwrap();
         assert_eq!(graph.node_count(), 6);
-        assert!(graph
-            .node_indices()
-            .all(|node| !graph.is_self_mirror_node(node)));
-        assert_eq!(old_graph.node_count(), 6);
-        assert!(old_graph
-            .node_indices()
-            .all(|node| !old_graph.is_self_mirror_node(node)));
-        debug_assert_eq!(
-            input,
-            output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(input.clone()).unwrap(),
-            String::from_utf8(output.clone()).unwrap()
+
+        // A simple xorshift generator keeps the test deterministic without extra dependencies.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let alphabet = b">0123456789 \n\r:+-.LNKCkmfiACGTN\xff";
+
+        for _ in 0..2000 {
+            // Random bytes over an alphabet that frequently produces almost valid records.
+            let length = (next() % 64) as usize;
+            let bytes: Vec<_> = (0..length)
+                .map(|_| alphabet[(next() % alphabet.len() as u64) as usize])
+                .collect();
+            let _ = try_parse_bcalm2(&bytes);
+
+            // Random mutations of a valid file.
+            let mut bytes = test_file.to_vec();
+            for _ in 0..(next() % 4 + 1) {
+                let position = (next() % bytes.len() as u64) as usize;
+                bytes[position] = (next() % 256) as u8;
+            }
+            let _ = try_parse_bcalm2(&bytes);
+        }
+    }
+
+    #[test]
+    fn test_read_invalid_char_policy() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AGT\n\
+            >1 LN:i:4 L:-:0:-\n\
+            GXTA\n\
+            >2 LN:i:3\n\
+            CCA\n";
+        let read = |invalid_char_policy| {
+            let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+            let result: crate::error::Result<PetBCalm2NodeGraph<_>> =
+                read_bigraph_from_bcalm2_as_node_centric_with_options(
+                    BufReader::new(test_file),
+                    &mut sequence_store,
+                    &BCalm2ReadOptions {
+                        invalid_char_policy,
+                        ..Default::default()
+                    },
+                );
+            result.map(|graph| (graph, sequence_store))
+        };
+
+        assert!(matches!(
+            read(InvalidCharPolicy::Error),
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2InvalidSequence { id: 1, .. }
+            ))
+        ));
+
+        let (graph, _) = read(InvalidCharPolicy::Skip).unwrap();
+        let nodes: Vec<_> = graph.node_indices().collect();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.node_data(nodes[0]).id, 0);
+        assert_eq!(graph.node_data(nodes[1]).id, 2);
+
+        let (graph, sequence_store) = read(InvalidCharPolicy::Replace(b'A')).unwrap();
+        let nodes: Vec<_> = graph.node_indices().collect();
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(
+            sequence_store
+                .get(&graph.node_data(nodes[1]).sequence_handle)
+                .clone_as_vec(),
+            b"GATA".to_vec()
         );
-        debug_assert_eq!(
-            output,
-            old_output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(output.clone()).unwrap(),
-            String::from_utf8(old_output.clone()).unwrap()
+    }
+
+    #[test]
+    fn test_find_self_complemental_unitigs() {
+        let test_file: &'static [u8] = b">0 LN:i:4\n\
+            AAGT\n\
+            >1 LN:i:4\n\
+            ACGT\n\
+            >2 LN:i:5\n\
+            ACCGT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+
+        assert_eq!(
+            find_self_complemental_unitigs(BufReader::new(test_file), &mut sequence_store, 3)
+                .unwrap(),
+            vec![1, 2]
         );
+        assert_eq!(
+            find_self_complemental_unitigs(BufReader::new(test_file), &mut sequence_store, 4)
+                .unwrap(),
+            vec![1]
+        );
+        assert!(matches!(
+            find_self_complemental_unitigs(BufReader::new(test_file), &mut sequence_store, 0),
+            Err(crate::error::Error::ZeroKmerSize)
+        ));
     }
 
     #[test]
-    fn test_edge_read_write_plus_minus_loop() {
-        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:0:- L:+:1:- L:+:2:+\n\
-            CAT\n\
-            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:1:- L:+:2:+\n\
-            GGTCTCGGGTAAAT\n\
-            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:0:- L:-:1:- L:-:2:+\n\
-            ATGATT\n";
-        let input = Vec::from(test_file);
-        println!("{}", String::from_utf8(input.clone()).unwrap());
+    fn test_write_emit_both_strands() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
         let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
-
-        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
             BufReader::new(test_file),
             &mut sequence_store,
-            3,
         )
         .unwrap();
-        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
-            BufReader::new(test_file),
-            &mut sequence_store,
-            3,
+
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2_with_options(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+            &BCalm2WriteOptions {
+                emit_both_strands: true,
+                ..Default::default()
+            },
         )
         .unwrap();
 
-        let mut output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
-        let mut old_output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+        let mut output_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let records: Vec<PlainBCalm2NodeData<_>> =
+            bio::io::fasta::Reader::new(BufReader::new(output.as_slice()))
+                .records()
+                .map(|record| parse_bcalm2_fasta_record(record.unwrap(), &mut output_store))
+                .collect::<crate::error::Result<_>>()
+                .unwrap();
+        assert_eq!(records.len(), 6);
 
-        // expect self-mirror nodes
-        assert_eq!(graph.node_count(), 7);
-        assert!(graph
-            .node_indices()
-            .any(|node| graph.is_self_mirror_node(node)));
-        assert_eq!(old_graph.node_count(), 7);
-        assert!(old_graph
-            .node_indices()
-            .any(|node| old_graph.is_self_mirror_node(node)));
-        debug_assert_eq!(
-            input,
-            output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(input.clone()).unwrap(),
-            String::from_utf8(output.clone()).unwrap()
-        );
-        debug_assert_eq!(
-            output,
-            old_output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(output.clone()).unwrap(),
-            String::from_utf8(old_output.clone()).unwrap()
+        for (index, record) in records.iter().enumerate() {
+            assert_eq!(record.id, index);
+            for edge in &record.edges {
+                assert!(edge.to_node < records.len());
+                assert!(edge.to_side);
+            }
+        }
+        for pair in records.chunks(2) {
+            let forward = oriented_sequence_vec(&pair[0], &output_store);
+            let mut reverse_complement = oriented_sequence_vec(&pair[1], &output_store);
+            reverse_complement.reverse();
+            for character in &mut reverse_complement {
+                *character = match *character {
+                    b'A' => b'T',
+                    b'C' => b'G',
+                    b'G' => b'C',
+                    _ => b'A',
+                };
+            }
+            assert_eq!(forward, reverse_complement);
+        }
+
+        // Record 0 (AGT) links to the reverse complement of record 1, which is record 3.
+        assert_eq!(
+            records[0].edges,
+            vec![PlainBCalm2Edge {
+                from_side: true,
+                to_node: 3,
+                to_side: true,
+            }]
         );
     }
 
     #[test]
-    fn test_edge_read_write_minus_plus_loop() {
-        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:- L:-:0:+\n\
-            ATG\n\
+    fn test_read_edge_centric_with_id_map() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
             >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
-            GGTCTCGGGTAACA\n\
+            AATCTCGGGTAAAC\n\
             >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
-            CAGATT\n";
-        let input = Vec::from(test_file);
-        println!("{}", String::from_utf8(input.clone()).unwrap());
+            ACGAGG\n";
         let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let (graph, id_map): (PetBCalm2EdgeGraph<_>, _) =
+            read_bigraph_from_bcalm2_as_edge_centric_with_id_map(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                3,
+                &BCalm2ReadOptions {
+                    deduplicate_sequences: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
 
-        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+        assert_eq!(id_map.len(), 3);
+        assert_eq!(
+            id_map
+                .sequence_by_original_id(1, &sequence_store)
+                .unwrap()
+                .clone_as_vec(),
+            b"AATCTCGGGTAAAC".to_vec()
+        );
+        assert!(id_map.sequence_by_original_id(3, &sequence_store).is_none());
+
+        let edge = id_map.edge(2).unwrap();
+        assert_eq!(graph.edge_data(edge).id, 2);
+        assert_eq!(
+            graph.mirror_edge_edge_centric(edge),
+            Some(id_map.mirror_edge(2).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_write_isolated_tagless_node() {
+        let test_file: &'static [u8] = b">0\nAACG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
             BufReader::new(test_file),
             &mut sequence_store,
-            3,
         )
         .unwrap();
-        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(output, b">0\nAACG\n");
+    }
+
+    #[test]
+    fn test_raw_description_round_trip() {
+        let test_file: &'static [u8] = b">0 KC:i:4 L:+:1:- LN:i:3 km:f:3\n\
+            AGT\n\
+            >1 L:+:0:-  L:+:2:+ km:f:3.20 KC:i:2 LN:i:14\n\
+            GGTCTCGGGTAAGT\n\
+            >2 L:-:1:- km:f:2.2 LN:i:6 KC:i:15\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_with_options(
             BufReader::new(test_file),
             &mut sequence_store,
-            3,
+            &BCalm2ReadOptions {
+                preserve_raw_description: true,
+                ..Default::default()
+            },
         )
         .unwrap();
 
         let mut output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
-        let mut old_output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+        write_node_centric_bigraph_to_bcalm2_with_options(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+            &BCalm2WriteOptions {
+                emit_raw_description: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(output, test_file);
+    }
 
-        // expect self-mirror nodes
-        assert_eq!(graph.node_count(), 7);
-        assert!(graph
-            .node_indices()
-            .any(|node| graph.is_self_mirror_node(node)));
-        assert_eq!(old_graph.node_count(), 7);
-        assert!(old_graph
-            .node_indices()
-            .any(|node| old_graph.is_self_mirror_node(node)));
-        debug_assert_eq!(
-            input,
-            output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(input.clone()).unwrap(),
-            String::from_utf8(output.clone()).unwrap()
-        );
-        debug_assert_eq!(
-            output,
-            old_output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(output.clone()).unwrap(),
-            String::from_utf8(old_output.clone()).unwrap()
+    #[test]
+    fn test_raw_description_with_canonical_representative() {
+        let test_file: &'static [u8] = b">0 KC:i:4 L:+:1:- LN:i:3 km:f:3\n\
+            AGT\n\
+            >1 L:+:0:-  L:+:2:+ km:f:3.20 KC:i:2 LN:i:14\n\
+            GGTCTCGGGTAAGT\n\
+            >2 L:-:1:- km:f:2.2 LN:i:6 KC:i:15\n\
+            ATGATG\n\
+            >3 km:f:1 LN:i:4\n\
+            AACG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_with_options(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            &BCalm2ReadOptions {
+                preserve_raw_description: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2_with_options(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+            &BCalm2WriteOptions {
+                emit_raw_description: true,
+                representative: RepresentativeChoice::Canonical,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        // Records 0 and 1 are reverse complemented, and record 2 links to record 1,
+        // so only the description of record 3 is written verbatim.
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">0 LN:i:3 KC:i:4 km:f:3.0 L:-:1:+\nACT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:-:0:+ L:-:2:+\nACTTACCCGAGACC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:+\nATGATG\n\
+            >3 km:f:1 LN:i:4\nAACG\n"
         );
     }
 
     #[test]
-    fn test_edge_read_write_plus_minus_and_minus_plus_loop() {
-        let test_file: &'static [u8] =
-            b">0 LN:i:4 KC:i:4 km:f:3.0 L:+:0:- L:+:1:- L:+:2:+ L:-:0:+\n\
-            CGAT\n\
-            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:1:- L:+:2:+\n\
-            GGTCTCGGGTAAAT\n\
-            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:0:- L:-:1:- L:-:2:+\n\
+    fn test_read_topology_only() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
             ATGATG\n";
-        let input = Vec::from(test_file);
-        println!("{}", String::from_utf8(input.clone()).unwrap());
         let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
-
-        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
             BufReader::new(test_file),
             &mut sequence_store,
-            3,
         )
         .unwrap();
-        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
+        let topology: PetBCalm2NodeGraph<()> =
+            read_bcalm2_topology_only(BufReader::new(test_file)).unwrap();
+
+        assert_eq!(topology.node_count(), graph.node_count());
+        assert_eq!(topology.edge_count(), graph.edge_count());
+        for node in graph.node_indices() {
+            assert_eq!(topology.mirror_node(node), graph.mirror_node(node));
+            assert_eq!(topology.node_data(node).id, graph.node_data(node).id);
+            assert_eq!(
+                topology.node_data(node).length,
+                graph.node_data(node).length
+            );
+        }
+        let endpoints = |edges: Vec<Edge<_>>| {
+            let mut edges: Vec<_> = edges
+                .into_iter()
+                .map(|Edge { from_node, to_node }| (from_node, to_node))
+                .collect();
+            edges.sort_unstable();
+            edges
+        };
+        assert_eq!(
+            endpoints(
+                topology
+                    .edge_indices()
+                    .map(|edge| topology.edge_endpoints(edge))
+                    .collect()
+            ),
+            endpoints(
+                graph
+                    .edge_indices()
+                    .map(|edge| graph.edge_endpoints(edge))
+                    .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn test_read_edge_centric_with_overlaps() {
+        // The second record is shorter than the nominal k, and the links out of the first record overlap differently.
+        let test_file: &'static [u8] = b">0 LN:i:5 L:+:1:+ L:+:2:+\n\
+            AACGT\n\
+            >1 LN:i:5 L:-:0:-\n\
+            GTTTC\n\
+            >2 LN:i:4 L:-:0:-\n\
+            CGTA\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let (graph, overlaps): (PetBCalm2EdgeGraph<_>, _) =
+            read_bigraph_from_bcalm2_as_edge_centric_with_overlaps(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                5,
+                &BCalm2ReadOptions::default(),
+            )
+            .unwrap();
+
+        let edge = |id, forwards| {
+            graph
+                .edge_indices()
+                .find(|&edge| {
+                    graph.edge_data(edge).id == id && graph.edge_data(edge).forwards == forwards
+                })
+                .unwrap()
+        };
+        assert_eq!(
+            graph.edge_endpoints(edge(0, true)).to_node,
+            graph.edge_endpoints(edge(2, true)).from_node
+        );
+        assert_eq!(overlaps.len(), 4);
+        assert_eq!(overlaps[&(edge(0, true), edge(1, true))], 2);
+        assert_eq!(overlaps[&(edge(1, false), edge(0, false))], 2);
+        assert_eq!(overlaps[&(edge(0, true), edge(2, true))], 3);
+        assert_eq!(overlaps[&(edge(2, false), edge(0, false))], 3);
+
+        let test_file: &'static [u8] = b">0 LN:i:5 L:+:1:+\n\
+            AAAAA\n\
+            >1 LN:i:5 L:-:0:-\n\
+            CCCCC\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<(PetBCalm2EdgeGraph<_>, _)> =
+            read_bigraph_from_bcalm2_as_edge_centric_with_overlaps(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                5,
+                &BCalm2ReadOptions::default(),
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2MissingOverlap { from: 0, to: 1 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_plain_edge_order() {
+        let edge = |from_side, to_node, to_side| PlainBCalm2Edge {
+            from_side,
+            to_node,
+            to_side,
+        };
+        let mut edges = vec![
+            edge(true, 0, false),
+            edge(false, 2, true),
+            edge(true, 0, true),
+            edge(false, 1, true),
+            edge(false, 2, false),
+        ];
+        edges.sort();
+        assert_eq!(
+            edges,
+            vec![
+                edge(true, 0, false),
+                edge(true, 0, true),
+                edge(false, 1, true),
+                edge(false, 2, false),
+                edge(false, 2, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_colors_round_trip() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 CL:Z:0,2,5 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 CL:Z: L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
             BufReader::new(test_file),
             &mut sequence_store,
-            3,
         )
         .unwrap();
+        let nodes: Vec<_> = graph.node_indices().collect();
+        assert_eq!(graph.node_data(nodes[0]).colors, Some(vec![0, 2, 5]));
+        assert_eq!(graph.node_data(nodes[1]).colors, Some(Vec::new()));
+        assert_eq!(graph.node_data(nodes[2]).colors, None);
 
         let mut output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
-        let mut old_output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(output, test_file);
 
-        // expect self-mirror nodes but not edges
-        assert_eq!(graph.node_count(), 6);
-        assert!(graph
-            .node_indices()
-            .any(|node| graph.is_self_mirror_node(node)));
-        assert!(graph.edge_indices().all(|edge| {
-            let Edge { from_node, to_node } = graph.edge_endpoints(edge);
-            graph.mirror_node(from_node) != Some(to_node)
-        }));
-        assert_eq!(old_graph.node_count(), 6);
-        assert!(old_graph
-            .node_indices()
-            .any(|node| old_graph.is_self_mirror_node(node)));
-        assert!(old_graph.edge_indices().all(|edge| {
-            let Edge { from_node, to_node } = old_graph.edge_endpoints(edge);
-            old_graph.mirror_node(from_node) != Some(to_node)
-        }));
-        debug_assert_eq!(
-            input,
-            output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(input.clone()).unwrap(),
-            String::from_utf8(output.clone()).unwrap()
-        );
-        debug_assert_eq!(
-            output,
-            old_output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(output.clone()).unwrap(),
-            String::from_utf8(old_output.clone()).unwrap()
-        );
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric(
+                BufReader::new(b">0 CL:Z:0,x\nAGT\n".as_slice()),
+                &mut sequence_store,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2MalformedParameterError { .. }
+            ))
+        ));
     }
 
     #[test]
-    fn test_edge_read_self_mirror_edge() {
-        let test_file: &'static [u8] = b">0 LN:i:5 KC:i:4 km:f:3.0 L:+:1:+ L:-:1:+\n\
-            ACTGT\n\
-            >1 LN:i:4 KC:i:2 km:f:3.2 L:-:0:- L:-:0:+ L:-:2:-\n\
-            GTTC\n\
-            >2 LN:i:4 KC:i:15 km:f:2.2 L:+:1:+\n\
-            GGGT\n";
-        let input = Vec::from(test_file);
+    fn test_read_from_stdin_like_input() {
+        // The stdin readers wrap the locked stdin like this, so a cursor stands in for stdin.
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let stdin = std::io::Cursor::new(test_file.to_vec());
         let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
-
-        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
-            BufReader::new(test_file),
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            crate::io::maybe_decompress(stdin).unwrap(),
             &mut sequence_store,
-            3,
         )
         .unwrap();
-        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
-            BufReader::new(test_file),
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 4);
+
+        let stdin = std::io::Cursor::new(test_file.to_vec());
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            crate::io::maybe_decompress(stdin).unwrap(),
             &mut sequence_store,
             3,
         )
         .unwrap();
+        assert_eq!(graph.edge_count(), 6);
+    }
 
-        let mut output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
-        let mut old_output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+    #[test]
+    fn test_edge_read_sequence_too_short() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:+\n\
+            AAC\n\
+            >1 LN:i:2 L:-:0:-\n\
+            AC\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<PetBCalm2EdgeGraph<_>> =
+            read_bigraph_from_bcalm2_as_edge_centric(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                3,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2SequenceTooShort {
+                    id: 1,
+                    length: 2,
+                    kmer_size: 3
+                }
+            ))
+        ));
 
-        // expect self-mirror edges
-        assert_eq!(graph.node_count(), 6);
-        assert!(graph
-            .node_indices()
-            .all(|node| !graph.is_self_mirror_node(node)));
-        assert!(graph.edge_indices().any(|edge| {
-            let Edge { from_node, to_node } = graph.edge_endpoints(edge);
-            graph.mirror_node(from_node) == Some(to_node)
-        }));
-        assert_eq!(old_graph.node_count(), 6);
-        assert!(old_graph
-            .node_indices()
-            .all(|node| !old_graph.is_self_mirror_node(node)));
-        assert!(old_graph.edge_indices().any(|edge| {
-            let Edge { from_node, to_node } = old_graph.edge_endpoints(edge);
-            old_graph.mirror_node(from_node) == Some(to_node)
-        }));
-        debug_assert_eq!(
-            input,
-            output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(input.clone()).unwrap(),
-            String::from_utf8(output.clone()).unwrap()
-        );
-        debug_assert_eq!(
-            output,
-            old_output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(output.clone()).unwrap(),
-            String::from_utf8(old_output.clone()).unwrap()
-        );
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<PetBCalm2EdgeGraph<_>> =
+            read_bigraph_from_bcalm2_as_edge_centric(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                0,
+            );
+        assert!(matches!(result, Err(crate::error::Error::ZeroKmerSize)));
     }
 
     #[test]
-    fn test_edge_read_self_mirror_node_and_edge() {
-        let test_file: &'static [u8] = b">0 LN:i:5 KC:i:4 km:f:3.0 L:+:0:- L:+:0:+ L:+:1:+ L:+:2:- L:-:0:- L:-:0:+ L:-:1:+ L:-:2:-\n\
-            ATTAT\n\
-            >1 LN:i:5 KC:i:2 km:f:3.2 L:-:0:- L:-:0:+ L:-:1:+ L:-:2:-\n\
-            ATGTC\n\
-            >2 LN:i:4 KC:i:15 km:f:2.2 L:+:0:- L:+:0:+ L:+:1:+ L:+:2:-\n\
-            GGAT\n";
-        let input = Vec::from(test_file);
+    fn test_edge_read_kmer_size_too_large() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
         let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<PetBCalm2EdgeGraph<_>> =
+            read_bigraph_from_bcalm2_as_edge_centric(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                31,
+            );
+        let Err(error) = result else {
+            panic!("reading with a too large k-mer size succeeded");
+        };
+        assert!(error.to_string().contains("k = 31"));
+        assert!(matches!(
+            error,
+            crate::error::Error::BCalm2IoError(BCalm2IoError::BCalm2SequenceTooShort {
+                id: 0,
+                length: 3,
+                kmer_size: 31
+            })
+        ));
+    }
+
+    #[test]
+    fn test_edge_write_sorted_by_id() {
+        let record_count = 1000;
+        let mut test_file = Vec::new();
+        for index in 0..record_count {
+            // Write the records in a scrambled order of their ids.
+            let id = index * 7919 % record_count;
+            let mut sequence = b"AAC".to_vec();
+            let mut value = id;
+            for _ in 0..5 {
+                sequence.push(b"ACGT"[value % 4]);
+                value /= 4;
+            }
+            // Avoid self-complemental sequences, which would only complicate the test.
+            sequence.push(b'C');
+            test_file.extend_from_slice(format!(">{id} LN:i:{}\n", sequence.len()).as_bytes());
+            test_file.extend_from_slice(&sequence);
+            test_file.push(b'\n');
+        }
 
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
         let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
-            BufReader::new(test_file),
+            BufReader::new(test_file.as_slice()),
             &mut sequence_store,
             3,
         )
         .unwrap();
-        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
-            BufReader::new(test_file),
-            &mut sequence_store,
-            3,
+
+        let write = |sort_by_id| {
+            let mut output = Vec::new();
+            write_edge_centric_bigraph_to_bcalm2_with_options(
+                &graph,
+                &sequence_store,
+                &mut output,
+                &BCalm2WriteOptions {
+                    sort_by_id,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        let records = |output: &str| -> Vec<(usize, String)> {
+            let lines: Vec<_> = output.lines().collect();
+            lines
+                .chunks(2)
+                .map(|record| {
+                    let id = record[0][1..].split(' ').next().unwrap().parse().unwrap();
+                    (id, record.join("\n"))
+                })
+                .collect()
+        };
+
+        let mut unsorted = records(&write(false));
+        let sorted = records(&write(true));
+        assert_eq!(sorted.len(), record_count);
+        assert_ne!(unsorted, sorted);
+        unsorted.sort();
+        assert_eq!(unsorted, sorted);
+    }
+
+    #[test]
+    fn test_read_and_compact() {
+        let test_file: &'static [u8] = b">0 LN:i:4 KC:i:4 km:f:2.0 L:+:1:+\n\
+            AACG\n\
+            >1 LN:i:4 KC:i:8 km:f:4.0 L:+:2:+ L:-:0:-\n\
+            CGTT\n\
+            >2 LN:i:4 KC:i:3 km:f:1.5 L:-:1:-\n\
+            TTGC\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> =
+            read_and_compact_bcalm2(BufReader::new(test_file), &mut sequence_store, 3).unwrap();
+
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 0);
+        assert!(graph.verify_node_pairing());
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
         )
         .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">0 LN:i:8 KC:i:15 km:f:2.5\nAACGTTGC\n"
+        );
+    }
 
-        let mut output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
-        let mut old_output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
+    #[test]
+    fn test_node_read_with_edge_data() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: NodeBigraphWrapper<PetGraph<PlainBCalm2NodeData<_>, (usize, bool, bool)>> =
+            read_bigraph_from_bcalm2_as_node_centric_with_edge_data(
+                BufReader::new(test_file),
+                &mut sequence_store,
+                &BCalm2ReadOptions::default(),
+                |from_node, edge| (from_node, edge.from_side(), edge.to_side()),
+            )
+            .unwrap();
 
-        // expect self-mirror edges
-        assert_eq!(graph.node_count(), 5);
-        assert!(graph
-            .node_indices()
-            .any(|node| graph.is_self_mirror_node(node)));
-        assert!(graph.edge_indices().any(|edge| {
-            let Edge { from_node, to_node } = graph.edge_endpoints(edge);
-            graph.mirror_node(from_node) == Some(to_node)
-        }));
-        assert_eq!(old_graph.node_count(), 5);
-        assert!(old_graph
-            .node_indices()
-            .any(|node| old_graph.is_self_mirror_node(node)));
-        assert!(old_graph.edge_indices().any(|edge| {
-            let Edge { from_node, to_node } = old_graph.edge_endpoints(edge);
-            old_graph.mirror_node(from_node) == Some(to_node)
-        }));
-        debug_assert_eq!(
-            input,
-            output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(input.clone()).unwrap(),
-            String::from_utf8(output.clone()).unwrap()
-        );
-        debug_assert_eq!(
-            output,
-            old_output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(output.clone()).unwrap(),
-            String::from_utf8(old_output.clone()).unwrap()
+        assert_eq!(graph.edge_count(), 4);
+        let mut edges: Vec<_> = graph
+            .edge_indices()
+            .map(|edge| {
+                let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+                (
+                    from_node.as_usize(),
+                    to_node.as_usize(),
+                    *graph.edge_data(edge),
+                )
+            })
+            .collect();
+        edges.sort_unstable();
+        assert_eq!(
+            edges,
+            vec![
+                (0, 4, (0, true, false)),
+                (1, 2, (1, true, true)),
+                (1, 3, (1, true, false)),
+                (5, 4, (2, false, false)),
+            ]
         );
     }
 
     #[test]
-    fn test_edge_read_write_forward_merge() {
-        let test_file: &'static [u8] = b"\
-            >0 LN:i:3 KC:i:4 km:f:3.0 L:+:2:-\n\
+    fn test_owned_record() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
             AGT\n\
-            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:2:-\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
             GGTCTCGGGTAAGT\n\
-            >2 LN:i:6 KC:i:15 km:f:2.2 L:+:0:- L:+:1:-\n\
-            AAGAAC\n";
-        let input = Vec::from(test_file);
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let owned: Vec<OwnedBCalm2Record<DnaAlphabet>> = {
+            let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+            let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+                BufReader::new(test_file),
+                &mut sequence_store,
+            )
+            .unwrap();
+            graph
+                .node_indices()
+                .map(|node| {
+                    OwnedBCalm2Record::from_node_data(
+                        graph.node_data(node).clone(),
+                        &sequence_store,
+                    )
+                })
+                .collect()
+        };
+
+        assert_eq!(owned.len(), 6);
+        assert_eq!(owned[0].oriented_sequence().clone_as_vec(), b"AGT");
+        // Record i is node i, and its mirror is node 3 + i.
+        assert_eq!(owned[3].oriented_sequence().clone_as_vec(), b"ACT");
+        assert_eq!(owned[1].data.id, 1);
+        assert_eq!(owned[1].data.total_abundance, Some(2));
+        assert_eq!(owned[1].data.edges.len(), 2);
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let data = owned[5].clone().into_node_data(&mut sequence_store);
+        assert_eq!(data.id, 2);
+        assert!(!data.forwards);
+        assert_eq!(oriented_sequence_vec(&data, &sequence_store), b"CATCAT");
+    }
+
+    #[test]
+    fn test_sort_edges_on_read() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:2:+ L:+:0:- L:-:2:-\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:+:1:+ L:-:1:-\n\
+            ATGATG\n";
+        let reordered_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:-:2:- L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:- L:+:1:+\n\
+            ATGATG\n";
+        let read = |input: &'static [u8], sort_edges_on_read| {
+            let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+            let graph: PetBCalm2NodeGraph<_> =
+                read_bigraph_from_bcalm2_as_node_centric_with_options(
+                    BufReader::new(input),
+                    &mut sequence_store,
+                    &BCalm2ReadOptions {
+                        sort_edges_on_read,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            graph
+                .node_indices()
+                .map(|node| format!("{:?}", graph.node_data(node)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_ne!(read(test_file, false), read(reordered_file, false));
+        assert_eq!(read(test_file, true), read(reordered_file, true));
+    }
+
+    #[test]
+    fn test_node_read_from_files() {
+        let first_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n";
+        // Continues the numbering of the first file, so it can link to it.
+        let second_file: &'static [u8] = b">2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        // Starts its own numbering.
+        let third_file: &'static [u8] = b">0 LN:i:4 L:+:1:+\n\
+            AACG\n\
+            >1 LN:i:4 L:-:0:-\n\
+            CGTT\n";
+        let directory = std::env::temp_dir();
+        let paths: Vec<_> = [first_file, second_file, third_file]
+            .into_iter()
+            .enumerate()
+            .map(|(index, content)| {
+                let path = directory.join(format!(
+                    "genome_graph_test_node_read_from_files_{}_{index}.fa",
+                    std::process::id()
+                ));
+                std::fs::write(&path, content).unwrap();
+                path
+            })
+            .collect();
+
+        let read = |paths: &[std::path::PathBuf], id_scope| -> crate::error::Result<String> {
+            let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+            let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric_from_files(
+                paths,
+                &mut sequence_store,
+                id_scope,
+            )?;
+            let mut output = Vec::new();
+            write_node_centric_bigraph_to_bcalm2(
+                &graph,
+                &sequence_store,
+                bio::io::fasta::Writer::new(&mut output),
+            )?;
+            Ok(String::from_utf8(output).unwrap())
+        };
+        let global = read(&paths[..2], BCalm2IdScope::Global);
+        let twice = [paths[2].clone(), paths[2].clone()];
+        let per_input = read(&twice, BCalm2IdScope::PerInput);
+        // Without offset, the ids of the copies collide.
+        let colliding = read(&twice, BCalm2IdScope::Global);
+        for path in &paths {
+            std::fs::remove_file(path).unwrap();
+        }
+
+        assert_eq!(
+            global.unwrap(),
+            ">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\nAGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\nGGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\nATGATG\n"
+        );
+        assert_eq!(
+            per_input.unwrap(),
+            ">0 LN:i:4 L:+:1:+\nAACG\n\
+            >1 LN:i:4 L:-:0:-\nCGTT\n\
+            >2 LN:i:4 L:+:3:+\nAACG\n\
+            >3 LN:i:4 L:-:2:-\nCGTT\n"
+        );
+        assert!(matches!(
+            colliding,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2DuplicateNodeId { id: 0 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_read_ambiguity_codes() {
+        use compact_genome::implementation::alphabets::dna_alphabet_or_n::DnaAlphabetOrN;
+        use compact_genome::implementation::alphabets::dna_iupac_nucleic_acid_alphabet::DnaIupacNucleicAcidAlphabet;
+
+        let test_file: &'static [u8] = b">0 LN:i:5\n\
+            AANCG\n";
         let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            read_bigraph_from_bcalm2_as_node_centric(
+                BufReader::new(test_file),
+                &mut sequence_store,
+            );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::BCalm2IoError(
+                BCalm2IoError::BCalm2InvalidSequence { id: 0, .. }
+            ))
+        ));
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabetOrN>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(
+            oriented_sequence_vec(graph.node_data(0.into()), &sequence_store),
+            b"AANCG"
+        );
+        assert_eq!(
+            oriented_sequence_vec(graph.node_data(1.into()), &sequence_store),
+            b"CGNTT"
+        );
 
+        let test_file: &'static [u8] = b">0 LN:i:5\n\
+            AARCT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaIupacNucleicAcidAlphabet>::default();
         let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
             BufReader::new(test_file),
             &mut sequence_store,
@@ -1440,71 +7598,67 @@ mod tests {
             3,
         )
         .unwrap();
+        for graph in [&graph, &old_graph] {
+            assert_eq!(graph.edge_count(), 2);
+            assert!(graph.verify_node_pairing());
+            assert!(graph.verify_edge_mirror_property());
+            let mut sequences: Vec<_> = graph
+                .edge_indices()
+                .map(|edge| oriented_sequence_vec(graph.edge_data(edge), &sequence_store))
+                .collect();
+            sequences.sort();
+            assert_eq!(sequences, vec![b"AARCT".to_vec(), b"AGYTT".to_vec()]);
+        }
 
         let mut output = Vec::new();
         write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
-        let mut old_output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
-
-        debug_assert_eq!(
-            input,
-            output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(input.clone()).unwrap(),
-            String::from_utf8(output.clone()).unwrap()
-        );
-        debug_assert_eq!(
-            output,
-            old_output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(output.clone()).unwrap(),
-            String::from_utf8(old_output.clone()).unwrap()
-        );
+        assert_eq!(output, test_file);
     }
 
     #[test]
-    fn test_edge_read_write_multigraph() {
-        let test_file: &'static [u8] = b"\
-            >0 LN:i:7 KC:i:4 km:f:3.0 L:+:2:+ L:-:2:-\n\
-            AGTTCTC\n\
-            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:2:+ L:-:2:-\n\
-            AGTCTCGGGTAATC\n\
-            >2 LN:i:6 KC:i:15 km:f:2.2 L:+:0:+ L:+:1:+ L:-:0:- L:-:1:-\n\
-            TCGAAG\n";
-        let input = Vec::from(test_file);
+    #[cfg(feature = "bgzip")]
+    fn test_write_to_bgzip_file() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
         let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
-
-        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
             BufReader::new(test_file),
             &mut sequence_store,
-            3,
         )
         .unwrap();
-        let old_graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric_old(
-            BufReader::new(test_file),
-            &mut sequence_store,
-            3,
+
+        let path = std::env::temp_dir().join(format!(
+            "genome_graph_test_write_to_bgzip_file_{}.fa.gz",
+            std::process::id()
+        ));
+        crate::io::bcalm2::write_node_centric_bigraph_to_bcalm2_to_bgzip_file(
+            &graph,
+            &sequence_store,
+            &path,
         )
         .unwrap();
+        let compressed = std::fs::read(&path).unwrap();
+        let mut read_sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let read_graph: crate::error::Result<PetBCalm2NodeGraph<_>> =
+            crate::io::bcalm2::read_bigraph_from_bcalm2_as_node_centric_from_file(
+                &path,
+                &mut read_sequence_store,
+            );
+        std::fs::remove_file(&path).unwrap();
+        let read_graph = read_graph.unwrap();
+        assert!(compressed.starts_with(&[0x1f, 0x8b]));
 
         let mut output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&graph, &sequence_store, &mut output).unwrap();
-        let mut old_output = Vec::new();
-        write_edge_centric_bigraph_to_bcalm2(&old_graph, &sequence_store, &mut old_output).unwrap();
-
-        debug_assert_eq!(
-            input,
-            output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(input.clone()).unwrap(),
-            String::from_utf8(output.clone()).unwrap()
-        );
-        debug_assert_eq!(
-            output,
-            old_output,
-            "in:\n{}\n\nout:\n{}\n",
-            String::from_utf8(output.clone()).unwrap(),
-            String::from_utf8(old_output.clone()).unwrap()
-        );
+        write_node_centric_bigraph_to_bcalm2(
+            &read_graph,
+            &read_sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(output, test_file);
     }
 }
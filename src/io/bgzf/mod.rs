@@ -0,0 +1,145 @@
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use std::io::Write;
+
+/// The maximum amount of uncompressed data in a single block, as used by htslib.
+const BLOCK_DATA_SIZE: usize = 0xff00;
+/// The maximum size of a compressed block including its header and footer.
+const MAX_BLOCK_SIZE: usize = 0x10000;
+/// The size of the header and footer of a block.
+const BLOCK_OVERHEAD: usize = 26;
+/// The header of a block up to its size field.
+const BLOCK_HEADER: [u8; 16] = [
+    0x1f, 0x8b, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x06, 0x00, b'B', b'C', 0x02, 0x00,
+];
+/// The empty block that marks the end of a BGZF file.
+const EOF_BLOCK: [u8; 28] = [
+    0x1f, 0x8b, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x06, 0x00, b'B', b'C', 0x02, 0x00,
+    0x1b, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// A writer that compresses its output in the blocked gzip format (BGZF) as written by `bgzip`.
+///
+/// The output is a sequence of independent gzip members of at most 64KiB, so it can be read by any gzip reader,
+/// including the `_from_file` readers of this crate.
+/// Flushing the writer ends the current block early.
+///
+/// The output is completed by [`BgzfWriter::finish`].
+/// If the writer is dropped without finishing, it is finished automatically, but errors are ignored.
+pub struct BgzfWriter<W: Write> {
+    writer: Option<W>,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> BgzfWriter<W> {
+    /// Wraps the given writer into a BGZF encoder.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Some(writer),
+            buffer: Vec::with_capacity(BLOCK_DATA_SIZE),
+        }
+    }
+
+    /// Writes the remaining data and the end-of-file marker, and returns the inner writer.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        self.write_end()?;
+        Ok(self
+            .writer
+            .take()
+            .expect("writer is only taken when finishing"))
+    }
+
+    fn write_end(&mut self) -> std::io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.write_block()?;
+        }
+        let writer = self.writer_mut();
+        writer.write_all(&EOF_BLOCK)?;
+        writer.flush()
+    }
+
+    fn write_block(&mut self) -> std::io::Result<()> {
+        let mut compressed = deflate(&self.buffer, Compression::default())?;
+        // Incompressible data may grow beyond the maximum block size, but stored blocks only add a few bytes.
+        if compressed.len() + BLOCK_OVERHEAD > MAX_BLOCK_SIZE {
+            compressed = deflate(&self.buffer, Compression::none())?;
+        }
+        let mut crc = Crc::new();
+        crc.update(&self.buffer);
+        let block_size = (compressed.len() + BLOCK_OVERHEAD - 1) as u16;
+        let data_size = self.buffer.len() as u32;
+
+        let writer = self.writer_mut();
+        writer.write_all(&BLOCK_HEADER)?;
+        writer.write_all(&block_size.to_le_bytes())?;
+        writer.write_all(&compressed)?;
+        writer.write_all(&crc.sum().to_le_bytes())?;
+        writer.write_all(&data_size.to_le_bytes())?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    fn writer_mut(&mut self) -> &mut W {
+        self.writer
+            .as_mut()
+            .expect("writer is only taken when finishing")
+    }
+}
+
+impl<W: Write> Write for BgzfWriter<W> {
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        let amount = buffer.len().min(BLOCK_DATA_SIZE - self.buffer.len());
+        self.buffer.extend_from_slice(&buffer[..amount]);
+        if self.buffer.len() == BLOCK_DATA_SIZE {
+            self.write_block()?;
+        }
+        Ok(amount)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.write_block()?;
+        }
+        self.writer_mut().flush()
+    }
+}
+
+impl<W: Write> Drop for BgzfWriter<W> {
+    fn drop(&mut self) {
+        if self.writer.is_some() {
+            let _ = self.write_end();
+        }
+    }
+}
+
+fn deflate(data: &[u8], compression: Compression) -> std::io::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), compression);
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::bgzf::{BgzfWriter, EOF_BLOCK};
+    use std::io::{Read, Write};
+
+    #[test]
+    fn test_bgzf_round_trip() {
+        // Spans multiple blocks.
+        let mut data: Vec<u8> = (0..100_000u32).flat_map(|i| i.to_le_bytes()).collect();
+        data.extend(b"ACGT".repeat(50_000));
+
+        let mut writer = BgzfWriter::new(Vec::new());
+        writer.write_all(&data).unwrap();
+        let output = writer.finish().unwrap();
+        assert!(output.ends_with(&EOF_BLOCK));
+
+        let mut decoded = Vec::new();
+        flate2::read::MultiGzDecoder::new(output.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+
+        assert_eq!(BgzfWriter::new(Vec::new()).finish().unwrap(), EOF_BLOCK);
+    }
+}
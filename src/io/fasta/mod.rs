@@ -1,6 +1,6 @@
 use crate::error::Result;
 use crate::io::gfa::BidirectedGfaEdgeData;
-use crate::io::SequenceData;
+use crate::io::{maybe_decompress, SequenceData};
 use bigraph::interface::dynamic_bigraph::{DynamicBigraph, DynamicEdgeCentricBigraph};
 use bigraph::interface::BidirectedData;
 use bigraph::traitgraph::index::GraphIndex;
@@ -40,6 +40,144 @@ pub trait FastaData<AlphabetType: Alphabet, SourceSequenceStore: SequenceStore<A
     ) -> &'a Self::GenomeSubsequence;
 }
 
+/// A rule for spelling the sequence of an edge-centric walk from the sequences of its edges.
+pub trait SequenceSpeller<
+    AlphabetType: Alphabet,
+    SourceSequenceStore: SequenceStore<AlphabetType>,
+    Graph: GraphBase,
+>
+{
+    /// Spell the sequence of the given walk, where consecutive edges overlap by `kmer_size - 1` characters.
+    fn spell(
+        &self,
+        graph: &Graph,
+        source_sequence_store: &SourceSequenceStore,
+        walk: &[Graph::EdgeIndex],
+        kmer_size: usize,
+    ) -> crate::error::Result<BitVectorGenome<AlphabetType>>;
+}
+
+/// Spells walks by removing the overlap of `kmer_size - 1` characters between consecutive edges.
+/// This spells the sequence that the walk represents in a de Bruijn graph.
+/// Returns [`Error::ZeroKmerSize`](crate::error::Error::ZeroKmerSize) if `kmer_size` is zero.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TrimOverlapSpeller;
+
+/// Spells walks by concatenating the full sequences of their edges, keeping the overlaps between consecutive edges.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeepOverlapSpeller;
+
+impl<
+        AlphabetType: Alphabet + 'static,
+        SourceSequenceStore: SequenceStore<AlphabetType>,
+        EdgeData: SequenceData<AlphabetType, SourceSequenceStore>,
+        Graph: ImmutableGraphContainer<EdgeData = EdgeData>,
+    > SequenceSpeller<AlphabetType, SourceSequenceStore, Graph> for TrimOverlapSpeller
+{
+    fn spell(
+        &self,
+        graph: &Graph,
+        source_sequence_store: &SourceSequenceStore,
+        walk: &[Graph::EdgeIndex],
+        kmer_size: usize,
+    ) -> crate::error::Result<BitVectorGenome<AlphabetType>> {
+        let overlap = kmer_size
+            .checked_sub(1)
+            .ok_or(crate::error::Error::ZeroKmerSize)?;
+        let mut sequence = Vec::new();
+        for (i, edge) in walk.iter().enumerate() {
+            let edge_sequence: BitVectorGenome<AlphabetType> =
+                graph.edge_data(*edge).sequence_owned(source_sequence_store);
+            let overlap = if i == 0 { 0 } else { overlap };
+            sequence.extend(edge_sequence.iter().skip(overlap).cloned());
+        }
+        Ok(sequence.into_iter().collect())
+    }
+}
+
+impl<
+        AlphabetType: Alphabet + 'static,
+        SourceSequenceStore: SequenceStore<AlphabetType>,
+        EdgeData: SequenceData<AlphabetType, SourceSequenceStore>,
+        Graph: ImmutableGraphContainer<EdgeData = EdgeData>,
+    > SequenceSpeller<AlphabetType, SourceSequenceStore, Graph> for KeepOverlapSpeller
+{
+    fn spell(
+        &self,
+        graph: &Graph,
+        source_sequence_store: &SourceSequenceStore,
+        walk: &[Graph::EdgeIndex],
+        _kmer_size: usize,
+    ) -> crate::error::Result<BitVectorGenome<AlphabetType>> {
+        let mut sequence = Vec::new();
+        for edge in walk {
+            let edge_sequence: BitVectorGenome<AlphabetType> =
+                graph.edge_data(*edge).sequence_owned(source_sequence_store);
+            sequence.extend(edge_sequence.iter().cloned());
+        }
+        Ok(sequence.into_iter().collect())
+    }
+}
+
+/// How the fasta records of contigs are named by the walk writers.
+pub struct ContigNaming<'a, Index> {
+    /// Computes the id of a record from the index of its contig and its walk.
+    pub name: Box<dyn Fn(usize, &[Index]) -> String + 'a>,
+    /// If true, `LN:i:<length>` is written as description of each record,
+    /// where `<length>` is the length of the sequence of the contig.
+    pub append_length: bool,
+}
+
+impl<Index> ContigNaming<'_, Index> {
+    /// Names the records by the index of their contig, without description.
+    /// This is the naming of the walk writers without naming parameter.
+    pub fn index() -> Self {
+        Self {
+            name: Box::new(|index, _| index.to_string()),
+            append_length: false,
+        }
+    }
+
+    /// Create a fasta record for the contig with the given index, walk and sequence.
+    fn record(&self, index: usize, walk: &[Index], sequence: &[u8]) -> Record {
+        let description = self
+            .append_length
+            .then(|| format!("LN:i:{}", sequence.len()));
+        Record::with_attrs(&(self.name)(index, walk), description.as_deref(), sequence)
+    }
+}
+
+impl<Index> Default for ContigNaming<'_, Index> {
+    /// Names the records `contig_<index>`, without description.
+    fn default() -> Self {
+        Self {
+            name: Box::new(|index, _| format!("contig_{index}")),
+            append_length: false,
+        }
+    }
+}
+
+/// Spell the reverse complement of an edge-centric walk.
+///
+/// The walk is spelled like by [`TrimOverlapSpeller`], so the result equals the spelling of the mirror walk,
+/// i.e. the walk of the mirror edges in reverse order.
+/// Returns [`Error::ZeroKmerSize`](crate::error::Error::ZeroKmerSize) if `kmer_size` is zero.
+pub fn reverse_complement_walk<
+    AlphabetType: Alphabet + 'static,
+    SourceSequenceStore: SequenceStore<AlphabetType>,
+    EdgeData: SequenceData<AlphabetType, SourceSequenceStore>,
+    Graph: ImmutableGraphContainer<EdgeData = EdgeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &SourceSequenceStore,
+    walk: &[Graph::EdgeIndex],
+    kmer_size: usize,
+) -> crate::error::Result<BitVectorGenome<AlphabetType>> {
+    Ok(TrimOverlapSpeller
+        .spell(graph, source_sequence_store, walk, kmer_size)?
+        .clone_as_reverse_complement())
+}
+
 /// Write a sequence of walks in a graph as fasta records.
 pub fn write_walks_as_fasta<
     'ws,
@@ -57,30 +195,75 @@ pub fn write_walks_as_fasta<
     kmer_size: usize,
     walks: WalkSource,
     writer: &mut bio::io::fasta::Writer<Writer>,
+) -> crate::error::Result<()> {
+    write_walks_as_fasta_with_speller(
+        graph,
+        source_sequence_store,
+        kmer_size,
+        walks,
+        &TrimOverlapSpeller,
+        writer,
+    )
+}
+
+/// Write a sequence of walks in a graph as fasta records, using the given speller to construct their sequences.
+pub fn write_walks_as_fasta_with_speller<
+    'ws,
+    AlphabetType: Alphabet + 'static,
+    SourceSequenceStore: SequenceStore<AlphabetType>,
+    Graph: GraphBase,
+    Walk: 'ws + EdgeWalk<Graph, Subwalk>,
+    Subwalk: EdgeWalk<Graph, Subwalk> + ?Sized,
+    WalkSource: 'ws + IntoIterator<Item = &'ws Walk>,
+    Writer: std::io::Write,
+>(
+    graph: &Graph,
+    source_sequence_store: &SourceSequenceStore,
+    kmer_size: usize,
+    walks: WalkSource,
+    speller: &dyn SequenceSpeller<AlphabetType, SourceSequenceStore, Graph>,
+    writer: &mut bio::io::fasta::Writer<Writer>,
+) -> crate::error::Result<()> {
+    write_walks_as_fasta_with_naming(
+        graph,
+        source_sequence_store,
+        kmer_size,
+        walks,
+        speller,
+        &ContigNaming::index(),
+        writer,
+    )
+}
+
+/// Write a sequence of walks in a graph as fasta records, using the given speller to construct their sequences
+/// and the given naming to construct their ids.
+pub fn write_walks_as_fasta_with_naming<
+    'ws,
+    AlphabetType: Alphabet + 'static,
+    SourceSequenceStore: SequenceStore<AlphabetType>,
+    Graph: GraphBase,
+    Walk: 'ws + EdgeWalk<Graph, Subwalk>,
+    Subwalk: EdgeWalk<Graph, Subwalk> + ?Sized,
+    WalkSource: 'ws + IntoIterator<Item = &'ws Walk>,
+    Writer: std::io::Write,
+>(
+    graph: &Graph,
+    source_sequence_store: &SourceSequenceStore,
+    kmer_size: usize,
+    walks: WalkSource,
+    speller: &dyn SequenceSpeller<AlphabetType, SourceSequenceStore, Graph>,
+    naming: &ContigNaming<Graph::EdgeIndex>,
+    writer: &mut bio::io::fasta::Writer<Writer>,
 ) -> crate::error::Result<()> {
     for (i, walk) in walks.into_iter().enumerate() {
         if walk.is_empty() {
             return Err(FastaIoError::EmptyWalkError.into());
         }
 
-        let mut sequence: DefaultGenome<AlphabetType> = graph
-            .edge_data(walk[0])
-            .sequence_owned(source_sequence_store);
-        for edge in walk.iter().skip(1) {
-            let edge_data = graph.edge_data(*edge);
-            if let Some(sequence_ref) = edge_data.sequence_ref(source_sequence_store) {
-                let sequence_ref = sequence_ref.iter().skip(kmer_size - 1);
-                sequence.extend(sequence_ref.cloned());
-            } else {
-                let sequence_owned: DefaultGenome<AlphabetType> =
-                    edge_data.sequence_owned(source_sequence_store);
-                let sequence_owned = sequence_owned.iter().skip(kmer_size - 1);
-                sequence.extend(sequence_owned.cloned());
-            }
-        }
+        let walk: Vec<_> = walk.iter().copied().collect();
+        let sequence = speller.spell(graph, source_sequence_store, &walk, kmer_size)?;
 
-        let record =
-            bio::io::fasta::Record::with_attrs(&format!("{}", i), None, &sequence.clone_as_vec());
+        let record = naming.record(i, &walk, &sequence.clone_as_vec());
         writer.write_record(&record).map_err(FastaIoError::from)?;
     }
 
@@ -132,12 +315,42 @@ pub fn write_node_centric_walks_as_fasta<
     kmer_size: usize,
     walks: WalkSource,
     writer: &mut bio::io::fasta::Writer<Writer>,
+) -> crate::error::Result<()> {
+    write_node_centric_walks_as_fasta_with_naming(
+        graph,
+        source_sequence_store,
+        kmer_size,
+        walks,
+        &ContigNaming::index(),
+        writer,
+    )
+}
+
+/// Write a sequence of node-centric walks in a graph as fasta records, using the given naming to construct their ids.
+pub fn write_node_centric_walks_as_fasta_with_naming<
+    'ws,
+    AlphabetType: Alphabet + 'static,
+    SourceSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: SequenceData<AlphabetType, SourceSequenceStore>,
+    Graph: ImmutableGraphContainer<NodeData = NodeData>,
+    Walk: 'ws + NodeWalk<Graph, Subwalk>,
+    Subwalk: NodeWalk<Graph, Subwalk> + ?Sized,
+    WalkSource: 'ws + IntoIterator<Item = &'ws Walk>,
+    Writer: std::io::Write,
+>(
+    graph: &Graph,
+    source_sequence_store: &SourceSequenceStore,
+    kmer_size: usize,
+    walks: WalkSource,
+    naming: &ContigNaming<Graph::NodeIndex>,
+    writer: &mut bio::io::fasta::Writer<Writer>,
 ) -> crate::error::Result<()> {
     for (i, walk) in walks.into_iter().enumerate() {
         if walk.is_empty() {
             return Err(FastaIoError::EmptyWalkError.into());
         }
 
+        let walk: Vec<_> = walk.iter().copied().collect();
         let mut sequence: DefaultGenome<AlphabetType> = graph
             .node_data(walk[0])
             .sequence_owned(source_sequence_store);
@@ -154,8 +367,7 @@ pub fn write_node_centric_walks_as_fasta<
             }
         }
 
-        let record =
-            bio::io::fasta::Record::with_attrs(&format!("{}", i), None, &sequence.clone_as_vec());
+        let record = naming.record(i, &walk, &sequence.clone_as_vec());
         writer.write_record(&record).map_err(FastaIoError::from)?;
     }
 
@@ -516,7 +728,7 @@ where
     <GenomeSequenceStore as SequenceStore<AlphabetType>>::Handle: Clone,
 {
     read_bigraph_from_fasta_as_edge_centric(
-        BufReader::new(File::open(path)?),
+        maybe_decompress(BufReader::new(File::open(path)?))?,
         target_sequence_store,
         kmer_size,
     )
@@ -799,3 +1011,204 @@ where
     Ok(bigraph)
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use crate::io::bcalm2::{
+        read_bigraph_from_bcalm2_as_edge_centric, read_bigraph_from_bcalm2_as_node_centric,
+    };
+    use crate::io::fasta::{
+        reverse_complement_walk, write_node_centric_walks_as_fasta_with_naming,
+        write_walks_as_fasta_with_naming, write_walks_as_fasta_with_speller, ContigNaming,
+        KeepOverlapSpeller, SequenceSpeller, TrimOverlapSpeller,
+    };
+    use crate::types::{PetBCalm2EdgeGraph, PetBCalm2NodeGraph};
+    use bigraph::interface::static_bigraph::StaticEdgeCentricBigraph;
+    use bigraph::traitgraph::index::GraphIndex;
+    use bigraph::traitgraph::interface::ImmutableGraphContainer;
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use compact_genome::interface::sequence::GenomeSequence;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_spellers() {
+        let test_file: &'static [u8] = b">0 LN:i:4 L:+:1:+\n\
+            AACG\n\
+            >1 LN:i:4 L:-:0:-\n\
+            CGAT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let forward_edge = |id| {
+            graph
+                .edge_indices()
+                .find(|&edge| graph.edge_data(edge).id == id && graph.edge_data(edge).forwards)
+                .unwrap()
+        };
+        let walk = vec![forward_edge(0), forward_edge(1)];
+
+        assert_eq!(
+            TrimOverlapSpeller
+                .spell(&graph, &sequence_store, &walk, 3)
+                .unwrap()
+                .clone_as_vec(),
+            b"AACGAT".to_vec()
+        );
+        assert_eq!(
+            KeepOverlapSpeller
+                .spell(&graph, &sequence_store, &walk, 3)
+                .unwrap()
+                .clone_as_vec(),
+            b"AACGCGAT".to_vec()
+        );
+        assert!(matches!(
+            TrimOverlapSpeller.spell(&graph, &sequence_store, &walk, 0),
+            Err(crate::error::Error::ZeroKmerSize)
+        ));
+
+        let mut output = Vec::new();
+        {
+            let mut writer = bio::io::fasta::Writer::new(&mut output);
+            write_walks_as_fasta_with_speller(
+                &graph,
+                &sequence_store,
+                3,
+                &vec![walk],
+                &KeepOverlapSpeller,
+                &mut writer,
+            )
+            .unwrap();
+            writer.flush().unwrap();
+        }
+        assert_eq!(String::from_utf8(output).unwrap(), ">0\nAACGCGAT\n");
+    }
+
+    #[test]
+    fn test_reverse_complement_walk() {
+        let test_file: &'static [u8] = b">0 LN:i:4 L:+:1:+\n\
+            AACG\n\
+            >1 LN:i:4 L:-:0:-\n\
+            CGAT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let forward_edge = |id| {
+            graph
+                .edge_indices()
+                .find(|&edge| graph.edge_data(edge).id == id && graph.edge_data(edge).forwards)
+                .unwrap()
+        };
+        let walk = vec![forward_edge(0), forward_edge(1)];
+        let mirror_walk: Vec<_> = walk
+            .iter()
+            .rev()
+            .map(|&edge| graph.mirror_edge_edge_centric(edge).unwrap())
+            .collect();
+
+        let reverse_complement =
+            reverse_complement_walk(&graph, &sequence_store, &walk, 3).unwrap();
+        assert_eq!(reverse_complement.clone_as_vec(), b"ATCGTT".to_vec());
+        assert_eq!(
+            reverse_complement.clone_as_vec(),
+            TrimOverlapSpeller
+                .spell(&graph, &sequence_store, &mirror_walk, 3)
+                .unwrap()
+                .clone_as_vec()
+        );
+        assert!(matches!(
+            reverse_complement_walk(&graph, &sequence_store, &walk, 0),
+            Err(crate::error::Error::ZeroKmerSize)
+        ));
+    }
+
+    #[test]
+    fn test_contig_naming() {
+        let test_file: &'static [u8] = b">0 LN:i:4 L:+:1:+\n\
+            AACG\n\
+            >1 LN:i:4 L:-:0:-\n\
+            CGAT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2EdgeGraph<_> = read_bigraph_from_bcalm2_as_edge_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+            3,
+        )
+        .unwrap();
+        let forward_edge = |id| {
+            graph
+                .edge_indices()
+                .find(|&edge| graph.edge_data(edge).id == id && graph.edge_data(edge).forwards)
+                .unwrap()
+        };
+        let walk = vec![forward_edge(0), forward_edge(1)];
+
+        let mut output = Vec::new();
+        {
+            let mut writer = bio::io::fasta::Writer::new(&mut output);
+            write_walks_as_fasta_with_naming(
+                &graph,
+                &sequence_store,
+                3,
+                &vec![walk],
+                &TrimOverlapSpeller,
+                &ContigNaming {
+                    append_length: true,
+                    ..Default::default()
+                },
+                &mut writer,
+            )
+            .unwrap();
+            writer.flush().unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">contig_0 LN:i:6\nAACGAT\n"
+        );
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+        let nodes: Vec<_> = graph.node_indices().collect();
+        let mut output = Vec::new();
+        {
+            let mut writer = bio::io::fasta::Writer::new(&mut output);
+            write_node_centric_walks_as_fasta_with_naming(
+                &graph,
+                &sequence_store,
+                3,
+                &vec![vec![nodes[0], nodes[1]]],
+                &ContigNaming {
+                    name: Box::new(|index, walk| {
+                        format!(
+                            "utg{}_nodes{}_from{}",
+                            index + 1,
+                            walk.len(),
+                            walk[0].as_usize()
+                        )
+                    }),
+                    append_length: false,
+                },
+                &mut writer,
+            )
+            .unwrap();
+            writer.flush().unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">utg1_nodes2_from0\nAACGAT\n"
+        );
+    }
+}
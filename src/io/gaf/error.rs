@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GafIoError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("walk is empty")]
+    EmptyWalkError,
+}
@@ -0,0 +1,114 @@
+use crate::io::bcalm2::{BCalm2NodeData, PlainBCalm2NodeData};
+use bigraph::traitgraph::interface::ImmutableGraphContainer;
+use bigraph::traitgraph::walks::NodeWalk;
+use error::GafIoError;
+
+pub mod error;
+
+/// Node data that identifies an oriented segment, as used in the path column of the graph alignment format.
+pub trait GafSegment {
+    /// The name of the segment in the path.
+    fn segment_id(&self) -> usize;
+
+    /// True if the node is the forward orientation of its segment, false if it is the reverse complement.
+    fn is_forward(&self) -> bool;
+}
+
+impl<GenomeSequenceStoreHandle> GafSegment for BCalm2NodeData<GenomeSequenceStoreHandle> {
+    fn segment_id(&self) -> usize {
+        self.id
+    }
+
+    fn is_forward(&self) -> bool {
+        self.forwards
+    }
+}
+
+impl<GenomeSequenceStoreHandle> GafSegment for PlainBCalm2NodeData<GenomeSequenceStoreHandle> {
+    fn segment_id(&self) -> usize {
+        self.id
+    }
+
+    fn is_forward(&self) -> bool {
+        self.forwards
+    }
+}
+
+/// Write a sequence of node-centric walks in a graph as paths of the graph alignment format (gaf), one per line.
+///
+/// Each node of a walk is written as its segment id, prefixed with `>` if the node is forward and with `<` if it is reverse,
+/// e.g. `>0<1>2`.
+pub fn write_walks_as_gaf_paths<
+    'ws,
+    W: std::io::Write,
+    NodeData: GafSegment,
+    Graph: ImmutableGraphContainer<NodeData = NodeData>,
+    Walk: 'ws + NodeWalk<Graph, Subwalk>,
+    Subwalk: NodeWalk<Graph, Subwalk> + ?Sized,
+    WalkSource: 'ws + IntoIterator<Item = &'ws Walk>,
+>(
+    graph: &Graph,
+    walks: WalkSource,
+    mut writer: W,
+) -> crate::error::Result<()> {
+    for walk in walks {
+        if walk.is_empty() {
+            return Err(GafIoError::EmptyWalkError.into());
+        }
+
+        let mut path = String::new();
+        for node in walk.iter() {
+            let node_data = graph.node_data(*node);
+            path.push(if node_data.is_forward() { '>' } else { '<' });
+            path.push_str(&node_data.segment_id().to_string());
+        }
+        writeln!(writer, "{path}").map_err(GafIoError::from)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::bcalm2::read_bigraph_from_bcalm2_as_node_centric;
+    use crate::io::gaf::error::GafIoError;
+    use crate::io::gaf::write_walks_as_gaf_paths;
+    use crate::types::PetBCalm2NodeGraph;
+    use bigraph::traitgraph::interface::ImmutableGraphContainer;
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use std::io::BufReader;
+
+    #[test]
+    fn test_write_walks_as_gaf_paths() {
+        let test_file: &'static [u8] = b">0 LN:i:3 L:+:1:-\n\
+            AAC\n\
+            >1 LN:i:3 L:+:0:- L:-:2:+\n\
+            GGT\n\
+            >2 LN:i:3 L:-:1:+\n\
+            CCA\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        // Record i is node i, and its mirror is node 3 + i.
+        let nodes: Vec<_> = graph.node_indices().collect();
+        let walks = vec![
+            vec![nodes[0], nodes[4], nodes[2]],
+            vec![nodes[5], nodes[1], nodes[3]],
+        ];
+        let mut output = Vec::new();
+        write_walks_as_gaf_paths(&graph, &walks, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), ">0<1>2\n<2>1<0\n");
+
+        let walks = vec![nodes[..0].to_vec()];
+        assert!(matches!(
+            write_walks_as_gaf_paths(&graph, &walks, &mut Vec::new()),
+            Err(crate::error::Error::GafIoError(GafIoError::EmptyWalkError))
+        ));
+    }
+}
@@ -10,4 +10,23 @@ pub enum GfaIoError {
 
     #[error("an L-line was encountered, at least one of the nodes is missing")]
     MissingNode,
+
+    #[error("a P-line contains a malformed step: '{step}'")]
+    MalformedPath { step: String },
+
+    #[error("a W-line is malformed: '{line}'")]
+    MalformedWalk { line: String },
+
+    #[error("a P-line or W-line refers to a missing segment: '{segment}'")]
+    MissingPathSegment { segment: String },
+
+    #[error("segment '{id}' declares length {declared}, but its sequence has length {actual}")]
+    SegmentLengthMismatch {
+        id: String,
+        declared: usize,
+        actual: usize,
+    },
+
+    #[error("segment '{id}' has a malformed length tag: '{tag}'")]
+    MalformedSegmentLength { id: String, tag: String },
 }
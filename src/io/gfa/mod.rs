@@ -1,9 +1,10 @@
 use crate::error::Result;
-use crate::io::SequenceData;
+use crate::io::{maybe_decompress, SequenceData};
 use bigraph::interface::dynamic_bigraph::{DynamicBigraph, DynamicEdgeCentricBigraph};
 use bigraph::interface::BidirectedData;
 use bigraph::traitgraph::index::GraphIndex;
 use bigraph::traitgraph::interface::GraphBase;
+use bigraph::traitgraph::walks::VecNodeWalk;
 use compact_genome::implementation::DefaultGenome;
 use compact_genome::interface::alphabet::Alphabet;
 use compact_genome::interface::sequence::{GenomeSequence, OwnedGenomeSequence};
@@ -142,6 +143,17 @@ pub struct GfaReadFileProperties {
     pub header: Option<String>,
 }
 
+/// The label of a walk given by a `W` line of a gfa file.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct WalkLabel {
+    /// The name of the sample the walk belongs to.
+    pub sample: String,
+    /// The index of the haplotype of the sample the walk belongs to.
+    pub haplotype: usize,
+    /// The name of the sequence the walk spells, e.g. a chromosome or contig.
+    pub sequence_name: String,
+}
+
 /// Read a bigraph in gfa format from a file.
 /// This method also returns the k-mer length given in the gfa file.
 pub fn read_gfa_as_bigraph_from_file<
@@ -164,7 +176,7 @@ pub fn read_gfa_as_bigraph_from_file<
     allow_messy_edges: bool,
 ) -> Result<(Graph, GfaReadFileProperties)> {
     read_gfa_as_bigraph(
-        BufReader::new(File::open(gfa_file)?),
+        maybe_decompress(BufReader::new(File::open(gfa_file)?))?,
         target_sequence_store,
         ignore_k,
         allow_messy_edges,
@@ -173,6 +185,7 @@ pub fn read_gfa_as_bigraph_from_file<
 
 /// Read a bigraph in gfa format from a `BufRead`.
 /// This method also returns the k-mer length given in the gfa file.
+/// `P` and `W` lines are ignored, use [`read_gfa_as_bigraph_with_paths`] to read them.
 pub fn read_gfa_as_bigraph<
     R: BufRead,
     AlphabetType: Alphabet,
@@ -192,10 +205,93 @@ pub fn read_gfa_as_bigraph<
     ignore_k: bool,
     allow_messy_edges: bool,
 ) -> Result<(Graph, GfaReadFileProperties)> {
+    read_gfa_as_bigraph_with_optional_paths(
+        gfa,
+        target_sequence_store,
+        ignore_k,
+        allow_messy_edges,
+        false,
+    )
+    .map(|(graph, properties, _, _)| (graph, properties))
+}
+
+/// Read a bigraph in gfa format from a `BufRead`, including the paths given by its `P` lines
+/// and the labeled walks given by its `W` lines.
+/// This method also returns the k-mer length given in the gfa file.
+///
+/// Each path is returned as a walk in the order of the `P` lines,
+/// where a step `name+` is the forward node of the segment and a step `name-` is its mirror node.
+/// Likewise, the walks are returned in the order of the `W` lines,
+/// where a step `>name` is the forward node of the segment and a step `<name` is its mirror node.
+/// The segments of paths and walks may be defined anywhere in the file.
+#[allow(clippy::type_complexity)]
+pub fn read_gfa_as_bigraph_with_paths<
+    R: BufRead,
+    AlphabetType: Alphabet,
+    GenomeSequenceStoreHandle: Clone,
+    GenomeSequenceStoreRef: GenomeSequence<AlphabetType, GenomeSequenceStoreRef> + Debug + ?Sized,
+    GenomeSequenceStore: SequenceStore<
+        AlphabetType,
+        Handle = GenomeSequenceStoreHandle,
+        SequenceRef = GenomeSequenceStoreRef,
+    >,
+    NodeData: From<BidirectedGfaNodeData<GenomeSequenceStore::Handle, ()>>,
+    EdgeData: From<BidirectedGfaEdgeData<()>>,
+    Graph: DynamicBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    gfa: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    ignore_k: bool,
+    allow_messy_edges: bool,
+) -> Result<(
+    Graph,
+    GfaReadFileProperties,
+    Vec<VecNodeWalk<Graph>>,
+    Vec<(WalkLabel, VecNodeWalk<Graph>)>,
+)> {
+    read_gfa_as_bigraph_with_optional_paths(
+        gfa,
+        target_sequence_store,
+        ignore_k,
+        allow_messy_edges,
+        true,
+    )
+}
+
+/// Read a bigraph in gfa format, and if `read_paths` is true, also its paths and walks.
+/// Otherwise, `P` and `W` lines are skipped without being parsed, and the returned paths and walks are empty.
+#[allow(clippy::type_complexity)]
+fn read_gfa_as_bigraph_with_optional_paths<
+    R: BufRead,
+    AlphabetType: Alphabet,
+    GenomeSequenceStoreHandle: Clone,
+    GenomeSequenceStoreRef: GenomeSequence<AlphabetType, GenomeSequenceStoreRef> + Debug + ?Sized,
+    GenomeSequenceStore: SequenceStore<
+        AlphabetType,
+        Handle = GenomeSequenceStoreHandle,
+        SequenceRef = GenomeSequenceStoreRef,
+    >,
+    NodeData: From<BidirectedGfaNodeData<GenomeSequenceStore::Handle, ()>>,
+    EdgeData: From<BidirectedGfaEdgeData<()>>,
+    Graph: DynamicBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default,
+>(
+    gfa: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    ignore_k: bool,
+    allow_messy_edges: bool,
+    read_paths: bool,
+) -> Result<(
+    Graph,
+    GfaReadFileProperties,
+    Vec<VecNodeWalk<Graph>>,
+    Vec<(WalkLabel, VecNodeWalk<Graph>)>,
+)> {
     let mut graph = Graph::default();
     let mut k = usize::MAX;
     let mut header = None;
     let mut node_name_map = HashMap::new();
+    let mut path_lines = Vec::new();
+    let mut walk_lines = Vec::new();
 
     for line in gfa.lines() {
         let line = line?;
@@ -227,6 +323,7 @@ pub fn read_gfa_as_bigraph<
                     panic!("Genome sequence with node_name {node_name} is invalid: {error:?}")
                 });
             let sequence = target_sequence_store.get(&sequence_handle);
+            validate_gfa_segment_length(node_name, columns, sequence.len())?;
             debug_assert!(
                 sequence.len() >= k || ignore_k,
                 "Node {} has sequence '{:?}' of length {} (k = {})",
@@ -306,6 +403,10 @@ pub fn read_gfa_as_bigraph<
             } else {
                 return Err(GfaIoError::MissingNode.into());
             }
+        } else if read_paths && line.starts_with('P') {
+            path_lines.push(line);
+        } else if read_paths && line.starts_with('W') {
+            walk_lines.push(line);
         }
     }
 
@@ -313,7 +414,139 @@ pub fn read_gfa_as_bigraph<
         k = 0;
     }
 
-    Ok((graph, GfaReadFileProperties { k, header }))
+    let paths = path_lines
+        .iter()
+        .map(|line| parse_gfa_path_line::<Graph>(line, &node_name_map))
+        .collect::<Result<_>>()?;
+    let walks = walk_lines
+        .iter()
+        .map(|line| parse_gfa_walk_line::<Graph>(line, &node_name_map))
+        .collect::<Result<_>>()?;
+
+    Ok((graph, GfaReadFileProperties { k, header }, paths, walks))
+}
+
+/// Check that the `LN:i:` tag among the optional columns of an `S` line, if any, matches the length of the sequence.
+fn validate_gfa_segment_length<'a>(
+    id: &str,
+    tags: impl Iterator<Item = &'a str>,
+    actual: usize,
+) -> Result<()> {
+    for tag in tags {
+        if let Some(declared) = tag.strip_prefix("LN:i:") {
+            let declared = declared
+                .parse()
+                .map_err(|_| GfaIoError::MalformedSegmentLength {
+                    id: id.to_owned(),
+                    tag: tag.to_owned(),
+                })?;
+            if declared != actual {
+                return Err(GfaIoError::SegmentLengthMismatch {
+                    id: id.to_owned(),
+                    declared,
+                    actual,
+                }
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the steps of a `P` line into a walk.
+/// The node name map maps each segment name to its forward node, whose mirror node has the next index.
+fn parse_gfa_path_line<Graph: GraphBase>(
+    line: &str,
+    node_name_map: &HashMap<String, Graph::NodeIndex>,
+) -> Result<VecNodeWalk<Graph>> {
+    let steps = line
+        .split('\t')
+        .nth(2)
+        .ok_or_else(|| GfaIoError::MalformedPath {
+            step: line.to_owned(),
+        })?;
+
+    steps
+        .split(',')
+        .map(|step| {
+            let (segment, direction) = if let Some(segment) = step.strip_suffix('+') {
+                (segment, 0)
+            } else if let Some(segment) = step.strip_suffix('-') {
+                (segment, 1)
+            } else {
+                return Err(GfaIoError::MalformedPath {
+                    step: step.to_owned(),
+                }
+                .into());
+            };
+            if segment.is_empty() {
+                return Err(GfaIoError::MalformedPath {
+                    step: step.to_owned(),
+                }
+                .into());
+            }
+
+            resolve_gfa_step::<Graph>(segment, direction, node_name_map)
+        })
+        .collect()
+}
+
+/// Resolve a `W` line into its label and walk.
+fn parse_gfa_walk_line<Graph: GraphBase>(
+    line: &str,
+    node_name_map: &HashMap<String, Graph::NodeIndex>,
+) -> Result<(WalkLabel, VecNodeWalk<Graph>)> {
+    let malformed_walk = || GfaIoError::MalformedWalk {
+        line: line.to_owned(),
+    };
+    let columns: Vec<_> = line.split('\t').collect();
+    if columns.len() < 7 {
+        return Err(malformed_walk().into());
+    }
+
+    let label = WalkLabel {
+        sample: columns[1].to_owned(),
+        haplotype: columns[2].parse().map_err(|_| malformed_walk())?,
+        sequence_name: columns[3].to_owned(),
+    };
+
+    let steps = columns[6];
+    if !steps.starts_with(['>', '<']) {
+        return Err(malformed_walk().into());
+    }
+    let mut walk = Vec::new();
+    let mut rest = steps;
+    while let Some(orientation) = rest.chars().next() {
+        let direction = if orientation == '>' { 0 } else { 1 };
+        rest = &rest[1..];
+        let end = rest.find(['>', '<']).unwrap_or(rest.len());
+        let segment = &rest[..end];
+        if segment.is_empty() {
+            return Err(malformed_walk().into());
+        }
+        walk.push(resolve_gfa_step::<Graph>(
+            segment,
+            direction,
+            node_name_map,
+        )?);
+        rest = &rest[end..];
+    }
+
+    Ok((label, walk))
+}
+
+/// Returns the node of the given segment, which is the forward node if `direction` is zero and the mirror node if it is one.
+fn resolve_gfa_step<Graph: GraphBase>(
+    segment: &str,
+    direction: usize,
+    node_name_map: &HashMap<String, Graph::NodeIndex>,
+) -> Result<Graph::NodeIndex> {
+    let node = node_name_map
+        .get(segment)
+        .ok_or_else(|| GfaIoError::MissingPathSegment {
+            segment: segment.to_owned(),
+        })?;
+    Ok((node.as_usize() + direction).into())
 }
 
 /// Read an edge-centric bigraph in gfa format from a file.
@@ -341,7 +574,7 @@ pub fn read_gfa_as_edge_centric_bigraph_from_file<
     estimate_k: bool,
 ) -> Result<(Graph, GfaReadFileProperties)> {
     read_gfa_as_edge_centric_bigraph(
-        BufReader::new(File::open(gfa_file)?),
+        maybe_decompress(BufReader::new(File::open(gfa_file)?))?,
         target_sequence_store,
         estimate_k,
     )
@@ -447,7 +680,7 @@ pub fn read_gfa_as_edge_centric_bigraph<
             let edge_data: EdgeData = edge_data.into();
             let reverse_edge_data = edge_data.mirror();
 
-            debug_assert!(columns.next().is_none());
+            validate_gfa_segment_length(&node_index.to_string(), columns, sequence.len())?;
             debug_assert!(
                 sequence.len() >= k,
                 "Node {} has sequence '{:?}' of length {} (k = {})",
@@ -488,9 +721,14 @@ pub fn read_gfa_as_edge_centric_bigraph<
 
 #[cfg(test)]
 mod tests {
+    use crate::error::Error;
+    use crate::io::gfa::error::GfaIoError;
     use crate::io::gfa::{
-        read_gfa_as_edge_centric_bigraph, GfaReadFileProperties, PetGfaEdgeGraph,
+        read_gfa_as_bigraph, read_gfa_as_bigraph_with_paths, read_gfa_as_edge_centric_bigraph,
+        GfaReadFileProperties, PetGfaEdgeGraph, PetGfaGraph, WalkLabel,
     };
+    use bigraph::interface::static_bigraph::StaticBigraph;
+    use bigraph::traitgraph::interface::ImmutableGraphContainer;
     use compact_genome::implementation::{
         alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
     };
@@ -509,4 +747,171 @@ mod tests {
             .unwrap();
         debug_assert_eq!(k, 3);
     }
+
+    #[test]
+    fn test_read_gfa_paths() {
+        let gfa = "H\tKL:Z:3\n\
+            S\t1\tACGA\n\
+            S\t2\tGATT\n\
+            S\t3\tTTCC\n\
+            L\t1\t+\t2\t+\t2M\n\
+            L\t2\t+\t3\t+\t2M\n\
+            P\tpath\t1+,2+,3+\t2M,2M\n\
+            P\treverse\t3-,2-\t2M\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let (graph, _, paths, _): (PetGfaGraph<(), (), _>, _, _, _) =
+            read_gfa_as_bigraph_with_paths(
+                BufReader::new(gfa.as_bytes()),
+                &mut sequence_store,
+                false,
+                false,
+            )
+            .unwrap();
+        let nodes: Vec<_> = graph.node_indices().collect();
+
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0], vec![nodes[0], nodes[2], nodes[4]]);
+        assert_eq!(
+            paths[1],
+            vec![
+                graph.mirror_node(nodes[4]).unwrap(),
+                graph.mirror_node(nodes[2]).unwrap()
+            ]
+        );
+        for path in &paths {
+            for window in path.windows(2) {
+                assert!(graph.contains_edge_between(window[0], window[1]));
+            }
+        }
+
+        for (gfa, expected_step) in [
+            ("S\t1\tACGA\nP\tpath\t1+,1\t*\n", Some("1")),
+            ("S\t1\tACGA\nP\tpath\t1+,+\t*\n", Some("+")),
+            ("S\t1\tACGA\nP\tpath\t1+,2+\t*\n", None),
+        ] {
+            let result: crate::error::Result<(PetGfaGraph<(), (), _>, _, _, _)> =
+                read_gfa_as_bigraph_with_paths(
+                    BufReader::new(gfa.as_bytes()),
+                    &mut sequence_store,
+                    true,
+                    false,
+                );
+            match (result, expected_step) {
+                (Err(Error::GfaIoError(GfaIoError::MalformedPath { step })), Some(expected)) => {
+                    assert_eq!(step, expected)
+                }
+                (Err(Error::GfaIoError(GfaIoError::MissingPathSegment { segment })), None) => {
+                    assert_eq!(segment, "2")
+                }
+                (result, _) => panic!("unexpected result: {:?}", result.err()),
+            }
+
+            // Paths are only parsed on request.
+            let result: crate::error::Result<(PetGfaGraph<(), (), _>, _)> = read_gfa_as_bigraph(
+                BufReader::new(gfa.as_bytes()),
+                &mut sequence_store,
+                true,
+                false,
+            );
+            assert!(result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_read_gfa_walks() {
+        let gfa = "S\ts1\tACGA\n\
+            S\ts2\tGATT\n\
+            S\ts3\tTTCC\n\
+            L\ts1\t+\ts2\t+\t2M\n\
+            L\ts2\t+\ts3\t+\t2M\n\
+            W\tHG002\t1\tchr1\t0\t8\t>s1>s2>s3\n\
+            W\tHG002\t2\tchr1\t0\t6\t<s3<s2\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let (graph, _, paths, walks): (PetGfaGraph<(), (), _>, _, _, _) =
+            read_gfa_as_bigraph_with_paths(
+                BufReader::new(gfa.as_bytes()),
+                &mut sequence_store,
+                true,
+                false,
+            )
+            .unwrap();
+        let nodes: Vec<_> = graph.node_indices().collect();
+
+        assert!(paths.is_empty());
+        assert_eq!(walks.len(), 2);
+        assert_eq!(
+            walks[0].0,
+            WalkLabel {
+                sample: "HG002".to_owned(),
+                haplotype: 1,
+                sequence_name: "chr1".to_owned(),
+            }
+        );
+        assert_eq!(walks[0].1, vec![nodes[0], nodes[2], nodes[4]]);
+        assert_eq!(walks[1].0.haplotype, 2);
+        assert_eq!(walks[1].1, vec![nodes[5], nodes[3]]);
+
+        for gfa in [
+            "S\ts1\tACGA\nW\tHG002\t1\tchr1\t0\t4\ts1\n",
+            "S\ts1\tACGA\nW\tHG002\tx\tchr1\t0\t4\t>s1\n",
+            "S\ts1\tACGA\nW\tHG002\t1\tchr1\t0\t4\t>s1<\n",
+            "S\ts1\tACGA\nW\tHG002\t1\tchr1\n",
+        ] {
+            let result: crate::error::Result<(PetGfaGraph<(), (), _>, _, _, _)> =
+                read_gfa_as_bigraph_with_paths(
+                    BufReader::new(gfa.as_bytes()),
+                    &mut sequence_store,
+                    true,
+                    false,
+                );
+            assert!(matches!(
+                result,
+                Err(Error::GfaIoError(GfaIoError::MalformedWalk { .. }))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_read_gfa_segment_length() {
+        let gfa = "H\tKL:Z:3\nS\t1\tACGA\tLN:i:4\nS\t2\tTCGT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<(PetGfaGraph<(), (), _>, _, _, _)> =
+            read_gfa_as_bigraph_with_paths(
+                BufReader::new(gfa.as_bytes()),
+                &mut sequence_store,
+                false,
+                false,
+            );
+        assert!(result.is_ok());
+
+        let gfa = "H\tKL:Z:3\nS\t1\tACGA\tLN:i:5\nS\t2\tTCGT\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<(PetGfaGraph<(), (), _>, _, _, _)> =
+            read_gfa_as_bigraph_with_paths(
+                BufReader::new(gfa.as_bytes()),
+                &mut sequence_store,
+                false,
+                false,
+            );
+        assert!(matches!(
+            result,
+            Err(Error::GfaIoError(GfaIoError::SegmentLengthMismatch {
+                declared: 5,
+                actual: 4,
+                ..
+            }))
+        ));
+
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let result: crate::error::Result<(PetGfaEdgeGraph<(), (), _>, _)> =
+            read_gfa_as_edge_centric_bigraph(
+                BufReader::new(gfa.as_bytes()),
+                &mut sequence_store,
+                false,
+            );
+        assert!(matches!(
+            result,
+            Err(Error::GfaIoError(GfaIoError::SegmentLengthMismatch { .. }))
+        ));
+    }
 }
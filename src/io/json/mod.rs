@@ -0,0 +1,141 @@
+use crate::io::SequenceData;
+use bigraph::interface::static_bigraph::StaticNodeCentricBigraph;
+use bigraph::traitgraph::index::GraphIndex;
+use bigraph::traitgraph::interface::Edge;
+use compact_genome::implementation::bit_vec_sequence::BitVectorGenome;
+use compact_genome::interface::alphabet::Alphabet;
+use compact_genome::interface::sequence::GenomeSequence;
+use compact_genome::interface::sequence_store::SequenceStore;
+use serde::Serialize;
+
+/// A genome graph as written by [`write_graph_as_json`].
+#[derive(Debug, Clone, Serialize)]
+struct JsonGraph {
+    nodes: Vec<JsonNode>,
+    edges: Vec<JsonEdge>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct JsonNode {
+    id: usize,
+    length: usize,
+    sequence: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct JsonEdge {
+    from: usize,
+    to: usize,
+    from_side: &'static str,
+    to_side: &'static str,
+}
+
+/// Write a node-centric genome graph as JSON node and edge lists, e.g. for web-based graph viewers.
+///
+/// The output has the form `{"nodes": [{"id", "length", "sequence"}], "edges": [{"from", "to", "from_side", "to_side"}]}`.
+/// Only the node with the lower index of each mirror pair is written, and its index is used as id.
+/// Each edge is written once together with its mirror, where the sides are `+` for the written node and `-` for its mirror,
+/// like the orientations of links in GFA.
+pub fn write_graph_as_json<
+    W: std::io::Write,
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+    NodeData: SequenceData<AlphabetType, GenomeSequenceStore>,
+    Graph: StaticNodeCentricBigraph<NodeData = NodeData>,
+>(
+    graph: &Graph,
+    source_sequence_store: &GenomeSequenceStore,
+    writer: W,
+) -> crate::error::Result<()> {
+    // Returns the representative of the mirror pair of the node and the side of the node.
+    let representative = |node: Graph::NodeIndex| match graph.mirror_node(node) {
+        Some(mirror_node) if mirror_node.as_usize() < node.as_usize() => (mirror_node, "-"),
+        _ => (node, "+"),
+    };
+
+    let mut nodes = Vec::new();
+    for node in graph.node_indices() {
+        if representative(node).0 != node {
+            continue;
+        }
+
+        let sequence: BitVectorGenome<AlphabetType> =
+            graph.node_data(node).sequence_owned(source_sequence_store);
+        nodes.push(JsonNode {
+            id: node.as_usize(),
+            length: sequence.len(),
+            sequence: String::from_utf8(sequence.clone_as_vec())
+                .expect("sequences consist of ASCII characters"),
+        });
+    }
+
+    let mut edges = Vec::new();
+    for edge in graph.edge_indices() {
+        if graph
+            .mirror_edge_node_centric(edge)
+            .is_some_and(|mirror_edge| mirror_edge.as_usize() < edge.as_usize())
+        {
+            continue;
+        }
+
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+        let (from, from_side) = representative(from_node);
+        let (to, to_side) = representative(to_node);
+        edges.push(JsonEdge {
+            from: from.as_usize(),
+            to: to.as_usize(),
+            from_side,
+            to_side,
+        });
+    }
+
+    serde_json::to_writer(writer, &JsonGraph { nodes, edges }).map_err(std::io::Error::from)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::bcalm2::read_bigraph_from_bcalm2_as_node_centric;
+    use crate::io::json::write_graph_as_json;
+    use crate::types::PetBCalm2NodeGraph;
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use std::io::BufReader;
+
+    #[test]
+    fn test_write_graph_as_json() {
+        let test_file: &'static [u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            GGTCTCGGGTAAGT\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ATGATG\n";
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let graph: PetBCalm2NodeGraph<_> = read_bigraph_from_bcalm2_as_node_centric(
+            BufReader::new(test_file),
+            &mut sequence_store,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        write_graph_as_json(&graph, &sequence_store, &mut output).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        let nodes = json["nodes"].as_array().unwrap();
+        assert_eq!(nodes.len(), 3);
+        // The ids are the node indices, and the forward nodes come before all mirror nodes.
+        assert_eq!(nodes[1]["id"], 1);
+        assert_eq!(nodes[1]["length"], 14);
+        assert_eq!(nodes[1]["sequence"], "GGTCTCGGGTAAGT");
+
+        let edges = json["edges"].as_array().unwrap();
+        assert_eq!(edges.len(), 2);
+        assert!(edges.contains(&serde_json::json!({
+            "from": 0,
+            "to": 1,
+            "from_side": "+",
+            "to_side": "-",
+        })));
+    }
+}
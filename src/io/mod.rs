@@ -1,3 +1,8 @@
+use crate::io::bcalm2::{read_bigraph_from_bcalm2_as_edge_centric, PlainBCalm2NodeData};
+use crate::io::gfa::{read_gfa_as_edge_centric_bigraph, BidirectedGfaNodeData};
+use bigraph::interface::dynamic_bigraph::DynamicEdgeCentricBigraph;
+use bigraph::interface::BidirectedData;
+use bigraph::traitgraph::interface::GraphBase;
 use compact_genome::implementation::bit_vec_sequence_store::{
     BitVectorSequenceStore, BitVectorSequenceStoreHandle,
 };
@@ -7,16 +12,210 @@ use compact_genome::implementation::vec_sequence_store::{
 use compact_genome::interface::alphabet::Alphabet;
 use compact_genome::interface::sequence::{GenomeSequence, OwnedGenomeSequence};
 use compact_genome::interface::sequence_store::SequenceStore;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::io::{BufRead, Read};
 
 /// A module providing types and functions for IO in the bcalm2 fasta format.
 pub mod bcalm2;
+/// A module providing a writer for the blocked gzip format (BGZF) as written by `bgzip`.
+#[cfg(feature = "bgzip")]
+pub mod bgzf;
 /// A module providing functions to read and write walks in a de Bruijn graph as fasta.
 pub mod fasta;
+/// A module providing functions to write walks in a genome graph as paths of the graph alignment format (gaf).
+pub mod gaf;
 /// A module providing types and functions for IO in gfa format.
 pub mod gfa;
+/// A module providing functions to export genome graphs as JSON for visualization.
+#[cfg(feature = "json")]
+pub mod json;
 /// A module providing types and functions for IO in the wtdbg2 graph and contig formats.
 pub mod wtdbg2;
 
+/// Wrap a reader into a gzip decoder if its content starts with the gzip magic bytes `0x1f 0x8b`,
+/// and otherwise return it unchanged.
+///
+/// All `_from_file` readers pass their input through this function, so they transparently handle gzip-compressed files.
+/// Decoding requires the `gzip` feature, which is enabled by default.
+/// Without it, gzip-compressed input results in an error of kind [`std::io::ErrorKind::Unsupported`].
+pub fn maybe_decompress<'a, R: BufRead + 'a>(
+    mut reader: R,
+) -> std::io::Result<Box<dyn BufRead + 'a>> {
+    if !reader.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        return Ok(Box::new(reader));
+    }
+
+    #[cfg(feature = "gzip")]
+    {
+        Ok(Box::new(std::io::BufReader::new(
+            flate2::bufread::MultiGzDecoder::new(reader),
+        )))
+    }
+    #[cfg(not(feature = "gzip"))]
+    {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "reading gzip-compressed input requires the gzip feature",
+        ))
+    }
+}
+
+/// A file format of a genome graph, as detected by [`detect_format`].
+///
+/// Only [`BCalm2`](GraphFormat::BCalm2) and [`Gfa`](GraphFormat::Gfa) can be read by [`read_bigraph_from_any_format`],
+/// the other formats are detected, but not dispatched to a reader.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GraphFormat {
+    /// The bcalm2 fasta format.
+    BCalm2,
+    /// The graphical fragment assembly format.
+    Gfa,
+    /// The .dot format as output by wtdbg2.
+    /// Not supported by [`read_bigraph_from_any_format`], since it contains no sequences.
+    Wtdbg2Dot,
+    /// The fastg format as output e.g. by SPAdes.
+    /// Not supported by [`read_bigraph_from_any_format`], since this crate has no fastg reader.
+    Fastg,
+}
+
+/// Detect the format of a genome graph from the first line of the given reader.
+///
+/// The reader is not advanced, such that it can be passed to the reader of the detected format afterwards.
+/// Only the bytes that are currently buffered are examined, which is usually much more than the first line.
+/// Fasta files are detected as bcalm2 only if their first header contains bcalm2 tags.
+pub fn detect_format<R: BufRead>(reader: &mut R) -> crate::error::Result<GraphFormat> {
+    let buffer = reader.fill_buf()?;
+    let first_line = buffer.split(|&byte| byte == b'\n').next().unwrap_or(buffer);
+
+    let format = if first_line.starts_with(b"digraph") {
+        Some(GraphFormat::Wtdbg2Dot)
+    } else if [b"H\t", b"S\t", b"L\t"]
+        .iter()
+        .any(|prefix| first_line.starts_with(*prefix))
+    {
+        Some(GraphFormat::Gfa)
+    } else if first_line.starts_with(b">EDGE_") {
+        Some(GraphFormat::Fastg)
+    } else if first_line.starts_with(b">") {
+        String::from_utf8_lossy(first_line)
+            .split_whitespace()
+            .skip(1)
+            .any(|tag| {
+                ["LN:i:", "KC:i:", "km:f:", "KM:f:", "L:"]
+                    .iter()
+                    .any(|prefix| tag.starts_with(prefix))
+            })
+            .then_some(GraphFormat::BCalm2)
+    } else {
+        None
+    };
+
+    format.ok_or_else(|| crate::error::Error::UnknownGraphFormat {
+        start: String::from_utf8_lossy(first_line).into_owned(),
+    })
+}
+
+/// Read an edge-centric genome graph in bcalm2 or gfa format, detecting which of the two with [`detect_format`].
+///
+/// Bcalm2 files are read with the given `kmer_size`, while gfa files are read with the k-mer size from their header.
+/// Only these two formats are supported, since wtdbg2 .dot files contain no sequences and fastg files have no reader.
+/// All other detected formats result in an [`UnsupportedGraphFormat`](crate::error::Error::UnsupportedGraphFormat) error.
+pub fn read_bigraph_from_any_format<
+    R: BufRead,
+    AlphabetType: Alphabet + Hash + Eq + Clone + 'static,
+    GenomeSequenceStoreRef: GenomeSequence<AlphabetType, GenomeSequenceStoreRef> + Debug + ?Sized,
+    GenomeSequenceStore: SequenceStore<AlphabetType, SequenceRef = GenomeSequenceStoreRef>,
+    NodeData: Default + Clone,
+    EdgeData: From<PlainBCalm2NodeData<GenomeSequenceStore::Handle>>
+        + From<BidirectedGfaNodeData<GenomeSequenceStore::Handle, ()>>
+        + Default
+        + Clone
+        + Eq
+        + BidirectedData,
+    Graph: DynamicEdgeCentricBigraph<NodeData = NodeData, EdgeData = EdgeData> + Default + Debug,
+>(
+    mut reader: R,
+    target_sequence_store: &mut GenomeSequenceStore,
+    kmer_size: usize,
+) -> crate::error::Result<Graph>
+where
+    <Graph as GraphBase>::NodeIndex: Clone,
+    GenomeSequenceStore::Handle: Clone + Eq,
+{
+    match detect_format(&mut reader)? {
+        GraphFormat::BCalm2 => {
+            read_bigraph_from_bcalm2_as_edge_centric(reader, target_sequence_store, kmer_size)
+        }
+        GraphFormat::Gfa => read_gfa_as_edge_centric_bigraph(reader, target_sequence_store, false)
+            .map(|(graph, _)| graph),
+        format @ (GraphFormat::Wtdbg2Dot | GraphFormat::Fastg) => {
+            Err(crate::error::Error::UnsupportedGraphFormat { format })
+        }
+    }
+}
+
+/// A reader that skips all lines starting with one of a set of comment prefixes.
+///
+/// This allows to parse hand-edited files with interspersed comment lines using parsers that do not support comments.
+pub struct CommentFilteringReader<R> {
+    reader: R,
+    comment_prefixes: Vec<Vec<u8>>,
+    line: Vec<u8>,
+    position: usize,
+}
+
+impl<R> CommentFilteringReader<R> {
+    /// Wraps the given reader such that lines starting with one of the given prefixes are skipped.
+    pub fn new(reader: R, comment_prefixes: &[&str]) -> Self {
+        Self {
+            reader,
+            comment_prefixes: comment_prefixes
+                .iter()
+                .map(|prefix| prefix.as_bytes().to_vec())
+                .collect(),
+            line: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl<R: BufRead> BufRead for CommentFilteringReader<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        while self.position >= self.line.len() {
+            self.line.clear();
+            self.position = 0;
+
+            if self.reader.read_until(b'\n', &mut self.line)? == 0 {
+                break;
+            }
+            if self
+                .comment_prefixes
+                .iter()
+                .any(|prefix| self.line.starts_with(prefix))
+            {
+                self.line.clear();
+            }
+        }
+
+        Ok(&self.line[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.line.len());
+    }
+}
+
+impl<R: BufRead> Read for CommentFilteringReader<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let amount = available.len().min(buffer.len());
+        buffer[..amount].copy_from_slice(&available[..amount]);
+        self.consume(amount);
+        Ok(amount)
+    }
+}
+
 /// Node or edge data of a genome graph that has an associated sequence.
 pub trait SequenceData<AlphabetType: Alphabet, GenomeSequenceStore: SequenceStore<AlphabetType>> {
     /// Returns the handle of the sequence stored in this type.
@@ -39,6 +238,89 @@ pub trait SequenceData<AlphabetType: Alphabet, GenomeSequenceStore: SequenceStor
     ) -> ResultSequence;
 }
 
+/// Node or edge data that consists of nothing but a sequence handle and an orientation.
+///
+/// This allows to use the sequence-based IO functions without constructing format-specific node data.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct RawSequenceData<Handle> {
+    /// The handle of the sequence in the sequence store.
+    pub sequence_handle: Handle,
+    /// If true, then the sequence is used as is, otherwise its reverse complement is used.
+    pub forwards: bool,
+}
+
+impl<Handle> RawSequenceData<Handle> {
+    /// Creates a new instance with the given handle in forwards orientation.
+    pub fn new(sequence_handle: Handle) -> Self {
+        Self {
+            sequence_handle,
+            forwards: true,
+        }
+    }
+}
+
+impl<Handle> From<PlainBCalm2NodeData<Handle>> for RawSequenceData<Handle> {
+    fn from(data: PlainBCalm2NodeData<Handle>) -> Self {
+        Self {
+            sequence_handle: data.sequence_handle,
+            forwards: data.forwards,
+        }
+    }
+}
+
+impl<Handle, Data> From<BidirectedGfaNodeData<Handle, Data>> for RawSequenceData<Handle> {
+    fn from(data: BidirectedGfaNodeData<Handle, Data>) -> Self {
+        Self {
+            sequence_handle: data.sequence_handle,
+            forwards: data.forward,
+        }
+    }
+}
+
+impl<Handle: Clone> BidirectedData for RawSequenceData<Handle> {
+    fn mirror(&self) -> Self {
+        let mut result = self.clone();
+        result.forwards = !result.forwards;
+        result
+    }
+}
+
+impl<AlphabetType: Alphabet, GenomeSequenceStore: SequenceStore<AlphabetType>>
+    SequenceData<AlphabetType, GenomeSequenceStore>
+    for RawSequenceData<GenomeSequenceStore::Handle>
+{
+    fn sequence_handle(&self) -> &GenomeSequenceStore::Handle {
+        &self.sequence_handle
+    }
+
+    fn sequence_ref<'this: 'result, 'store: 'result, 'result>(
+        &'this self,
+        source_sequence_store: &'store GenomeSequenceStore,
+    ) -> Option<&'result <GenomeSequenceStore as SequenceStore<AlphabetType>>::SequenceRef> {
+        if self.forwards {
+            Some(source_sequence_store.get(&self.sequence_handle))
+        } else {
+            None
+        }
+    }
+
+    fn sequence_owned<
+        ResultSequence: OwnedGenomeSequence<AlphabetType, ResultSubsequence>,
+        ResultSubsequence: GenomeSequence<AlphabetType, ResultSubsequence> + ?Sized,
+    >(
+        &self,
+        source_sequence_store: &GenomeSequenceStore,
+    ) -> ResultSequence {
+        if self.forwards {
+            source_sequence_store.get(&self.sequence_handle).convert()
+        } else {
+            source_sequence_store
+                .get(&self.sequence_handle)
+                .convert_with_reverse_complement()
+        }
+    }
+}
+
 impl<AlphabetType: Alphabet + 'static>
     SequenceData<AlphabetType, BitVectorSequenceStore<AlphabetType>>
     for BitVectorSequenceStoreHandle<AlphabetType>
@@ -97,3 +379,115 @@ impl<AlphabetType: Alphabet + 'static> SequenceData<AlphabetType, VectorSequence
         source_sequence_store.get(self).convert()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::io::fasta::write_node_centric_walks_as_fasta;
+    use crate::io::{detect_format, read_bigraph_from_any_format, GraphFormat, RawSequenceData};
+    use bigraph::implementation::node_bigraph_wrapper::NodeBigraphWrapper;
+    use bigraph::interface::BidirectedData;
+    use bigraph::traitgraph::implementation::petgraph_impl::PetGraph;
+    use bigraph::traitgraph::interface::{ImmutableGraphContainer, MutableGraphContainer};
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use compact_genome::interface::sequence_store::SequenceStore;
+    use std::io::{BufReader, Read};
+
+    #[test]
+    fn test_write_raw_sequence_data_walks() {
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let mut graph = PetGraph::default();
+
+        let n0 = graph.add_node(RawSequenceData::new(
+            sequence_store.add_from_slice_u8(b"ACGT").unwrap(),
+        ));
+        let n1 = graph.add_node(RawSequenceData::new(
+            sequence_store.add_from_slice_u8(b"GTTA").unwrap(),
+        ));
+        // Spells TAGG.
+        let n2 = graph.add_node(
+            RawSequenceData::new(sequence_store.add_from_slice_u8(b"CCTA").unwrap()).mirror(),
+        );
+        graph.add_edge(n0, n1, ());
+        graph.add_edge(n1, n2, ());
+
+        let walks = vec![vec![n0, n1, n2], vec![n2]];
+        let mut output = Vec::new();
+        {
+            let mut writer = bio::io::fasta::Writer::new(&mut output);
+            write_node_centric_walks_as_fasta(&graph, &sequence_store, 3, &walks, &mut writer)
+                .unwrap();
+            writer.flush().unwrap();
+        }
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">0\nACGTTAGG\n>1\nTAGG\n"
+        );
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let cases: [(&[u8], GraphFormat); 6] = [
+            (b">0 LN:i:3 L:+:1:-\nAGT\n", GraphFormat::BCalm2),
+            (b"H\tVN:Z:1.0\tKL:Z:3\n", GraphFormat::Gfa),
+            (b"S\t0\tAGT\tLN:i:3\n", GraphFormat::Gfa),
+            (b"L\t0\t+\t1\t-\t2M\n", GraphFormat::Gfa),
+            (b"digraph {\nnode [shape=record]\n", GraphFormat::Wtdbg2Dot),
+            (
+                b">EDGE_1_length_3_cov_2.0:EDGE_2_length_4_cov_1.0;\nAGT\n",
+                GraphFormat::Fastg,
+            ),
+        ];
+        for (input, format) in cases {
+            let mut reader = BufReader::new(input);
+            assert_eq!(detect_format(&mut reader).unwrap(), format);
+            // The reader is not advanced.
+            let mut content = Vec::new();
+            reader.read_to_end(&mut content).unwrap();
+            assert_eq!(content, input);
+        }
+
+        for input in [b"".as_slice(), b">read1\nAGT\n", b"ACGT\n"] {
+            assert!(matches!(
+                detect_format(&mut BufReader::new(input)),
+                Err(crate::error::Error::UnknownGraphFormat { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_read_bigraph_from_any_format() {
+        type Graph<Handle> = NodeBigraphWrapper<PetGraph<(), RawSequenceData<Handle>>>;
+        let bcalm2: &[u8] = b">0 LN:i:3 KC:i:4 km:f:3.0 L:+:1:-\n\
+            AGT\n\
+            >1 LN:i:14 KC:i:2 km:f:3.2 L:+:0:- L:+:2:+\n\
+            AATCTCGGGTAAAC\n\
+            >2 LN:i:6 KC:i:15 km:f:2.2 L:-:1:-\n\
+            ACGAGG\n";
+        let gfa: &[u8] = b"H\tKL:Z:3\nS\t1\tACGA\nS\t2\tTCGT\n";
+
+        // Each unitig or segment becomes an edge and its mirror edge.
+        for (input, unitig_count) in [(bcalm2, 3), (gfa, 2)] {
+            let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+            let graph: Graph<_> =
+                read_bigraph_from_any_format(BufReader::new(input), &mut sequence_store, 3)
+                    .unwrap();
+            assert_eq!(graph.edge_count(), 2 * unitig_count);
+        }
+
+        let dot: &[u8] = b"digraph {\nnode [shape=record]\n}\n";
+        let fastg: &[u8] = b">EDGE_1_length_3_cov_2.0;\nAGT\n";
+        for (input, format) in [(dot, GraphFormat::Wtdbg2Dot), (fastg, GraphFormat::Fastg)] {
+            let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+            let result: crate::error::Result<Graph<_>> =
+                read_bigraph_from_any_format(BufReader::new(input), &mut sequence_store, 3);
+            assert!(matches!(
+                result,
+                Err(crate::error::Error::UnsupportedGraphFormat { format: unsupported })
+                    if unsupported == format
+            ));
+        }
+    }
+}
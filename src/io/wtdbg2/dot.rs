@@ -1,8 +1,18 @@
 use crate::bigraph::interface::dynamic_bigraph::DynamicBigraph;
 use crate::bigraph::traitgraph::traitsequence::interface::Sequence;
 use crate::error::Result;
-use bigraph::traitgraph::interface::StaticGraph;
+use crate::io::bcalm2::PlainBCalm2NodeData;
+use crate::io::maybe_decompress;
+use crate::types::{PetBCalm2NodeGraph, PetWtdbg2DotGraph};
+use bigraph::interface::static_bigraph::StaticBigraph;
+use bigraph::interface::BidirectedData;
+use bigraph::traitgraph::index::GraphIndex;
+use bigraph::traitgraph::interface::{
+    Edge, ImmutableGraphContainer, MutableGraphContainer, StaticGraph,
+};
 use bigraph::traitgraph::walks::{EdgeWalk, VecNodeWalk};
+use compact_genome::interface::alphabet::Alphabet;
+use compact_genome::interface::sequence_store::SequenceStore;
 use error::DotIoError;
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -37,7 +47,7 @@ pub fn read_graph_from_wtdbg2_dot_from_file<
 where
     <NodeData as FromStr>::Err: Debug,
 {
-    read_graph_from_wtdbg2_dot(BufReader::new(File::open(dot_file)?))
+    read_graph_from_wtdbg2_dot(maybe_decompress(BufReader::new(File::open(dot_file)?))?)
 }
 
 /// Read a bigraph in dot format from a `BufRead`.
@@ -187,6 +197,82 @@ where
     Ok(graph)
 }
 
+/// Convert a bigraph read from the .dot format into a node-centric bcalm2 graph.
+///
+/// Since .dot files do not contain sequences, the sequence of each node is looked up by its name
+/// (without orientation) in `sequence_handles`, and the sequence lengths are taken from `source_sequence_store`.
+/// The bcalm2 ids are assigned in the order of the forward nodes.
+/// If a node has no sequence, then a [`DotIoError::MissingSequence`] is returned.
+pub fn convert_wtdbg2_dot_graph_to_bcalm2_node_graph<
+    AlphabetType: Alphabet,
+    GenomeSequenceStore: SequenceStore<AlphabetType>,
+>(
+    graph: &PetWtdbg2DotGraph,
+    source_sequence_store: &GenomeSequenceStore,
+    sequence_handles: &HashMap<String, GenomeSequenceStore::Handle>,
+) -> Result<PetBCalm2NodeGraph<GenomeSequenceStore::Handle>>
+where
+    GenomeSequenceStore::Handle: Clone,
+{
+    let mut result = PetBCalm2NodeGraph::default();
+    let mut node_map = vec![None; graph.node_count()];
+
+    for node_id in graph.node_indices() {
+        if node_map[node_id.as_usize()].is_some() {
+            continue;
+        }
+        let mirror_node_id = graph
+            .mirror_node(node_id)
+            .expect("Dot graph node has no mirror.");
+
+        // The reader names the nodes of a mirror pair "<name> +" and "<name> -".
+        let (forward_node_id, backward_node_id) = if graph.node_data(node_id).ends_with('-') {
+            (mirror_node_id, node_id)
+        } else {
+            (node_id, mirror_node_id)
+        };
+        let forward_node_name = graph.node_data(forward_node_id);
+        let name = forward_node_name
+            .strip_suffix(" +")
+            .unwrap_or(forward_node_name);
+        let sequence_handle =
+            sequence_handles
+                .get(name)
+                .ok_or_else(|| DotIoError::MissingSequence {
+                    name: name.to_string(),
+                })?;
+
+        let forward_node_data = PlainBCalm2NodeData {
+            id: result.node_count() / 2,
+            sequence_handle: sequence_handle.clone(),
+            forwards: true,
+            length: Some(source_sequence_store.get(sequence_handle).len()),
+            total_abundance: None,
+            mean_abundance: None,
+            colors: None,
+            raw_description: None,
+            edges: Vec::new(),
+        };
+        let backward_node_data = forward_node_data.mirror();
+        let forward_result_node_id = result.add_node(forward_node_data);
+        let backward_result_node_id = result.add_node(backward_node_data);
+        result.set_mirror_nodes(forward_result_node_id, backward_result_node_id);
+        node_map[forward_node_id.as_usize()] = Some(forward_result_node_id);
+        node_map[backward_node_id.as_usize()] = Some(backward_result_node_id);
+    }
+
+    for edge_id in graph.edge_indices() {
+        let Edge { from_node, to_node } = graph.edge_endpoints(edge_id);
+        result.add_edge(
+            node_map[from_node.as_usize()].unwrap(),
+            node_map[to_node.as_usize()].unwrap(),
+            (),
+        );
+    }
+
+    Ok(result)
+}
+
 /// Write a list of contigs as lists of node ids to a file.
 /// The ids are accompanied by a + or - indicating their direction.
 pub fn write_dot_contigs_as_wtdbg2_node_ids_to_file<
@@ -236,3 +322,154 @@ pub fn write_dot_contigs_as_wtdbg2_node_ids<
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::io::bcalm2::write_node_centric_bigraph_to_bcalm2;
+    use crate::io::maybe_decompress;
+    use crate::io::wtdbg2::dot::error::DotIoError;
+    use crate::io::wtdbg2::dot::{
+        convert_wtdbg2_dot_graph_to_bcalm2_node_graph, read_graph_from_wtdbg2_dot,
+    };
+    use crate::types::PetWtdbg2DotGraph;
+    use bigraph::traitgraph::interface::{Edge, ImmutableGraphContainer};
+    use compact_genome::implementation::{
+        alphabets::dna_alphabet::DnaAlphabet, DefaultSequenceStore,
+    };
+    use compact_genome::interface::sequence_store::SequenceStore;
+    use std::collections::HashMap;
+    use std::io::BufReader;
+
+    const TEST_DOT: &[u8] = b"digraph {\n\
+        node [shape=record]\n\
+        N1 [label=\"{N1 0 | ACG}\"]\n\
+        N2 [label=\"{N2 0 | CGTT}\"]\n\
+        N1 -> N2 [label=\"++\"]\n\
+        N2 -> N1 [label=\"--\"]\n\
+        }\n";
+
+    #[test]
+    fn test_convert_dot_graph_to_bcalm2() {
+        let graph: PetWtdbg2DotGraph =
+            read_graph_from_wtdbg2_dot(BufReader::new(TEST_DOT)).unwrap();
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let mut sequence_handles = HashMap::new();
+        sequence_handles.insert(
+            "N1".to_string(),
+            sequence_store.add_from_slice_u8(b"ACG").unwrap(),
+        );
+        sequence_handles.insert(
+            "N2".to_string(),
+            sequence_store.add_from_slice_u8(b"CGTT").unwrap(),
+        );
+
+        let bcalm2_graph = convert_wtdbg2_dot_graph_to_bcalm2_node_graph(
+            &graph,
+            &sequence_store,
+            &sequence_handles,
+        )
+        .unwrap();
+        assert_eq!(bcalm2_graph.node_count(), 4);
+        assert_eq!(bcalm2_graph.edge_count(), 2);
+
+        let mut output = Vec::new();
+        write_node_centric_bigraph_to_bcalm2(
+            &bcalm2_graph,
+            &sequence_store,
+            bio::io::fasta::Writer::new(&mut output),
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">0 LN:i:3 L:+:1:+\nACG\n>1 LN:i:4 L:-:0:-\nCGTT\n"
+        );
+    }
+
+    #[test]
+    fn test_convert_dot_graph_to_bcalm2_missing_sequence() {
+        let graph: PetWtdbg2DotGraph =
+            read_graph_from_wtdbg2_dot(BufReader::new(TEST_DOT)).unwrap();
+        let mut sequence_store = DefaultSequenceStore::<DnaAlphabet>::default();
+        let mut sequence_handles = HashMap::new();
+        sequence_handles.insert(
+            "N1".to_string(),
+            sequence_store.add_from_slice_u8(b"ACG").unwrap(),
+        );
+
+        assert!(matches!(
+            convert_wtdbg2_dot_graph_to_bcalm2_node_graph(
+                &graph,
+                &sequence_store,
+                &sequence_handles,
+            ),
+            Err(crate::error::Error::DotIoError(DotIoError::MissingSequence { name })) if name == "N2"
+        ));
+    }
+
+    #[test]
+    fn test_read_dot_parallel_edges() {
+        let test_dot: &[u8] = b"digraph {\n\
+            node [shape=record]\n\
+            A [label=\"{A 0 | AC}\"]\n\
+            B [label=\"{B 0 | CG}\"]\n\
+            A -> B [label=\"++\"]\n\
+            A -> B [label=\"+-\"]\n\
+            }\n";
+        let graph: PetWtdbg2DotGraph =
+            read_graph_from_wtdbg2_dot(BufReader::new(test_dot)).unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+
+        let mut edges: Vec<_> = graph
+            .edge_indices()
+            .map(|edge_id| {
+                let Edge { from_node, to_node } = graph.edge_endpoints(edge_id);
+                (
+                    graph.node_data(from_node).as_str(),
+                    graph.node_data(to_node).as_str(),
+                )
+            })
+            .collect();
+        edges.sort_unstable();
+        assert_eq!(edges, vec![("A +", "B +"), ("A +", "B -")]);
+    }
+
+    #[test]
+    fn test_read_dot_maybe_decompress() {
+        let graph: PetWtdbg2DotGraph =
+            read_graph_from_wtdbg2_dot(maybe_decompress(BufReader::new(TEST_DOT)).unwrap())
+                .unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn test_read_gzipped_dot() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(TEST_DOT).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let graph: PetWtdbg2DotGraph = read_graph_from_wtdbg2_dot(
+            maybe_decompress(BufReader::new(compressed.as_slice())).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    #[cfg(not(feature = "gzip"))]
+    fn test_read_gzipped_dot_without_gzip_feature() {
+        let compressed: &[u8] = &[0x1f, 0x8b, 0x08, 0x00];
+        assert_eq!(
+            maybe_decompress(BufReader::new(compressed))
+                .err()
+                .unwrap()
+                .kind(),
+            std::io::ErrorKind::Unsupported
+        );
+    }
+}
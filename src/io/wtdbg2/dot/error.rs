@@ -10,6 +10,9 @@ pub enum DotIoError {
 
     #[error("duplicate node id: '{name}'")]
     DuplicateNodeId { name: String },
+
+    #[error("no sequence given for node: '{name}'")]
+    MissingSequence { name: String },
 }
 
 impl DotIoError {
@@ -7,4 +7,10 @@ pub enum Wtdbg2IoError {
 
     #[error("unknown line start: {line_start}")]
     UnknownLineStart { line_start: String },
+
+    #[error("malformed line: {line}")]
+    MalformedLine { line: String },
+
+    #[error("walk is empty")]
+    EmptyWalk,
 }
@@ -1,4 +1,5 @@
 use crate::error::Result;
+use crate::io::maybe_decompress;
 use bigraph::interface::dynamic_bigraph::DynamicBigraph;
 use bigraph::interface::BidirectedData;
 use bigraph::traitgraph::interface::{Edge, ImmutableGraphContainer, StaticGraph};
@@ -70,39 +71,76 @@ impl Default for PlainWtdbg2NodeData {
     }
 }
 
-impl<'a> From<&'a str> for PlainWtdbg2NodeData {
-    fn from(string: &'a str) -> Self {
-        let mut split = string.split('\t');
-        let id = &split.next().unwrap()[1..];
-        let closed = id.ends_with('*');
-        let id = if closed {
-            id[..id.len() - 1].parse()
-        } else {
-            id.parse()
+impl<'a> TryFrom<&'a str> for PlainWtdbg2NodeData {
+    type Error = crate::error::Error;
+
+    fn try_from(string: &'a str) -> Result<Self> {
+        Self::parse(string)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Wtdbg2ReadLocation {
+    type Error = crate::error::Error;
+
+    fn try_from(string: &'a str) -> Result<Self> {
+        Self::parse(string)
+    }
+}
+
+/// Parse a wtdbg2 direction, where `F` is forwards and `R` is reverse.
+fn parse_direction(direction: &str) -> Result<bool> {
+    match direction {
+        "F" => Ok(true),
+        "R" => Ok(false),
+        unknown => Err(Wtdbg2IoError::UnknownNodeDirection {
+            direction: unknown.to_string(),
         }
-        .unwrap();
-        split.next();
+        .into()),
+    }
+}
+
+impl PlainWtdbg2NodeData {
+    /// Parse a line of a .1.nodes file from wtdbg2.
+    pub fn parse(line: &str) -> Result<Self> {
+        let malformed_line = || Wtdbg2IoError::MalformedLine {
+            line: line.to_string(),
+        };
+        let mut split = line.split('\t');
+        let id = split.next().unwrap_or("");
+        let Some(id) = id.strip_prefix('N') else {
+            return Err(Wtdbg2IoError::UnknownLineStart {
+                line_start: id.chars().take(1).collect(),
+            }
+            .into());
+        };
+        let closed = id.ends_with('*');
+        let id = id
+            .strip_suffix('*')
+            .unwrap_or(id)
+            .parse()
+            .map_err(|_| malformed_line())?;
+        split.next().ok_or_else(malformed_line)?;
         let mut read_associations = Vec::new();
 
         for read_association in split {
             let mut split = read_association.rsplitn(4, '_');
-            let bucket_len = split.next().unwrap();
+            let bucket_len = split.next().ok_or_else(malformed_line)?;
             let exclamation_mark = bucket_len.ends_with('!');
             let star = bucket_len.ends_with('*');
             let bucket_len = if exclamation_mark || star {
-                bucket_len[..bucket_len.len() - 1].parse()
+                &bucket_len[..bucket_len.len() - 1]
             } else {
-                bucket_len.parse()
+                bucket_len
             }
-            .unwrap();
-            let bucket_offset = split.next().unwrap().parse().unwrap();
-            let direction = split.next().unwrap();
-            let direction = match direction {
-                "F" => true,
-                "R" => false,
-                unknown => panic!("Unknown direction: '{}'", unknown),
-            };
-            let read_id = split.next().unwrap().to_owned();
+            .parse()
+            .map_err(|_| malformed_line())?;
+            let bucket_offset = split
+                .next()
+                .ok_or_else(malformed_line)?
+                .parse()
+                .map_err(|_| malformed_line())?;
+            let direction = parse_direction(split.next().ok_or_else(malformed_line)?)?;
+            let read_id = split.next().ok_or_else(malformed_line)?.to_owned();
 
             read_associations.push(Wtdbg2NodeReadAssociation {
                 read_id,
@@ -116,32 +154,116 @@ impl<'a> From<&'a str> for PlainWtdbg2NodeData {
             })
         }
 
-        PlainWtdbg2NodeData {
+        Ok(PlainWtdbg2NodeData {
             index: id,
             forward: true,
             closed,
             read_associations,
-        }
+        })
     }
 }
 
-impl<'a> From<&'a str> for Wtdbg2ReadLocation {
-    fn from(string: &'a str) -> Self {
-        let mut split = string.split('_');
-        let direction = split.next().unwrap();
-        let direction = match direction {
-            "F" => true,
-            "R" => false,
-            unknown => panic!("Unknown direction: '{}'", unknown),
+impl Wtdbg2ReadLocation {
+    /// Parse a read location of the form `F_<bucket offset>_<bucket length>` or `R_<bucket offset>_<bucket length>`.
+    pub fn parse(string: &str) -> Result<Self> {
+        let malformed_line = || Wtdbg2IoError::MalformedLine {
+            line: string.to_string(),
         };
-        let bucket_offset = split.next().unwrap().parse().unwrap();
-        let bucket_len = split.next().unwrap().parse().unwrap();
-
-        Self {
+        let mut split = string.split('_');
+        let direction = parse_direction(split.next().ok_or_else(malformed_line)?)?;
+        let bucket_offset = split
+            .next()
+            .ok_or_else(malformed_line)?
+            .parse()
+            .map_err(|_| malformed_line())?;
+        let bucket_len = split
+            .next()
+            .ok_or_else(malformed_line)?
+            .parse()
+            .map_err(|_| malformed_line())?;
+
+        Ok(Self {
             direction,
             bucket_offset,
             bucket_len,
+        })
+    }
+}
+
+/// Parse a node index of the form `N<index>` as used in the dot file of wtdbg2.
+fn parse_dot_node_index(string: Option<&str>) -> Option<usize> {
+    string?.get(1..)?.parse().ok()
+}
+
+/// Parse a direction character of the label of an edge in the dot file of wtdbg2.
+fn parse_dot_direction(direction: char) -> Result<bool> {
+    match direction {
+        '+' => Ok(true),
+        '-' => Ok(false),
+        unknown => Err(Wtdbg2IoError::UnknownNodeDirection {
+            direction: unknown.to_string(),
         }
+        .into()),
+    }
+}
+
+/// Parse an edge line of the dot file of wtdbg2 of the form `N<from> -> N<to> [label="<from direction><to direction>..."]`.
+/// Returns the node indices and directions as `(from_index, from_forward, to_index, to_forward)`.
+fn parse_dot_edge_line(line: &str) -> Result<(usize, bool, usize, bool)> {
+    let malformed_line = || Wtdbg2IoError::MalformedLine {
+        line: line.to_string(),
+    };
+    let mut split = line.split(' ');
+    let from_index = parse_dot_node_index(split.next()).ok_or_else(malformed_line)?;
+    split.next();
+    let to_index = parse_dot_node_index(split.next()).ok_or_else(malformed_line)?;
+    let mut label = split
+        .next()
+        .and_then(|label| label.get(8..10))
+        .ok_or_else(malformed_line)?
+        .chars();
+    let from_forward = parse_dot_direction(label.next().ok_or_else(malformed_line)?)?;
+    let to_forward = parse_dot_direction(label.next().ok_or_else(malformed_line)?)?;
+
+    Ok((from_index, from_forward, to_index, to_forward))
+}
+
+/// A node on a read as given in a .1.reads file from wtdbg2.
+struct Wtdbg2ReadNode {
+    index: usize,
+    star: bool,
+    exclamation_mark: bool,
+    location: Wtdbg2ReadLocation,
+}
+
+impl Wtdbg2ReadNode {
+    /// Parse a node of a line of a .1.reads file from wtdbg2 of the form `N<index>[*|!]:<read location>`.
+    fn parse(string: &str) -> Result<Self> {
+        let malformed_line = || Wtdbg2IoError::MalformedLine {
+            line: string.to_string(),
+        };
+        let mut split = string.split(':');
+        let index = split
+            .next()
+            .and_then(|index| index.get(1..))
+            .ok_or_else(malformed_line)?;
+        let star = index.ends_with('*');
+        let exclamation_mark = index.ends_with('!');
+        let index = if star || exclamation_mark {
+            &index[..index.len() - 1]
+        } else {
+            index
+        }
+        .parse()
+        .map_err(|_| malformed_line())?;
+        let location = Wtdbg2ReadLocation::parse(split.next().ok_or_else(malformed_line)?)?;
+
+        Ok(Self {
+            index,
+            star,
+            exclamation_mark,
+            location,
+        })
     }
 }
 
@@ -305,9 +427,9 @@ pub fn read_graph_from_wtdbg2_from_files<
     dot_file: P3,
 ) -> Result<Graph> {
     read_graph_from_wtdbg2(
-        BufReader::new(File::open(nodes_file)?),
-        BufReader::new(File::open(reads_file)?),
-        BufReader::new(File::open(dot_file)?),
+        maybe_decompress(BufReader::new(File::open(nodes_file)?))?,
+        maybe_decompress(BufReader::new(File::open(reads_file)?))?,
+        maybe_decompress(BufReader::new(File::open(dot_file)?))?,
     )
 }
 
@@ -330,7 +452,7 @@ pub fn read_graph_from_wtdbg2<
     info!("Loading nodes");
     for line in nodes.lines() {
         let line = line?;
-        let forward_node_data = PlainWtdbg2NodeData::from(line.as_str());
+        let forward_node_data = PlainWtdbg2NodeData::parse(&line)?;
         let reverse_node = graph.add_node(forward_node_data.clone_reverse().into());
         let forward_node = graph.add_node(forward_node_data.clone().into());
         node_map.insert(forward_node_data.index, (reverse_node, forward_node));
@@ -345,36 +467,10 @@ pub fn read_graph_from_wtdbg2<
             continue;
         }
 
-        let mut split = line.split(' ');
-        let n1 = node_map
-            .get(&split.next().unwrap()[1..].parse().unwrap())
-            .unwrap();
-        split.next();
-        let n2 = node_map
-            .get(&split.next().unwrap()[1..].parse().unwrap())
-            .unwrap();
-        let label = &split.next().unwrap()[8..10];
-        #[expect(clippy::iter_nth_zero)]
-        let from_forward = match label.chars().nth(0).unwrap() {
-            '+' => true,
-            '-' => false,
-            unknown => {
-                return Err(Wtdbg2IoError::UnknownNodeDirection {
-                    direction: unknown.to_string(),
-                }
-                .into())
-            }
-        };
-        let to_forward = match label.chars().nth(1).unwrap() {
-            '+' => true,
-            '-' => false,
-            unknown => {
-                return Err(Wtdbg2IoError::UnknownNodeDirection {
-                    direction: unknown.to_string(),
-                }
-                .into())
-            }
-        };
+        let (n1, from_forward, n2, to_forward) = parse_dot_edge_line(&line)?;
+        let malformed_line = || Wtdbg2IoError::MalformedLine { line: line.clone() };
+        let n1 = node_map.get(&n1).ok_or_else(malformed_line)?;
+        let n2 = node_map.get(&n2).ok_or_else(malformed_line)?;
 
         let n1 = if from_forward { n1.1 } else { n1.0 };
         let n2 = if to_forward { n2.1 } else { n2.0 };
@@ -391,31 +487,27 @@ pub fn read_graph_from_wtdbg2<
     info!("Loading edge read associations");
     for line in reads.lines() {
         let line = line?;
+        let malformed_line = || Wtdbg2IoError::MalformedLine { line: line.clone() };
         let mut split = line.split('\t');
-        let read_id = split.next().unwrap().to_owned();
+        let read_id = split.next().ok_or_else(malformed_line)?.to_owned();
         split.next();
-        let node_association_amount: usize = split.next().unwrap().parse().unwrap();
+        let node_association_amount: usize = split
+            .next()
+            .ok_or_else(malformed_line)?
+            .parse()
+            .map_err(|_| malformed_line())?;
         if node_association_amount < 2 {
             continue;
         }
-        let nodes: Vec<_> = split.collect();
+        let nodes = split
+            .map(Wtdbg2ReadNode::parse)
+            .collect::<Result<Vec<_>>>()?;
 
         for (index, n1) in nodes.iter().enumerate() {
-            let mut n1_split = n1.split(':');
-            let n1_index = &n1_split.next().unwrap()[1..];
-            let n1_star = n1_index.ends_with('*');
-            let n1_exclamation_mark = n1_index.ends_with('!');
-            let n1_index = node_map
-                .get(
-                    &if n1_star || n1_exclamation_mark {
-                        n1_index[..n1_index.len() - 1].parse()
-                    } else {
-                        n1_index.parse()
-                    }
-                    .unwrap(),
-                )
-                .unwrap();
-            let n1_read_location = Wtdbg2ReadLocation::from(n1_split.next().unwrap());
+            let n1_star = n1.star;
+            let n1_exclamation_mark = n1.exclamation_mark;
+            let n1_index = node_map.get(&n1.index).ok_or_else(malformed_line)?;
+            let n1_read_location = &n1.location;
 
             let n1_node_index = if n1_read_location.direction {
                 n1_index.1
@@ -429,21 +521,10 @@ pub fn read_graph_from_wtdbg2<
             };
 
             for n2 in nodes.iter().skip(index + 1) {
-                let mut n2_split = n2.split(':');
-                let n2_index = &n2_split.next().unwrap()[1..];
-                let n2_star = n2_index.ends_with('*');
-                let n2_exclamation_mark = n2_index.ends_with('!');
-                let n2_index = node_map
-                    .get(
-                        &if n2_star || n2_exclamation_mark {
-                            n2_index[..n2_index.len() - 1].parse()
-                        } else {
-                            n2_index.parse()
-                        }
-                        .unwrap(),
-                    )
-                    .unwrap();
-                let n2_read_location = Wtdbg2ReadLocation::from(n2_split.next().unwrap());
+                let n2_star = n2.star;
+                let n2_exclamation_mark = n2.exclamation_mark;
+                let n2_index = node_map.get(&n2.index).ok_or_else(malformed_line)?;
+                let n2_read_location = &n2.location;
 
                 let n2_node_index = if n2_read_location.direction {
                     n2_index.1
@@ -901,14 +982,7 @@ pub fn convert_walks_to_wtdbg2_contigs<
             last_print_time = Instant::now();
         }
 
-        // Compute edge offsets on the walk.
-        // The length of an edge is the median length of its supporting read fragments.
-        let mut offsets = vec![0];
-
-        for &edge in walk.iter() {
-            offsets.push(offsets.last().unwrap() + graph.edge_data(edge).length() - 4);
-        }
-
+        let offsets = wtdbg2_walk_offsets(graph, walk);
         if offsets.last().unwrap() * 256 < 5000 || walk.len() < 2 {
             dropped_walks += 1;
             continue;
@@ -964,7 +1038,7 @@ pub fn convert_walks_to_wtdbg2_contigs<
 
 /// Read a .ctg.lay file into a RawWtdbg2Contigs struct.
 pub fn read_wtdbg2_contigs_from_file<P: AsRef<Path>>(input_file: P) -> Result<RawWtdbg2Contigs> {
-    read_wtdbg2_contigs(File::open(input_file)?)
+    read_wtdbg2_contigs(maybe_decompress(BufReader::new(File::open(input_file)?))?)
 }
 
 /// Read a .ctg.lay source into a RawWtdbg2Contigs struct.
@@ -1180,14 +1254,7 @@ pub fn write_contigs_to_wtdbg2<
             last_print_time = Instant::now();
         }
 
-        // Compute edge offsets on the walk.
-        // The length of an edge is the median length of its supporting read fragments.
-        let mut offsets = vec![0];
-
-        for &edge in walk.iter() {
-            offsets.push(offsets.last().unwrap() + graph.edge_data(edge).length() - 4);
-        }
-
+        let offsets = wtdbg2_walk_offsets(graph, *walk);
         if offsets.last().unwrap() * 256 < 5000 || walk.len() < 2 {
             dropped_walks += 1;
             continue;
@@ -1195,27 +1262,10 @@ pub fn write_contigs_to_wtdbg2<
             printed_walks += 1;
         }
 
-        writeln!(
-            output,
-            ">ctg{} nodes={} len={}",
-            walk_index + 1,
-            walk.len() + 1,
-            offsets.last().unwrap() * 256
-        )?;
+        write_wtdbg2_layout_header(output, walk_index, walk.len(), &offsets)?;
 
         for (&edge, offset) in walk.iter().zip(offsets.iter()) {
-            let Edge { from_node, to_node } = graph.edge_endpoints(edge);
-            let from_node_data = graph.node_data(from_node);
-            let to_node_data = graph.node_data(to_node);
-            writeln!(
-                output,
-                "E\t{}\tN{}\t{}\tN{}\t{}",
-                offset * 256,
-                from_node_data.index(),
-                if from_node_data.forward() { '+' } else { '-' },
-                to_node_data.index(),
-                if to_node_data.forward() { '+' } else { '-' }
-            )?;
+            write_wtdbg2_layout_edge(output, graph, edge, *offset)?;
 
             for read_association in graph.edge_data(edge).edge_read_associations() {
                 let offset = read_association.location.bucket_offset * 256;
@@ -1244,6 +1294,104 @@ pub fn write_contigs_to_wtdbg2<
     Ok(())
 }
 
+/// Compute the offsets in buckets of the edges of a walk, followed by the length of the walk in buckets.
+/// The length of an edge is the median length of its supporting read fragments.
+fn wtdbg2_walk_offsets<
+    EdgeData: Wtdbg2EdgeData,
+    Graph: ImmutableGraphContainer<EdgeData = EdgeData>,
+    Walk: EdgeWalk<Graph, Subwalk> + ?Sized,
+    Subwalk: EdgeWalk<Graph, Subwalk> + ?Sized,
+>(
+    graph: &Graph,
+    walk: &Walk,
+) -> Vec<usize> {
+    let mut offsets = vec![0];
+
+    for &edge in walk.iter() {
+        offsets.push(offsets.last().unwrap() + graph.edge_data(edge).length() - 4);
+    }
+
+    offsets
+}
+
+/// Write the header line of a contig in wtdbg2's .ctg.lay format.
+fn write_wtdbg2_layout_header(
+    output: &mut impl Write,
+    walk_index: usize,
+    walk_len: usize,
+    offsets: &[usize],
+) -> Result<()> {
+    writeln!(
+        output,
+        ">ctg{} nodes={} len={}",
+        walk_index + 1,
+        walk_len + 1,
+        offsets.last().unwrap() * 256
+    )?;
+    Ok(())
+}
+
+/// Write the line of an edge of a contig in wtdbg2's .ctg.lay format.
+fn write_wtdbg2_layout_edge<
+    NodeData: Wtdbg2NodeData,
+    Graph: ImmutableGraphContainer<NodeData = NodeData>,
+>(
+    output: &mut impl Write,
+    graph: &Graph,
+    edge: Graph::EdgeIndex,
+    offset: usize,
+) -> Result<()> {
+    let Edge { from_node, to_node } = graph.edge_endpoints(edge);
+    let from_node_data = graph.node_data(from_node);
+    let to_node_data = graph.node_data(to_node);
+    writeln!(
+        output,
+        "E\t{}\tN{}\t{}\tN{}\t{}",
+        offset * 256,
+        from_node_data.index(),
+        if from_node_data.forward() { '+' } else { '-' },
+        to_node_data.index(),
+        if to_node_data.forward() { '+' } else { '-' }
+    )?;
+    Ok(())
+}
+
+/// Write a list of walks in wtdbg2's .ctg.lay format.
+///
+/// In contrast to [`write_contigs_to_wtdbg2`], this does not require the reads,
+/// and hence writes only the header and edge lines of each contig, without the read sequences.
+/// The walks are written in the given order, and no walks are dropped.
+/// Empty walks result in an error.
+pub fn write_walks_as_wtdbg2_layout<
+    'ws,
+    W: Write,
+    NodeData: Wtdbg2NodeData,
+    EdgeData: Wtdbg2EdgeData,
+    Graph: ImmutableGraphContainer<NodeData = NodeData, EdgeData = EdgeData>,
+    Walk: 'ws + EdgeWalk<Graph, Subwalk>,
+    Subwalk: EdgeWalk<Graph, Subwalk> + ?Sized,
+    WalkSource: 'ws + IntoIterator<Item = &'ws Walk>,
+>(
+    graph: &Graph,
+    walks: WalkSource,
+    output: &mut W,
+) -> Result<()> {
+    for (walk_index, walk) in walks.into_iter().enumerate() {
+        if walk.is_empty() {
+            return Err(Wtdbg2IoError::EmptyWalk.into());
+        }
+
+        let offsets = wtdbg2_walk_offsets(graph, walk);
+        write_wtdbg2_layout_header(output, walk_index, walk.len(), &offsets)?;
+        for (&edge, offset) in walk.iter().zip(offsets.iter()) {
+            write_wtdbg2_layout_edge(output, graph, edge, *offset)?;
+        }
+    }
+
+    output.flush()?;
+    Ok(())
+}
+
 /// Write a list of contigs as lists of wtdbg2's node ids to a file.
 pub fn write_contigs_as_wtdbg2_node_ids_to_file<
     'ws,
@@ -1351,3 +1499,138 @@ pub fn build_wtdbg2_unitigs_graph<
 
     graph
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::io::wtdbg2::error::Wtdbg2IoError;
+    use crate::io::wtdbg2::{
+        read_graph_from_wtdbg2, write_walks_as_wtdbg2_layout, PlainWtdbg2NodeData, Wtdbg2EdgeData,
+    };
+    use crate::types::PetWtdbg2Graph;
+    use bigraph::interface::static_bigraph::StaticBigraph;
+    use bigraph::traitgraph::interface::ImmutableGraphContainer;
+
+    const TEST_NODES: &str = "N0\t2\tread1_F_0_3\tread2_R_5_3\n\
+        N1*\t1\tread1_F_3_2!\n";
+    const TEST_READS: &str = "read1\tF\t2\tN0:F_0_3\tN1:F_3_2\n";
+    const TEST_DOT: &str = "digraph {\n\
+        N0 -> N1 [label=\"++\"]\n\
+        }\n";
+
+    #[test]
+    fn test_read_graph_from_wtdbg2() {
+        let graph: PetWtdbg2Graph = read_graph_from_wtdbg2(
+            TEST_NODES.as_bytes(),
+            TEST_READS.as_bytes(),
+            TEST_DOT.as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(graph.node_count(), 4);
+        assert!(graph.verify_node_pairing());
+        let node = graph
+            .node_indices()
+            .find(|&node| graph.node_data(node).index == 1 && graph.node_data(node).forward)
+            .unwrap();
+        let node_data = graph.node_data(node);
+        assert!(node_data.closed);
+        assert_eq!(node_data.read_associations.len(), 1);
+        assert!(node_data.read_associations[0].exclamation_mark);
+        assert_eq!(node_data.read_associations[0].location.bucket_offset, 3);
+
+        assert_eq!(graph.edge_count(), 1);
+        let edge = graph.edge_indices().next().unwrap();
+        assert_eq!(graph.edge_data(edge).multiplicity(), 1);
+        assert_eq!(graph.edge_data(edge).read_associations[0].read_id, "read1");
+    }
+
+    #[test]
+    fn test_parse_malformed_wtdbg2_nodes() {
+        assert!(matches!(
+            PlainWtdbg2NodeData::parse("N0\t1\tread1_X_0_3"),
+            Err(crate::error::Error::Wtdbg2IoError(
+                Wtdbg2IoError::UnknownNodeDirection { direction }
+            )) if direction == "X"
+        ));
+        assert!(matches!(
+            PlainWtdbg2NodeData::parse("X0\t1\tread1_F_0_3"),
+            Err(crate::error::Error::Wtdbg2IoError(
+                Wtdbg2IoError::UnknownLineStart { line_start }
+            )) if line_start == "X"
+        ));
+        assert!(matches!(
+            PlainWtdbg2NodeData::parse("N0\t1\tread1_F_a_3"),
+            Err(crate::error::Error::Wtdbg2IoError(
+                Wtdbg2IoError::MalformedLine { .. }
+            ))
+        ));
+        assert!(matches!(
+            PlainWtdbg2NodeData::try_from("N0"),
+            Err(crate::error::Error::Wtdbg2IoError(
+                Wtdbg2IoError::MalformedLine { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_read_malformed_wtdbg2_lines() {
+        for (reads, dot) in [
+            // Missing label.
+            (TEST_READS, "digraph {\nN0 -> N1\n}\n"),
+            // Unparseable node index.
+            (TEST_READS, "digraph {\nN0 -> Nx [label=\"++\"]\n}\n"),
+            // Unknown node.
+            (TEST_READS, "digraph {\nN0 -> N7 [label=\"++\"]\n}\n"),
+            // Missing node association amount.
+            ("read1\tF\n", TEST_DOT),
+            // Missing read location.
+            ("read1\tF\t2\tN0\tN1:F_3_2\n", TEST_DOT),
+            // Unknown node.
+            ("read1\tF\t2\tN0:F_0_3\tN7:F_3_2\n", TEST_DOT),
+        ] {
+            let result: crate::error::Result<PetWtdbg2Graph> =
+                read_graph_from_wtdbg2(TEST_NODES.as_bytes(), reads.as_bytes(), dot.as_bytes());
+            assert!(
+                matches!(
+                    result,
+                    Err(crate::error::Error::Wtdbg2IoError(
+                        Wtdbg2IoError::MalformedLine { .. }
+                    ))
+                ),
+                "{reads:?} {dot:?}"
+            );
+        }
+
+        let result: crate::error::Result<PetWtdbg2Graph> = read_graph_from_wtdbg2(
+            TEST_NODES.as_bytes(),
+            TEST_READS.as_bytes(),
+            "digraph {\nN0 -> N1 [label=\"+x\"]\n}\n".as_bytes(),
+        );
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::Wtdbg2IoError(
+                Wtdbg2IoError::UnknownNodeDirection { direction }
+            )) if direction == "x"
+        ));
+    }
+
+    #[test]
+    fn test_write_walks_as_wtdbg2_layout() {
+        let graph: PetWtdbg2Graph = read_graph_from_wtdbg2(
+            TEST_NODES.as_bytes(),
+            TEST_READS.as_bytes(),
+            TEST_DOT.as_bytes(),
+        )
+        .unwrap();
+
+        // The single edge N0+ -> N1+ has length 5 buckets, which includes an overlap of 4 buckets.
+        let walks = vec![graph.edge_indices().collect::<Vec<_>>()];
+        let mut output = Vec::new();
+        write_walks_as_wtdbg2_layout(&graph, &walks, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some(">ctg1 nodes=2 len=256"));
+        assert_eq!(lines.next(), Some("E\t0\tN0\t+\tN1\t+"));
+        assert_eq!(lines.next(), None);
+    }
+}
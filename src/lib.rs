@@ -5,6 +5,8 @@
 //!
 //! Currently, the format for input and output is the [bcalm2 fasta format](https://github.com/GATB/bcalm).
 
+/// Contains algorithms operating on genome graphs.
+pub mod algorithms;
 /// Contains the error types used by this crate.
 pub mod error;
 /// A module providing types and functions for IO in a generic node-centric format.